@@ -0,0 +1,87 @@
+//! Receive address verification. A compromised coordinator can show an
+//! address it controls instead of ours, so the device re-derives its own
+//! receive and change chains and only calls an address ours if it finds it.
+
+use crate::{descriptor::format_path, error::VulcanError, psbt::Account, types::ScriptType};
+use bitcoin::{
+  util::{address::AddressType, bip32},
+  Address, Network,
+};
+use core::{fmt, str::FromStr};
+
+/// Addresses searched on each chain unless the user asks for more, the gap
+/// limit most wallets use.
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// Where an address was found in the account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressMatch {
+  pub change: bool,
+  pub index: u32,
+  /// Full derivation path of the address
+  pub path: bip32::DerivationPath,
+}
+
+impl fmt::Display for AddressMatch {
+  /// Formats the path as `m/84h/1h/0h/0/17`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "m/{}", format_path(&self.path))
+  }
+}
+
+/// Parses an address as it's scanned or typed: bare, or in a BIP21
+/// `bitcoin:` URI whose parameters are ignored.
+pub fn parse_address(text: &str, network: Network) -> Result<Address, VulcanError> {
+  let text = text.trim();
+  let text = match text.get(..8) {
+    Some(scheme) if scheme.eq_ignore_ascii_case("bitcoin:") => &text[8..],
+    _ => text,
+  };
+  let text = text.split('?').next().unwrap_or("");
+  let address = Address::from_str(text).map_err(|_| VulcanError::BadAddress)?;
+  if !address.is_valid_for_network(network) {
+    return Err(VulcanError::WrongNetwork);
+  }
+  return Ok(address);
+}
+
+/// The script type of the accounts that can receive to `address`. P2PKH
+/// addresses have none, the device only has segwit and taproot accounts.
+pub fn address_script_type(address: &Address) -> Option<ScriptType> {
+  return match address.address_type()? {
+    AddressType::P2sh => Some(ScriptType::NestedSegwit),
+    AddressType::P2wpkh | AddressType::P2wsh => Some(ScriptType::Segwit),
+    AddressType::P2tr => Some(ScriptType::Taproot),
+    AddressType::P2pkh => None,
+  };
+}
+
+/// Searches the first `gap` addresses of the account's receive and change
+/// chains for `address`, lowest index first.
+pub fn find_address<A: Account + ?Sized>(
+  account: &A,
+  address: &Address,
+  gap: u32,
+) -> Result<Option<AddressMatch>, VulcanError> {
+  if !address.is_valid_for_network(account.network()) {
+    return Err(VulcanError::WrongNetwork);
+  }
+  let script_pubkey = address.script_pubkey();
+
+  for index in 0..gap {
+    for change in [false, true].iter() {
+      if account.script_pubkey(*change, index)? == script_pubkey {
+        let path = account.path().extend([
+          bip32::ChildNumber::from_normal_idx(*change as u32)?,
+          bip32::ChildNumber::from_normal_idx(index)?,
+        ]);
+        return Ok(Some(AddressMatch {
+          change: *change,
+          index,
+          path,
+        }));
+      }
+    }
+  }
+  return Ok(None);
+}
@@ -0,0 +1,49 @@
+//! Standard base64 for message signatures and PSBT files. bitcoin only
+//! brings the `base64` crate with `std`, which the firmware doesn't have.
+
+use alloc::{string::String, vec::Vec};
+
+const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes with padding.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+  let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+  for chunk in bytes.chunks(3) {
+    let b = [
+      chunk[0],
+      *chunk.get(1).unwrap_or(&0),
+      *chunk.get(2).unwrap_or(&0),
+    ];
+    let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+    for i in 0..4 {
+      if i <= chunk.len() {
+        out.push(CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+      } else {
+        out.push('=');
+      }
+    }
+  }
+  return out;
+}
+
+/// Decodes padded or unpadded base64, `None` if it isn't base64.
+pub(crate) fn decode(s: &str) -> Option<Vec<u8>> {
+  let s = s.trim_end_matches('=');
+  let mut out = Vec::with_capacity(s.len() * 3 / 4);
+  let mut n: u32 = 0;
+  for (i, c) in s.bytes().enumerate() {
+    let value = CHARS.iter().position(|b| *b == c)?;
+    n = n << 6 | value as u32;
+    if i % 4 == 3 {
+      out.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8]);
+      n = 0;
+    }
+  }
+  match s.len() % 4 {
+    0 => {}
+    2 => out.push((n >> 4) as u8),
+    3 => out.extend_from_slice(&[(n >> 10) as u8, (n >> 2) as u8]),
+    _ => return None,
+  }
+  return Some(out);
+}
@@ -0,0 +1,121 @@
+//! BIP85 deterministic entropy: child seeds and keys derived from the master
+//! key, so one backup covers every wallet made from them.
+//! https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki
+
+use crate::error::VulcanError;
+use alloc::{
+  string::{String, ToString},
+  vec::Vec,
+};
+use bip39::{Language, Mnemonic};
+use bitcoin::{
+  hashes::{hex::ToHex, hmac, sha512, Hash, HashEngine},
+  secp256k1::{Secp256k1, SecretKey},
+  util::bip32,
+  PrivateKey,
+};
+
+const PURPOSE: u32 = 83696968;
+const HMAC_KEY: &[u8] = b"bip-entropy-from-k";
+
+/// The BIP85 applications the device supports.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Bip85Application {
+  /// English BIP39 mnemonic of 12, 18 or 24 words
+  Bip39(usize),
+  /// Compressed private key in wallet import format
+  Wif,
+  /// Root extended private key
+  Xprv,
+  /// 16 to 64 bytes of hex
+  Hex(usize),
+}
+
+impl Bip85Application {
+  /// Derives the application's child at `index` and formats it the way it's
+  /// written down or imported.
+  pub fn derive(&self, root: &bip32::ExtendedPrivKey, index: u32) -> Result<String, VulcanError> {
+    return Ok(match self {
+      Bip85Application::Bip39(words) => bip85_mnemonic(root, *words, index)?.to_string(),
+      Bip85Application::Wif => bip85_wif(root, index)?.to_wif(),
+      Bip85Application::Xprv => bip85_xprv(root, index)?.to_string(),
+      Bip85Application::Hex(len) => bip85_hex(root, *len, index)?.to_hex(),
+    });
+  }
+}
+
+/// The 64 bytes of entropy at `m/83696968'/path`, all levels hardened.
+pub fn derive_entropy(
+  root: &bip32::ExtendedPrivKey,
+  path: &[u32],
+) -> Result<[u8; 64], VulcanError> {
+  let secp = Secp256k1::new();
+  let mut children = Vec::with_capacity(path.len() + 1);
+  for index in core::iter::once(&PURPOSE).chain(path.iter()) {
+    children.push(bip32::ChildNumber::from_hardened_idx(*index)?);
+  }
+  let derived = root.derive_priv(&secp, &bip32::DerivationPath::from(children))?;
+
+  let mut engine = hmac::HmacEngine::<sha512::Hash>::new(HMAC_KEY);
+  engine.input(&derived.private_key.secret_bytes());
+  return Ok(hmac::Hmac::<sha512::Hash>::from_engine(engine).into_inner());
+}
+
+/// BIP39 application, `m/83696968'/39'/0'/{words}'/{index}'`. Only English
+/// (language 0') is built into the firmware.
+pub fn bip85_mnemonic(
+  root: &bip32::ExtendedPrivKey,
+  words: usize,
+  index: u32,
+) -> Result<Mnemonic, VulcanError> {
+  let len = match words {
+    12 => 16,
+    18 => 24,
+    24 => 32,
+    _ => return Err(VulcanError::BadWordCount(words)),
+  };
+  let entropy = derive_entropy(root, &[39, 0, words as u32, index])?;
+  return Ok(Mnemonic::from_entropy_in(
+    Language::English,
+    &entropy[..len],
+  )?);
+}
+
+/// HD-Seed WIF application, `m/83696968'/2'/{index}'`. The key is for the
+/// root's network.
+pub fn bip85_wif(root: &bip32::ExtendedPrivKey, index: u32) -> Result<PrivateKey, VulcanError> {
+  let entropy = derive_entropy(root, &[2, index])?;
+  let key = SecretKey::from_slice(&entropy[..32]).map_err(bip32::Error::Secp256k1)?;
+  return Ok(PrivateKey::new(key, root.network));
+}
+
+/// XPRV application, `m/83696968'/32'/{index}'`. The first half of the
+/// entropy is the chain code, the second half the private key.
+pub fn bip85_xprv(
+  root: &bip32::ExtendedPrivKey,
+  index: u32,
+) -> Result<bip32::ExtendedPrivKey, VulcanError> {
+  let entropy = derive_entropy(root, &[32, index])?;
+  let key = SecretKey::from_slice(&entropy[32..]).map_err(bip32::Error::Secp256k1)?;
+  return Ok(bip32::ExtendedPrivKey {
+    network: root.network,
+    depth: 0,
+    parent_fingerprint: bip32::Fingerprint::default(),
+    child_number: bip32::ChildNumber::from_normal_idx(0)?,
+    private_key: key,
+    chain_code: bip32::ChainCode::from(&entropy[..32]),
+  });
+}
+
+/// HEX application, `m/83696968'/128169'/{len}'/{index}'`, for 16 to 64 bytes.
+pub fn bip85_hex(
+  root: &bip32::ExtendedPrivKey,
+  len: usize,
+  index: u32,
+) -> Result<Vec<u8>, VulcanError> {
+  if !(16..=64).contains(&len) {
+    return Err(VulcanError::BadEntropyLength(len));
+  }
+  let entropy = derive_entropy(root, &[128169, len as u32, index])?;
+  return Ok(entropy[..len].to_vec());
+}
@@ -0,0 +1,375 @@
+//! codex32 (BIP-93): the BIP32 master seed as bech32 strings whose BCH
+//! checksum can be computed and checked by hand, optionally split into
+//! k-of-n shares with Shamir's secret sharing over GF(32).
+//! https://github.com/bitcoin/bips/blob/master/bip-0093.mediawiki
+//!
+//! A string is `ms1`, the threshold (`0` for a seed that isn't split), a
+//! four character identifier, the share index (`s` for the seed itself), the
+//! seed bits and the checksum.
+
+use crate::{error::VulcanError, slip39::RandomBytes};
+use alloc::{string::String, vec, vec::Vec};
+use bitcoin::util::bip32;
+use core::{fmt, str::FromStr};
+
+/// The bech32 characters, in the order of the values they encode
+pub const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+/// The `ms` human readable part and the separator
+const PREFIX: &str = "ms1";
+/// Indexes of new shares in the order they're made
+const SHARE_INDEXES: &[u8] = b"acdefghjklmnpqrtuvwxyz023456789";
+const SECRET_INDEX: char = 's';
+/// threshold, identifier and share index
+const HEADER_LEN: usize = 6;
+const CHECKSUM_LEN: usize = 13;
+const LONG_CHECKSUM_LEN: usize = 15;
+/// Data parts up to this length have the short checksum
+const MAX_SHORT_DATA: usize = 93;
+const MIN_LONG_DATA: usize = 96;
+const MAX_LONG_DATA: usize = 124;
+const MIN_SEED_LEN: usize = 16;
+const MAX_SEED_LEN: usize = 64;
+const MAX_THRESHOLD: u8 = 9;
+
+const GEN: [u128; 5] = [
+  0x19dc500ce73fde210,
+  0x1bfae00def77fe529,
+  0x1fbd920fffe7bee52,
+  0x1739640bdeee3fdad,
+  0x07729a039cfc75f5a,
+];
+const LONG_GEN: [u128; 5] = [
+  0x3d59d273535ea62d897,
+  0x7a9becb6361c6c51507,
+  0x543f9b7e6c38d8a2a0e,
+  0x0c577eaeccf1990d13c,
+  0x1887f74f8dc71b10651,
+];
+const MS32_CONST: u128 = 0x10ce0795c2fd1e62a;
+const MS32_LONG_CONST: u128 = 0x43381e570bf4798ab26;
+
+/// One codex32 string: the seed itself or one of its shares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Codex32Share {
+  /// Shares needed to recover the seed, 0 if the seed isn't split
+  pub threshold: u8,
+  /// Four characters shared by every share of a backup
+  pub identifier: String,
+  /// `s` for the seed itself
+  pub index: char,
+  /// 5 bit values of the seed or share bits
+  payload: Vec<u8>,
+}
+
+impl Codex32Share {
+  /// The seed as a single codex32 string, without sharing.
+  pub fn from_seed(seed: &[u8], identifier: &str) -> Result<Self, VulcanError> {
+    if seed.len() < MIN_SEED_LEN || seed.len() > MAX_SEED_LEN {
+      return Err(VulcanError::BadEntropyLength(seed.len()));
+    }
+    let identifier = identifier.to_ascii_lowercase();
+    if identifier.len() != 4 || !identifier.bytes().all(|c| CHARSET.contains(&c)) {
+      return Err(VulcanError::BadCodex32);
+    }
+    return Ok(Codex32Share {
+      threshold: 0,
+      identifier,
+      index: SECRET_INDEX,
+      payload: to_u5(seed),
+    });
+  }
+
+  /// The data part as 5 bit values, without the checksum.
+  fn data(&self) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HEADER_LEN + self.payload.len());
+    data.push(u5(char::from(b'0' + self.threshold)));
+    data.extend(self.identifier.chars().map(u5));
+    data.push(u5(self.index));
+    data.extend_from_slice(&self.payload);
+    return data;
+  }
+}
+
+impl fmt::Display for Codex32Share {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let data = self.data();
+    let checksum = create_checksum(&data);
+    write!(f, "{}", PREFIX)?;
+    for value in data.iter().chain(checksum.iter()) {
+      write!(f, "{}", CHARSET[*value as usize] as char)?;
+    }
+    Ok(())
+  }
+}
+
+impl FromStr for Codex32Share {
+  type Err = VulcanError;
+
+  /// Parses a codex32 string in either case, checking its checksum.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    let lower = s.to_ascii_lowercase();
+    if lower != s && s.to_ascii_uppercase() != s {
+      // mixed case
+      return Err(VulcanError::BadCodex32);
+    }
+    if !lower.starts_with(PREFIX) {
+      return Err(VulcanError::BadCodex32);
+    }
+    let data = lower[PREFIX.len()..]
+      .bytes()
+      .map(|c| CHARSET.iter().position(|x| *x == c).map(|v| v as u8))
+      .collect::<Option<Vec<u8>>>()
+      .ok_or(VulcanError::BadCodex32)?;
+    let checksum_len = match data.len() {
+      len if len <= MAX_SHORT_DATA => CHECKSUM_LEN,
+      len if (MIN_LONG_DATA..=MAX_LONG_DATA).contains(&len) => LONG_CHECKSUM_LEN,
+      _ => return Err(VulcanError::BadCodex32),
+    };
+    if data.len() < HEADER_LEN + checksum_len || !verify_checksum(&data) {
+      return Err(VulcanError::BadCodex32);
+    }
+
+    let header = &lower[PREFIX.len()..PREFIX.len() + HEADER_LEN];
+    let threshold = match header.as_bytes()[0] {
+      c @ b'0' | c @ b'2'..=b'9' => c - b'0',
+      _ => return Err(VulcanError::BadCodex32),
+    };
+    let index = header.as_bytes()[5] as char;
+    if threshold == 0 && index != SECRET_INDEX {
+      return Err(VulcanError::BadCodex32);
+    }
+    let payload = data[HEADER_LEN..data.len() - checksum_len].to_vec();
+    // at most 4 bits of padding
+    let len = payload.len() * 5 / 8;
+    if !(MIN_SEED_LEN..=MAX_SEED_LEN).contains(&len) || payload.len() * 5 - len * 8 > 4 {
+      return Err(VulcanError::BadCodex32);
+    }
+    return Ok(Codex32Share {
+      threshold,
+      identifier: String::from(&header[1..5]),
+      index,
+      payload,
+    });
+  }
+}
+
+/// The identifier BIP-93 suggests for a seed: the first 20 bits of its root
+/// fingerprint, so shares can be matched to the wallet by hand.
+pub fn codex32_identifier(fingerprint: &bip32::Fingerprint) -> String {
+  return to_u5(fingerprint.as_bytes())
+    .iter()
+    .take(4)
+    .map(|v| CHARSET[*v as usize] as char)
+    .collect();
+}
+
+/// Splits `seed` into `count` shares, any `threshold` of which recover it.
+/// The first `threshold - 1` shares are random, seeded with `random`, which
+/// should be at least 32 bytes from a real RNG. The rest are interpolated.
+pub fn codex32_split(
+  seed: &[u8],
+  identifier: &str,
+  threshold: u8,
+  count: u8,
+  random: &[u8],
+) -> Result<Vec<Codex32Share>, VulcanError> {
+  if !(2..=MAX_THRESHOLD).contains(&threshold)
+    || count < threshold
+    || count as usize > SHARE_INDEXES.len()
+  {
+    return Err(VulcanError::BadShamirGroups);
+  }
+  if random.len() < 32 {
+    return Err(VulcanError::BadEntropyLength(random.len()));
+  }
+  let mut secret = Codex32Share::from_seed(seed, identifier)?;
+  secret.threshold = threshold;
+
+  let mut random = RandomBytes::new(random);
+  let mut shares = Vec::with_capacity(count as usize);
+  for index in SHARE_INDEXES.iter().take(threshold as usize - 1) {
+    let payload = random
+      .bytes(secret.payload.len())
+      .iter()
+      .map(|byte| byte & 31)
+      .collect();
+    shares.push(Codex32Share {
+      index: *index as char,
+      payload,
+      ..secret.clone()
+    });
+  }
+
+  let mut basis: Vec<&Codex32Share> = shares.iter().collect();
+  basis.push(&secret);
+  let derived: Vec<Codex32Share> = SHARE_INDEXES[threshold as usize - 1..count as usize]
+    .iter()
+    .map(|index| interpolate(&basis, *index as char))
+    .collect();
+  shares.extend(derived);
+  return Ok(shares);
+}
+
+/// Recovers the seed from its codex32 string, or from `threshold` shares.
+/// Extra shares are ignored.
+pub fn codex32_combine(shares: &[Codex32Share]) -> Result<Vec<u8>, VulcanError> {
+  let first = shares.first().ok_or(VulcanError::InsufficientShares)?;
+  let mut distinct: Vec<&Codex32Share> = Vec::new();
+  for share in shares {
+    if share.threshold != first.threshold
+      || share.identifier != first.identifier
+      || share.payload.len() != first.payload.len()
+    {
+      return Err(VulcanError::ShareMismatch);
+    }
+    match distinct.iter().find(|other| other.index == share.index) {
+      Some(other) if other.payload != share.payload => return Err(VulcanError::ShareMismatch),
+      Some(_) => {}
+      None => distinct.push(share),
+    }
+  }
+
+  if let Some(secret) = distinct.iter().find(|share| share.index == SECRET_INDEX) {
+    return Ok(from_u5(&secret.payload));
+  }
+  if first.threshold == 0 || distinct.len() < first.threshold as usize {
+    return Err(VulcanError::InsufficientShares);
+  }
+  let secret = interpolate(&distinct[..first.threshold as usize], SECRET_INDEX);
+  return Ok(from_u5(&secret.payload));
+}
+
+/// The share at `index` of the polynomial through `shares`.
+fn interpolate(shares: &[&Codex32Share], index: char) -> Codex32Share {
+  let x = u5(index);
+  let xs: Vec<u8> = shares.iter().map(|share| u5(share.index)).collect();
+  let mut payload = vec![0u8; shares[0].payload.len()];
+  for (i, share) in shares.iter().enumerate() {
+    // Lagrange basis polynomial of share i at x
+    let mut weight = 1;
+    for (j, xj) in xs.iter().enumerate() {
+      if i != j {
+        weight = gf32_mul(weight, gf32_mul(x ^ xj, gf32_inv(xs[i] ^ xj)));
+      }
+    }
+    for (value, y) in payload.iter_mut().zip(share.payload.iter()) {
+      *value ^= gf32_mul(weight, *y);
+    }
+  }
+  return Codex32Share {
+    index,
+    payload,
+    ..shares[0].clone()
+  };
+}
+
+/// Multiplication in GF(32) modulo x^5 + x^3 + 1, elements are bech32 values.
+fn gf32_mul(a: u8, b: u8) -> u8 {
+  let mut a = a;
+  let mut result = 0;
+  for i in 0..5 {
+    if b >> i & 1 == 1 {
+      result ^= a;
+    }
+    a <<= 1;
+    if a & 32 != 0 {
+      a ^= 41;
+    }
+  }
+  return result;
+}
+
+fn gf32_inv(a: u8) -> u8 {
+  // a^30 = a^-1 since the multiplicative group has order 31
+  let mut result = 1;
+  for _ in 0..30 {
+    result = gf32_mul(result, a);
+  }
+  return result;
+}
+
+fn polymod(values: &[u8]) -> u128 {
+  let long = values.len() > MAX_SHORT_DATA;
+  let (generator, shift) = if long { (&LONG_GEN, 70) } else { (&GEN, 60) };
+  let mask = (1u128 << shift) - 1;
+  let mut residue: u128 = 0x23181b3;
+  for value in values {
+    let top = residue >> shift;
+    residue = (residue & mask) << 5 ^ *value as u128;
+    for (i, g) in generator.iter().enumerate() {
+      if top >> i & 1 == 1 {
+        residue ^= g;
+      }
+    }
+  }
+  return residue;
+}
+
+fn verify_checksum(data: &[u8]) -> bool {
+  let target = if data.len() > MAX_SHORT_DATA {
+    MS32_LONG_CONST
+  } else {
+    MS32_CONST
+  };
+  return polymod(data) == target;
+}
+
+fn create_checksum(data: &[u8]) -> Vec<u8> {
+  let len = if data.len() + CHECKSUM_LEN > MAX_SHORT_DATA {
+    LONG_CHECKSUM_LEN
+  } else {
+    CHECKSUM_LEN
+  };
+  let mut values = data.to_vec();
+  values.resize(data.len() + len, 0);
+  let target = if len == LONG_CHECKSUM_LEN {
+    MS32_LONG_CONST
+  } else {
+    MS32_CONST
+  };
+  let residue = polymod(&values) ^ target;
+  return (0..len)
+    .map(|i| (residue >> (5 * (len - 1 - i)) & 31) as u8)
+    .collect();
+}
+
+/// The bech32 value of a character of the charset.
+fn u5(c: char) -> u8 {
+  return CHARSET.iter().position(|x| *x as char == c).unwrap_or(0) as u8;
+}
+
+/// Bytes as 5 bit values, the last one padded with zero bits.
+fn to_u5(bytes: &[u8]) -> Vec<u8> {
+  let mut values = Vec::with_capacity((bytes.len() * 8).div_ceil(5));
+  let mut acc: u32 = 0;
+  let mut bits = 0;
+  for byte in bytes {
+    acc = (acc << 8 | *byte as u32) & 0xfff;
+    bits += 8;
+    while bits >= 5 {
+      bits -= 5;
+      values.push((acc >> bits & 31) as u8);
+    }
+  }
+  if bits > 0 {
+    values.push((acc << (5 - bits) & 31) as u8);
+  }
+  return values;
+}
+
+/// 5 bit values as bytes, dropping the padding bits whatever they are.
+fn from_u5(values: &[u8]) -> Vec<u8> {
+  let mut bytes = Vec::with_capacity(values.len() * 5 / 8);
+  let mut acc: u32 = 0;
+  let mut bits = 0;
+  for value in values {
+    acc = (acc << 5 | *value as u32) & 0xfff;
+    bits += 5;
+    if bits >= 8 {
+      bits -= 8;
+      bytes.push((acc >> bits) as u8);
+    }
+  }
+  return bytes;
+}
@@ -0,0 +1,269 @@
+use crate::{
+  descriptor::checksum,
+  error::VulcanError,
+  keys::parse_xpub,
+  multisig::{get_multisig_wallet, Cosigner, MultisigWallet, MAX_COSIGNERS},
+  path::parse_path,
+  types::ScriptType,
+};
+use alloc::{
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+use bitcoin::{secp256k1::Secp256k1, util::bip32, Network};
+use core::{fmt, str::FromStr};
+
+/// A multisig wallet imported from a coordinator, before it is matched
+/// against our own key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigConfig {
+  pub name: String,
+  pub script_type: ScriptType,
+  pub threshold: usize,
+  pub cosigners: Vec<Cosigner>,
+}
+
+impl MultisigConfig {
+  /// Finds our key in the quorum and builds the wallet. One seed can hold
+  /// several keys of a quorum, so ours is the one whose xpub we derive at its
+  /// path.
+  pub fn register(
+    &self,
+    root: &bip32::ExtendedPrivKey,
+    network: Network,
+  ) -> Result<MultisigWallet, VulcanError> {
+    let secp = Secp256k1::new();
+    let fingerprint = root.fingerprint(&secp);
+    let ours = self
+      .cosigners
+      .iter()
+      .filter(|cosigner| cosigner.fingerprint == fingerprint)
+      .find(|cosigner| match root.derive_priv(&secp, &cosigner.path) {
+        Ok(xpriv) => bip32::ExtendedPubKey::from_priv(&secp, &xpriv) == cosigner.xpub,
+        Err(_) => false,
+      })
+      .ok_or(VulcanError::NotInQuorum)?;
+    return get_multisig_wallet(
+      root,
+      &ours.path,
+      self.script_type,
+      network,
+      self.threshold,
+      &self.cosigners,
+    );
+  }
+}
+
+impl fmt::Display for MultisigConfig {
+  /// Writes the Coldcard text format that `parse_multisig_config` reads, with
+  /// a derivation line per key.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let format = match self.script_type {
+      ScriptType::NestedSegwit => "P2SH-P2WSH",
+      ScriptType::Segwit => "P2WSH",
+      ScriptType::Taproot => "P2TR",
+    };
+    writeln!(f, "Name: {}", self.name)?;
+    writeln!(f, "Policy: {} of {}", self.threshold, self.cosigners.len())?;
+    writeln!(f, "Format: {}", format)?;
+    for cosigner in &self.cosigners {
+      writeln!(f)?;
+      writeln!(f, "Derivation: {}", cosigner.path)?;
+      writeln!(
+        f,
+        "{}: {}",
+        cosigner.fingerprint.to_string().to_ascii_uppercase(),
+        cosigner.xpub
+      )?;
+    }
+    Ok(())
+  }
+}
+
+/// Parses a multisig setup file. Accepts the Coldcard text format that
+/// Specter and Sparrow also export:
+///
+/// ```text
+/// Name: My Multisig
+/// Policy: 2 of 3
+/// Derivation: m/48'/0'/0'/2'
+/// Format: P2WSH
+///
+/// 73C5DA0A: xpub...
+/// ```
+///
+/// and `wsh(sortedmulti(...))` / `sh(wsh(sortedmulti(...)))` descriptors,
+/// either bare or as the `"descriptor"` field of a JSON export.
+pub fn parse_multisig_config(text: &str) -> Result<MultisigConfig, VulcanError> {
+  let text = text.trim_start_matches('\u{feff}').trim();
+
+  if let Some(desc) = find_descriptor(text) {
+    let name = json_field(text, "label").unwrap_or_default();
+    return parse_descriptor(&name, &desc);
+  }
+  return parse_text(text);
+}
+
+/// Reads the Coldcard style `Key: value` format.
+fn parse_text(text: &str) -> Result<MultisigConfig, VulcanError> {
+  let mut name = String::new();
+  let mut policy: Option<(usize, usize)> = None;
+  let mut script_type: Option<ScriptType> = None;
+  let mut derivation: Option<bip32::DerivationPath> = None;
+  let mut cosigners: Vec<Cosigner> = vec![];
+
+  for line in text.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let (key, value) = match line.find(':') {
+      Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+      None => return Err(VulcanError::BadMultisig),
+    };
+
+    match key.to_ascii_lowercase().as_str() {
+      "name" => name = value.to_string(),
+      "policy" => {
+        let mut parts = value.split_whitespace();
+        let m = parts.next().and_then(|m| m.parse::<usize>().ok());
+        let of = parts.next().map(|of| of.eq_ignore_ascii_case("of"));
+        let n = parts.next().and_then(|n| n.parse::<usize>().ok());
+        match (m, of, n) {
+          (Some(m), Some(true), Some(n)) => policy = Some((m, n)),
+          _ => return Err(VulcanError::BadMultisig),
+        }
+      }
+      "format" => {
+        script_type = match value.to_ascii_uppercase().as_str() {
+          "P2WSH" => Some(ScriptType::Segwit),
+          "P2SH-P2WSH" | "P2WSH-P2SH" => Some(ScriptType::NestedSegwit),
+          // legacy P2SH multisig isn't supported
+          _ => return Err(VulcanError::BadMultisig),
+        }
+      }
+      // applies to every key after it, files may repeat it per key
+      "derivation" => derivation = Some(parse_path(value)?),
+      _ if key.len() == 8 && key.chars().all(|c| c.is_ascii_hexdigit()) => {
+        let path = derivation.clone().ok_or(VulcanError::BadDerivationPath)?;
+        cosigners.push(Cosigner {
+          fingerprint: bip32::Fingerprint::from_str(&key.to_ascii_lowercase())
+            .map_err(|_| VulcanError::BadMultisig)?,
+          path,
+          xpub: parse_xpub(value)?,
+        });
+      }
+      // unknown fields are ignored
+      _ => {}
+    }
+  }
+
+  let (threshold, count) = policy.ok_or(VulcanError::BadMultisig)?;
+  if count != cosigners.len() {
+    return Err(VulcanError::BadMultisig);
+  }
+  return validate(MultisigConfig {
+    name,
+    // Coldcard defaults to P2SH, which we can't sign
+    script_type: script_type.ok_or(VulcanError::BadMultisig)?,
+    threshold,
+    cosigners,
+  });
+}
+
+/// Reads a `sortedmulti` descriptor. The checksum is verified if present.
+fn parse_descriptor(name: &str, desc: &str) -> Result<MultisigConfig, VulcanError> {
+  let desc = match desc.find('#') {
+    Some(i) => {
+      if checksum(&desc[..i])? != desc[i + 1..] {
+        return Err(VulcanError::BadDescriptor);
+      }
+      &desc[..i]
+    }
+    None => desc,
+  };
+
+  let (script_type, inner) = if let Some(inner) = desc
+    .strip_prefix("sh(wsh(sortedmulti(")
+    .and_then(|d| d.strip_suffix(")))"))
+  {
+    (ScriptType::NestedSegwit, inner)
+  } else if let Some(inner) = desc
+    .strip_prefix("wsh(sortedmulti(")
+    .and_then(|d| d.strip_suffix("))"))
+  {
+    (ScriptType::Segwit, inner)
+  } else {
+    return Err(VulcanError::BadDescriptor);
+  };
+
+  let mut parts = inner.split(',');
+  let threshold = parts
+    .next()
+    .and_then(|m| m.trim().parse::<usize>().ok())
+    .ok_or(VulcanError::BadDescriptor)?;
+  let mut cosigners: Vec<Cosigner> = vec![];
+  for key in parts {
+    let key = key.trim();
+    // the receive/change chain suffix is implied
+    let key = ["/<0;1>/*", "/0/*", "/1/*", "/**"]
+      .iter()
+      .find_map(|suffix| key.strip_suffix(suffix))
+      .unwrap_or(key);
+    cosigners.push(Cosigner::from_str(key)?);
+  }
+
+  return validate(MultisigConfig {
+    name: name.to_string(),
+    script_type,
+    threshold,
+    cosigners,
+  });
+}
+
+/// Checks the quorum makes sense on its own, before our key is involved.
+fn validate(config: MultisigConfig) -> Result<MultisigConfig, VulcanError> {
+  let n = config.cosigners.len();
+  if !(2..=MAX_COSIGNERS).contains(&n) || config.threshold == 0 || config.threshold > n {
+    return Err(VulcanError::BadMultisig);
+  }
+  for (i, cosigner) in config.cosigners.iter().enumerate() {
+    if config.cosigners[..i]
+      .iter()
+      .any(|other| other.xpub == cosigner.xpub)
+    {
+      return Err(VulcanError::BadMultisig);
+    }
+    // xpubs only tell mainnet from everything else
+    if (cosigner.xpub.network == Network::Bitcoin)
+      != (config.cosigners[0].xpub.network == Network::Bitcoin)
+    {
+      return Err(VulcanError::WrongNetwork);
+    }
+  }
+  return Ok(config);
+}
+
+/// The descriptor of a bare descriptor export or a JSON one.
+fn find_descriptor(text: &str) -> Option<String> {
+  if let Some(desc) = json_field(text, "descriptor") {
+    return Some(desc);
+  }
+  return text
+    .lines()
+    .map(|line| line.trim())
+    .find(|line| line.starts_with("wsh(") || line.starts_with("sh(wsh("))
+    .map(|line| line.to_string());
+}
+
+/// Minimal lookup of a string field in a flat JSON object. Descriptors
+/// and labels don't contain escaped quotes.
+fn json_field(text: &str, field: &str) -> Option<String> {
+  let key = ["\"", field, "\""].join("");
+  let rest = &text[text.find(&key)? + key.len()..];
+  let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+  let rest = rest.strip_prefix('"')?;
+  let end = rest.find('"')?;
+  return Some(rest[..end].to_string());
+}
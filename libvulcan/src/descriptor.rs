@@ -0,0 +1,181 @@
+use crate::{
+  error::VulcanError,
+  types::{ScriptType, WalletType},
+};
+use alloc::{format, string::String, vec::Vec};
+use bitcoin::util::bip32;
+
+const INPUT_CHARSET: &str =
+  "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn polymod(c: u64, val: u64) -> u64 {
+  let c0 = c >> 35;
+  let mut c = ((c & 0x7ffffffff) << 5) ^ val;
+  if c0 & 1 != 0 {
+    c ^= 0xf5dee51989;
+  }
+  if c0 & 2 != 0 {
+    c ^= 0xa9fdca3312;
+  }
+  if c0 & 4 != 0 {
+    c ^= 0x1bab10e32d;
+  }
+  if c0 & 8 != 0 {
+    c ^= 0x3706b1677a;
+  }
+  if c0 & 16 != 0 {
+    c ^= 0x644d626ffd;
+  }
+  return c;
+}
+
+/// Computes the 8 character BIP380 checksum of a descriptor.
+pub fn checksum(desc: &str) -> Result<String, VulcanError> {
+  let mut c = 1;
+  let mut cls = 0;
+  let mut clscount = 0;
+  for ch in desc.chars() {
+    let pos = match INPUT_CHARSET.find(ch) {
+      Some(pos) => pos as u64,
+      None => return Err(VulcanError::BadDescriptor),
+    };
+    c = polymod(c, pos & 31);
+    cls = cls * 3 + (pos >> 5);
+    clscount += 1;
+    if clscount == 3 {
+      c = polymod(c, cls);
+      cls = 0;
+      clscount = 0;
+    }
+  }
+  if clscount > 0 {
+    c = polymod(c, cls);
+  }
+  for _ in 0..8 {
+    c = polymod(c, 0);
+  }
+  c ^= 1;
+
+  let mut sum = String::with_capacity(8);
+  for j in 0..8 {
+    sum.push(CHECKSUM_CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char);
+  }
+  return Ok(sum);
+}
+
+/// Appends `#<checksum>` to a descriptor.
+pub fn with_checksum(desc: &str) -> Result<String, VulcanError> {
+  return Ok(format!("{}#{}", desc, checksum(desc)?));
+}
+
+/// The address chains a descriptor derives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Keychain {
+  Receive,
+  Change,
+  /// Both chains in one BIP389 `<0;1>` multipath descriptor
+  Multipath,
+}
+
+impl Keychain {
+  pub fn from_change(change: bool) -> Self {
+    return if change {
+      Keychain::Change
+    } else {
+      Keychain::Receive
+    };
+  }
+
+  /// What follows the account xpub in a key expression
+  fn suffix(&self) -> &'static str {
+    return match self {
+      Keychain::Receive => "/0/*",
+      Keychain::Change => "/1/*",
+      Keychain::Multipath => "/<0;1>/*",
+    };
+  }
+}
+
+/// Formats a path without the leading `m`, hardened steps as `h`.
+pub fn format_path(path: &bip32::DerivationPath) -> String {
+  let steps: Vec<String> = path
+    .into_iter()
+    .map(|child| format!("{:#}", child))
+    .collect();
+  return steps.join("/");
+}
+
+/// A key expression with origin info: `[fingerprint/path]xpub`.
+pub fn key_expression(
+  fingerprint: &bip32::Fingerprint,
+  path: &bip32::DerivationPath,
+  xpub: &bip32::ExtendedPubKey,
+) -> String {
+  if path.as_ref().is_empty() {
+    return format!("[{}]{}", fingerprint, xpub);
+  }
+  return format!("[{}/{}]{}", fingerprint, format_path(path), xpub);
+}
+
+/// Wraps key expressions in the output descriptor for the wallet and script
+/// type, with checksum. Single-sig takes exactly one key, multisig uses
+/// `sortedmulti` with `threshold` of the keys.
+pub fn build_descriptor(
+  wallet_type: &WalletType,
+  script_type: &ScriptType,
+  threshold: usize,
+  keys: &[String],
+) -> Result<String, VulcanError> {
+  let desc = match wallet_type {
+    WalletType::SingleSig => {
+      if keys.len() != 1 {
+        return Err(VulcanError::BadDescriptor);
+      }
+      match script_type {
+        ScriptType::NestedSegwit => format!("sh(wpkh({}))", keys[0]),
+        ScriptType::Segwit => format!("wpkh({})", keys[0]),
+        ScriptType::Taproot => format!("tr({})", keys[0]),
+      }
+    }
+    WalletType::MultiSig => {
+      if threshold == 0 || threshold > keys.len() {
+        return Err(VulcanError::BadDescriptor);
+      }
+      let multi = format!("sortedmulti({},{})", threshold, keys.join(","));
+      match script_type {
+        ScriptType::NestedSegwit => format!("sh(wsh({}))", multi),
+        ScriptType::Segwit => format!("wsh({})", multi),
+        ScriptType::Taproot => return Err(VulcanError::UnsupportedScriptType(2)),
+      }
+    }
+  };
+  return with_checksum(&desc);
+}
+
+/// The complete descriptor of an account, with checksum. Every key is an
+/// account xpub with its `[fingerprint/path]` origin, followed by the
+/// keychain's derivation steps.
+pub fn account_descriptor(
+  wallet_type: &WalletType,
+  script_type: &ScriptType,
+  threshold: usize,
+  keys: &[(
+    bip32::Fingerprint,
+    bip32::DerivationPath,
+    bip32::ExtendedPubKey,
+  )],
+  keychain: Keychain,
+) -> Result<String, VulcanError> {
+  let keys: Vec<String> = keys
+    .iter()
+    .map(|(fingerprint, path, xpub)| {
+      format!(
+        "{}{}",
+        key_expression(fingerprint, path, xpub),
+        keychain.suffix()
+      )
+    })
+    .collect();
+  return build_descriptor(wallet_type, script_type, threshold, &keys);
+}
@@ -0,0 +1,142 @@
+//! New mnemonics from words picked by hand, or from dice rolls or coin flips.
+//!
+//! Rolls and flips are turned into a mnemonic the same way SeedSigner does
+//! it. Dice rolls (`1` to `6`) are hashed as a string with SHA256 and 12 word
+//! mnemonics take the first 16 bytes of the digest, so without chip entropy
+//! the result can be checked offline with `printf 1234... | sha256sum`. Coin
+//! flips (`0` and `1`) already are uniform bits and become the entropy as
+//! they are, the first flip being the most significant bit.
+
+use crate::error::VulcanError;
+use alloc::{vec, vec::Vec};
+use bip39::{Language, Mnemonic};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+
+/// log2(6), the entropy of a fair die roll
+const DICE_BITS: f64 = 2.584962500721156;
+
+/// Where the user's entropy comes from.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum EntropySource {
+  /// Six-sided dice, entered as `1` to `6`
+  Dice,
+  /// Coin flips, entered as `0` and `1`
+  Coin,
+}
+
+impl EntropySource {
+  /// How many rolls or flips a mnemonic of `words` words needs: 50 or 99
+  /// rolls, 128 or 256 flips.
+  pub fn inputs(&self, words: usize) -> Result<usize, VulcanError> {
+    return match (self, words) {
+      (EntropySource::Dice, 12) => Ok(50),
+      (EntropySource::Dice, 24) => Ok(99),
+      (EntropySource::Coin, 12) => Ok(128),
+      (EntropySource::Coin, 24) => Ok(256),
+      _ => Err(VulcanError::BadWordCount(words)),
+    };
+  }
+
+  /// Whether `c` is a roll or flip of this source.
+  pub fn is_valid(&self, c: char) -> bool {
+    return match self {
+      EntropySource::Dice => ('1'..='6').contains(&c),
+      EntropySource::Coin => c == '0' || c == '1',
+    };
+  }
+
+  /// Whole bits of entropy in `count` rolls or flips.
+  pub fn bits(&self, count: usize) -> u32 {
+    return match self {
+      EntropySource::Dice => (count as f64 * DICE_BITS) as u32,
+      EntropySource::Coin => count as u32,
+    };
+  }
+}
+
+/// Builds a 12 or 24 word mnemonic from the rolls or flips in `input`. With
+/// `chip_entropy` the rolls' digest or the flips' bits are hashed again
+/// together with it, `SHA256(entropy || chip_entropy)`, so neither a weak die
+/// nor a weak RNG alone decides the seed.
+pub fn generate_mnemonic(
+  source: EntropySource,
+  input: &str,
+  chip_entropy: Option<&[u8]>,
+) -> Result<Mnemonic, VulcanError> {
+  if !input.chars().all(|c| source.is_valid(c)) {
+    return Err(VulcanError::BadEntropy);
+  }
+  let len = if input.len() == source.inputs(12)? {
+    16
+  } else if input.len() == source.inputs(24)? {
+    32
+  } else {
+    return Err(VulcanError::BadEntropyLength(input.len()));
+  };
+
+  let mut entropy = match source {
+    EntropySource::Dice => sha256::Hash::hash(input.as_bytes()).to_vec(),
+    EntropySource::Coin => {
+      let mut bits = vec![0u8; len];
+      for (i, flip) in input.chars().enumerate() {
+        set_bits(&mut bits, i, (flip == '1') as u16, 1);
+      }
+      bits
+    }
+  };
+  if let Some(chip_entropy) = chip_entropy {
+    let mut engine = sha256::Hash::engine();
+    engine.input(&entropy);
+    engine.input(chip_entropy);
+    entropy = sha256::Hash::from_engine(engine).to_vec();
+  }
+  return Ok(Mnemonic::from_entropy_in(
+    Language::English,
+    &entropy[..len],
+  )?);
+}
+
+/// Every English word that completes `words` to a mnemonic with a valid
+/// checksum, in wordlist order. The last word carries both entropy and
+/// checksum bits, so 11 words have 128 valid final words and 23 have 8.
+pub fn final_words(words: &[&str]) -> Result<Vec<&'static str>, VulcanError> {
+  let count = words.len() + 1;
+  if ![12, 15, 18, 21, 24].contains(&count) {
+    return Err(VulcanError::BadWordCount(words.len()));
+  }
+  let checksum_bits = count / 3;
+  let free_bits = 11 - checksum_bits;
+
+  // the entropy bits of the given words, MSB first
+  let mut entropy = vec![0u8; (count * 11 - checksum_bits) / 8];
+  for (i, word) in words.iter().enumerate() {
+    let index = Language::English
+      .find_word(word)
+      .ok_or(bip39::Error::UnknownWord(i))?;
+    set_bits(&mut entropy, i * 11, index, 11);
+  }
+
+  // the checksum decides the rest of the last word
+  let mut last_words = Vec::with_capacity(1 << free_bits);
+  for free in 0..(1u16 << free_bits) {
+    set_bits(&mut entropy, words.len() * 11, free, free_bits);
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy)?;
+    if let Some(word) = mnemonic.words().last() {
+      last_words.push(word);
+    }
+  }
+  return Ok(last_words);
+}
+
+/// Writes the low `len` bits of `value` to `bytes`, starting at bit `start`.
+fn set_bits(bytes: &mut [u8], start: usize, value: u16, len: usize) {
+  for i in 0..len {
+    let bit = start + i;
+    let mask = 0x80 >> (bit % 8);
+    if value >> (len - 1 - i) & 1 == 1 {
+      bytes[bit / 8] |= mask;
+    } else {
+      bytes[bit / 8] &= !mask;
+    }
+  }
+}
@@ -0,0 +1,147 @@
+use bitcoin::util::bip32;
+use core::fmt;
+
+/// Everything that can go wrong in libvulcan.
+///
+/// Input from QR codes, SD cards and coordinators is untrusted, so none of
+/// the public functions panic on bad data. The firmware shows these on an
+/// error screen instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VulcanError {
+  /// The QR payload has the wrong length or contains unexpected characters
+  MalformedQr,
+  /// The payload has more digits than fit in a 29x29 QR code
+  QrTooLarge(usize),
+  /// The payload was produced by a newer, unknown format version
+  UnsupportedVersion(u8),
+  /// Unknown script type code
+  UnsupportedScriptType(u8),
+  /// Mnemonics must have 12, 15, 18, 21 or 24 words
+  BadWordCount(usize),
+  /// The entropy has characters that aren't dice rolls or coin flips
+  BadEntropy,
+  /// The requested amount of entropy is out of range
+  BadEntropyLength(usize),
+  /// The SLIP-39 share has an unknown word, a bad checksum or bad padding
+  BadShare,
+  /// The shares belong to different backups or contradict each other
+  ShareMismatch,
+  /// More shares are needed to recover the secret
+  InsufficientShares,
+  /// The group or member thresholds can't be used to split a secret
+  BadShamirGroups,
+  /// Seed XOR needs 2 to 4 parts of the same length
+  BadSeedXor,
+  /// The codex32 string has a bad header, length, padding or checksum
+  BadCodex32,
+  /// The derivation path could not be parsed or derived
+  BadDerivationPath,
+  /// The descriptor contains characters outside the BIP380 charset
+  BadDescriptor,
+  /// The multisig threshold or cosigner keys don't make a valid quorum
+  BadMultisig,
+  /// None of the multisig keys are ours
+  NotInQuorum,
+  /// A key or address belongs to another network
+  WrongNetwork,
+  /// The output script has no address representation
+  NonStandardScript,
+  /// The PSBT maps don't match the unsigned transaction
+  InconsistentPsbt,
+  /// The PSBT can't be decoded or misses a required field
+  MalformedPsbt,
+  /// The text is not an address
+  BadAddress,
+  /// The message is empty, too long or has characters that can't be signed
+  BadMessage,
+  /// The signature can't be decoded
+  BadSignature,
+  /// The address type can't be used to sign or verify messages
+  UnsupportedMessageFormat,
+  /// Input has neither a `witness_utxo` nor a `non_witness_utxo`
+  MissingUtxo(usize),
+  /// The input's UTXO data doesn't match the outpoint it spends
+  UtxoMismatch(usize),
+  /// The device has no seed to derive keys from yet
+  NoWallet,
+  /// No SD card is inserted
+  NoSdCard,
+  /// The file isn't on the SD card, or is too large to read
+  FileNotFound(&'static str),
+  /// The file couldn't be written to the SD card
+  WriteFailed(&'static str),
+  /// The camera can't scan QR codes yet
+  NoCamera,
+  /// The wallet was recovered from shares, so it has no mnemonic or
+  /// passphrase
+  NoMnemonic,
+  /// The secret, in bytes, is a length Trezor can't restore SLIP-39 shares of
+  UnsupportedSecretLength(usize),
+  /// None of the PSBT's inputs can be signed with our keys
+  NothingToSign,
+  /// BIP32 key derivation failed
+  Bip32(bip32::Error),
+  /// The mnemonic is invalid
+  Mnemonic(bip39::Error),
+}
+
+impl fmt::Display for VulcanError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      VulcanError::MalformedQr => write!(f, "malformed QR code"),
+      VulcanError::QrTooLarge(len) => write!(f, "{} digits don't fit a 29x29 QR code", len),
+      VulcanError::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+      VulcanError::UnsupportedScriptType(code) => write!(f, "unsupported script type {}", code),
+      VulcanError::BadWordCount(count) => write!(f, "bad word count {}", count),
+      VulcanError::BadEntropy => write!(f, "bad dice rolls or coin flips"),
+      VulcanError::BadEntropyLength(len) => write!(f, "bad entropy length {}", len),
+      VulcanError::BadShare => write!(f, "bad SLIP-39 share"),
+      VulcanError::ShareMismatch => write!(f, "shares don't belong together"),
+      VulcanError::InsufficientShares => write!(f, "not enough shares"),
+      VulcanError::BadShamirGroups => write!(f, "bad share groups or thresholds"),
+      VulcanError::BadSeedXor => write!(f, "Seed XOR needs 2 to 4 parts of the same length"),
+      VulcanError::BadCodex32 => write!(f, "bad codex32 string"),
+      VulcanError::BadDerivationPath => write!(f, "bad derivation path"),
+      VulcanError::BadDescriptor => write!(f, "bad descriptor"),
+      VulcanError::BadMultisig => write!(f, "bad multisig quorum"),
+      VulcanError::NotInQuorum => write!(f, "not a cosigner of this wallet"),
+      VulcanError::WrongNetwork => write!(f, "wrong network"),
+      VulcanError::NonStandardScript => write!(f, "non-standard output script"),
+      VulcanError::InconsistentPsbt => write!(f, "inconsistent PSBT"),
+      VulcanError::MalformedPsbt => write!(f, "malformed PSBT"),
+      VulcanError::BadAddress => write!(f, "bad address"),
+      VulcanError::BadMessage => write!(f, "bad message"),
+      VulcanError::BadSignature => write!(f, "bad signature"),
+      VulcanError::UnsupportedMessageFormat => write!(f, "unsupported message address type"),
+      VulcanError::MissingUtxo(i) => write!(f, "input {} has no UTXO data", i),
+      VulcanError::UtxoMismatch(i) => write!(f, "input {} UTXO doesn't match", i),
+      VulcanError::NoWallet => write!(f, "no wallet loaded"),
+      VulcanError::NoSdCard => write!(f, "no SD card"),
+      VulcanError::FileNotFound(name) => write!(f, "no {} on the SD card", name),
+      VulcanError::WriteFailed(name) => write!(f, "can't write {} to the SD card", name),
+      VulcanError::NoCamera => write!(f, "can't scan QR codes yet"),
+      VulcanError::NoMnemonic => write!(f, "wallet has no mnemonic"),
+      VulcanError::UnsupportedSecretLength(len) => {
+        write!(f, "SLIP-39 needs a 128 or 256 bit secret, not {}", len * 8)
+      }
+      VulcanError::NothingToSign => write!(f, "no inputs to sign"),
+      VulcanError::Bip32(err) => write!(f, "bip32: {}", err),
+      VulcanError::Mnemonic(err) => write!(f, "mnemonic: {}", err),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VulcanError {}
+
+impl From<bip32::Error> for VulcanError {
+  fn from(err: bip32::Error) -> Self {
+    VulcanError::Bip32(err)
+  }
+}
+
+impl From<bip39::Error> for VulcanError {
+  fn from(err: bip39::Error) -> Self {
+    VulcanError::Mnemonic(err)
+  }
+}
@@ -0,0 +1,145 @@
+use crate::{
+  error::VulcanError,
+  keys::convert_xpub_slip132,
+  path::get_path,
+  types::{ScriptType, WalletType},
+  wallet::{get_wallet, Wallet},
+};
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec::Vec,
+};
+use bitcoin::{secp256k1::Secp256k1, util::bip32, Network};
+
+/// Specter Desktop wallet import: the account's receive descriptor as JSON.
+/// Specter derives the change descriptor from it.
+pub fn specter_export(wallet: &Wallet, label: &str) -> Result<String, VulcanError> {
+  return Ok(format!(
+    "{{\"label\": {}, \"blockheight\": 0, \"descriptor\": {}, \"devices\": [{{\"type\": \"other\", \"label\": \"Vulcan\"}}]}}",
+    json_string(label),
+    json_string(&wallet.descriptor(false)?)
+  ));
+}
+
+/// The Coldcard "generic" JSON export that Sparrow imports. It lists every
+/// account the device can sign for: BIP49, BIP84 and BIP86 single-sig and
+/// both BIP48 multisig script types.
+pub fn generic_export(
+  root: &bip32::ExtendedPrivKey,
+  network: Network,
+) -> Result<String, VulcanError> {
+  let secp = Secp256k1::new();
+  let fingerprint = root.fingerprint(&secp);
+  let xfp = fingerprint.to_string().to_uppercase();
+  let chain = match network {
+    Network::Bitcoin => "BTC",
+    Network::Regtest => "XRT",
+    _ => "XTN",
+  };
+
+  let mut sections: Vec<String> = Vec::new();
+  let single_sig = [
+    ("bip49", "p2sh-p2wpkh", ScriptType::NestedSegwit),
+    ("bip84", "p2wpkh", ScriptType::Segwit),
+    ("bip86", "p2tr", ScriptType::Taproot),
+  ];
+  for (section, name, script_type) in single_sig.iter() {
+    let path = get_path(&network, &WalletType::SingleSig, script_type)?;
+    let wallet = get_wallet(root, &path, *script_type, network)?;
+    let xpub = wallet.xpub()?;
+    let mut fields = section_fields(name, &xfp, &path, &xpub);
+    if *script_type != ScriptType::Taproot {
+      let slip132 = convert_xpub_slip132(&xpub, &network, &WalletType::SingleSig, script_type);
+      fields.push(format!("\"_pub\": {}", json_string(&slip132)));
+    }
+    fields.push(format!(
+      "\"desc\": {}",
+      json_string(&wallet.descriptor(false)?)
+    ));
+    fields.push(format!(
+      "\"first\": {}",
+      json_string(&wallet.address(false, 0)?.to_string())
+    ));
+    sections.push(format!("\"{}\": {{{}}}", section, fields.join(", ")));
+  }
+
+  let multisig = [
+    ("bip48_1", "p2sh-p2wsh", ScriptType::NestedSegwit),
+    ("bip48_2", "p2wsh", ScriptType::Segwit),
+  ];
+  for (section, name, script_type) in multisig.iter() {
+    let path = get_path(&network, &WalletType::MultiSig, script_type)?;
+    let xpriv = root.derive_priv(&secp, &path)?;
+    let xpub = bip32::ExtendedPubKey::from_priv(&secp, &xpriv);
+    let mut fields = section_fields(name, &xfp, &path, &xpub);
+    let slip132 = convert_xpub_slip132(&xpub, &network, &WalletType::MultiSig, script_type);
+    fields.push(format!("\"_pub\": {}", json_string(&slip132)));
+    sections.push(format!("\"{}\": {{{}}}", section, fields.join(", ")));
+  }
+
+  let root_xpub = bip32::ExtendedPubKey::from_priv(&secp, root);
+  return Ok(format!(
+    "{{\"chain\": \"{}\", \"xfp\": \"{}\", \"account\": 0, \"xpub\": \"{}\", {}}}",
+    chain,
+    xfp,
+    root_xpub,
+    sections.join(", ")
+  ));
+}
+
+/// An Electrum watch-only wallet file for a single-sig account. Electrum
+/// reads the script type from the SLIP-132 prefix and has no taproot support.
+pub fn electrum_export(wallet: &Wallet) -> Result<String, VulcanError> {
+  let script_type = wallet.script_type();
+  if script_type == ScriptType::Taproot {
+    return Err(VulcanError::UnsupportedScriptType(2));
+  }
+  let xpub = convert_xpub_slip132(
+    &wallet.xpub()?,
+    &wallet.network(),
+    &WalletType::SingleSig,
+    &script_type,
+  );
+  return Ok(format!(
+    "{{\"keystore\": {{\"type\": \"bip32\", \"xpub\": {}, \"derivation\": {}, \"root_fingerprint\": \"{}\", \"label\": \"Vulcan\"}}, \"wallet_type\": \"standard\", \"use_encryption\": false, \"seed_version\": 17}}",
+    json_string(&xpub),
+    json_string(&wallet.path().to_string()),
+    wallet.fingerprint()
+  ));
+}
+
+/// The fields every section of the generic export starts with
+fn section_fields(
+  name: &str,
+  xfp: &str,
+  path: &bip32::DerivationPath,
+  xpub: &bip32::ExtendedPubKey,
+) -> Vec<String> {
+  return [
+    format!("\"name\": \"{}\"", name),
+    format!("\"xfp\": \"{}\"", xfp),
+    format!("\"deriv\": {}", json_string(&path.to_string())),
+    format!("\"xpub\": \"{}\"", xpub),
+  ]
+  .to_vec();
+}
+
+/// Quotes and escapes a JSON string.
+fn json_string(s: &str) -> String {
+  let mut out = String::with_capacity(s.len() + 2);
+  out.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      '\r' => out.push_str("\\r"),
+      '\t' => out.push_str("\\t"),
+      c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+      c => out.push(c),
+    }
+  }
+  out.push('"');
+  return out;
+}
@@ -0,0 +1,115 @@
+use crate::{
+  error::VulcanError,
+  types::{ScriptType, WalletType},
+};
+use alloc::string::String;
+use bip39::Mnemonic;
+use bitcoin::{
+  util::{base58, bip32},
+  Network,
+};
+
+/// Derives the BIP32 root key of a mnemonic.
+pub fn get_root(
+  mnemonic: &Mnemonic,
+  network: Network,
+) -> Result<bip32::ExtendedPrivKey, VulcanError> {
+  return get_passphrase_root(mnemonic, "", network);
+}
+
+/// Derives the BIP32 root key of a mnemonic and BIP39 passphrase. The
+/// passphrase must already be NFKD normalized, which ASCII always is. Every
+/// passphrase gives a valid wallet, so show the root fingerprint to catch
+/// typos.
+pub fn get_passphrase_root(
+  mnemonic: &Mnemonic,
+  passphrase: &str,
+  network: Network,
+) -> Result<bip32::ExtendedPrivKey, VulcanError> {
+  let seed = mnemonic.to_seed_normalized(passphrase);
+  return get_seed_root(&seed, network);
+}
+
+/// Derives the BIP32 root key of a seed: the 64 bytes of a BIP39 mnemonic,
+/// or a SLIP-39 master secret.
+pub fn get_seed_root(seed: &[u8], network: Network) -> Result<bip32::ExtendedPrivKey, VulcanError> {
+  return Ok(bip32::ExtendedPrivKey::new_master(network, seed)?);
+}
+
+/// Parses an extended public key, accepting the SLIP-132 prefixes (ypub,
+/// zpub, Ypub, Zpub and their testnet versions) that coordinators export.
+pub fn parse_xpub(s: &str) -> Result<bip32::ExtendedPubKey, VulcanError> {
+  let mut data = base58::from_check(s.trim()).map_err(|_| VulcanError::BadDescriptor)?;
+  if data.len() != 78 {
+    return Err(VulcanError::BadDescriptor);
+  }
+  let version: [u8; 4] = match data[0..4] {
+    // xpub ypub zpub Ypub Zpub
+    [0x04, 0x88, 0xb2, 0x1e]
+    | [0x04, 0x9d, 0x7c, 0xb2]
+    | [0x04, 0xb2, 0x47, 0x46]
+    | [0x02, 0x95, 0xb4, 0x3f]
+    | [0x02, 0xaa, 0x7e, 0xd3] => [0x04, 0x88, 0xb2, 0x1e],
+    // tpub upub vpub Upub Vpub
+    [0x04, 0x35, 0x87, 0xcf]
+    | [0x04, 0x4a, 0x52, 0x62]
+    | [0x04, 0x5f, 0x1c, 0xf6]
+    | [0x02, 0x42, 0x89, 0xef]
+    | [0x02, 0x57, 0x54, 0x83] => [0x04, 0x35, 0x87, 0xcf],
+    _ => return Err(VulcanError::BadDescriptor),
+  };
+  data[0..4].copy_from_slice(&version);
+  return Ok(bip32::ExtendedPubKey::decode(&data)?);
+}
+
+/// Re-encodes an xpub with the SLIP-132 version bytes wallets like Electrum
+/// use to tell script types apart.
+pub fn convert_xpub_slip132(
+  xpub: &bip32::ExtendedPubKey,
+  network: &Network,
+  wallet_type: &WalletType,
+  script_type: &ScriptType,
+) -> String {
+  let version: [u8; 4] = match network {
+    Network::Bitcoin => match wallet_type {
+      WalletType::SingleSig => match script_type {
+        // zpub
+        ScriptType::Segwit => [0x04, 0xb2, 0x47, 0x46],
+        // ypub
+        ScriptType::NestedSegwit => [0x04, 0x9d, 0x7c, 0xb2],
+        // taproot has no SLIP-132 version, plain xpub
+        ScriptType::Taproot => [0x04, 0x88, 0xb2, 0x1e],
+      },
+      WalletType::MultiSig => match script_type {
+        // Zpub
+        ScriptType::Segwit => [0x02, 0xaa, 0x7e, 0xd3],
+        // Ypub
+        ScriptType::NestedSegwit => [0x02, 0x95, 0xb4, 0x3f],
+        // xpub
+        ScriptType::Taproot => [0x04, 0x88, 0xb2, 0x1e],
+      },
+    },
+    _ => match wallet_type {
+      WalletType::SingleSig => match script_type {
+        // vpub
+        ScriptType::Segwit => [0x04, 0x5f, 0x1c, 0xf6],
+        // upub
+        ScriptType::NestedSegwit => [0x04, 0x4a, 0x52, 0x62],
+        // tpub
+        ScriptType::Taproot => [0x04, 0x35, 0x87, 0xcf],
+      },
+      WalletType::MultiSig => match script_type {
+        // Vpub
+        ScriptType::Segwit => [0x02, 0x57, 0x54, 0x83],
+        // Upub
+        ScriptType::NestedSegwit => [0x02, 0x42, 0x89, 0xef],
+        // tpub
+        ScriptType::Taproot => [0x04, 0x35, 0x87, 0xcf],
+      },
+    },
+  };
+
+  let mut data = xpub.encode();
+  data[0..4].copy_from_slice(&version);
+  return base58::check_encode_slice(&data);
+}
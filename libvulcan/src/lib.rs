@@ -0,0 +1,71 @@
+//! Wallet logic for the vulcan signing device.
+//!
+//! The crate is `no_std + alloc` so the firmware can link it. Host builds
+//! enable the default `std` feature, the firmware uses
+//! `default-features = false, features = ["no-std"]`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use bip39;
+pub use bitcoin;
+
+mod base64;
+
+pub mod address;
+pub mod bip85;
+pub mod codex32;
+pub mod config;
+pub mod descriptor;
+pub mod entropy;
+pub mod error;
+pub mod export;
+pub mod keys;
+pub mod message;
+pub mod multisig;
+pub mod path;
+pub mod policy;
+pub mod psbt;
+pub mod psbtv2;
+pub mod seedqr;
+pub mod seedxor;
+pub mod slip39;
+pub mod types;
+pub mod wallet;
+
+pub use address::{
+  address_script_type, find_address, parse_address, AddressMatch, DEFAULT_GAP_LIMIT,
+};
+pub use bip85::{
+  bip85_hex, bip85_mnemonic, bip85_wif, bip85_xprv, derive_entropy, Bip85Application,
+};
+pub use codex32::{codex32_combine, codex32_identifier, codex32_split, Codex32Share};
+pub use config::{parse_multisig_config, MultisigConfig};
+pub use descriptor::{account_descriptor, Keychain};
+pub use entropy::{final_words, generate_mnemonic, EntropySource};
+pub use error::VulcanError;
+pub use export::{electrum_export, generic_export, specter_export};
+pub use keys::{convert_xpub_slip132, get_passphrase_root, get_root, get_seed_root, parse_xpub};
+pub use message::{
+  parse_message_request, sign_message, verify_message, MessageAddressType, MessageRequest,
+  SignatureFormat, SignedMessage,
+};
+pub use multisig::{get_multisig_wallet, Cosigner, MultisigWallet};
+pub use path::{coin_type, get_path};
+pub use policy::{check_psbt, Policy, PolicyIssue, PolicyReport};
+pub use psbt::{
+  calc_fee, calc_input, calc_spend_change, estimate_vsize, psbt_base64, psbt_bytes, Account,
+  OutputKind, Outputs, TransactionSummary,
+};
+pub use psbtv2::{determine_locktime, psbt_version, PsbtFile, PsbtV2, RequiredLockTime};
+pub use seedqr::{
+  decode_compact_seedqr, decode_seedqr, encode_compact_seedqr, encode_extended_seedqr,
+  encode_seedqr, parse_extended_seedqr,
+};
+pub use seedxor::{seed_xor_combine, seed_xor_split, MAX_SEED_XOR_PARTS, MIN_SEED_XOR_PARTS};
+pub use slip39::{
+  combine_mnemonics, combine_shares, slip39_words_by_prefix, split_master_secret, Share,
+};
+pub use types::{ScriptType, WalletType};
+pub use wallet::{get_wallet, Wallet};
@@ -1,341 +1,448 @@
-use bdk::{
+//! `vulcan-cli` runs the device's wallet logic on the host, so it can be
+//! prototyped and audited with test seeds. Never give it a real seed.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use libvulcan::{
+  address_script_type,
+  bip39::{Language, Mnemonic},
   bitcoin::{
-    base64, consensus,
+    base64,
+    hashes::hex::{FromHex, ToHex},
     secp256k1::Secp256k1,
-    util::{bip32, psbt::PartiallySignedTransaction},
-    Address, Network,
-  },
-  database::MemoryDatabase,
-  descriptor,
-  keys::{bip39, DerivableKey, DescriptorSecretKey, DescriptorSinglePriv, ExtendedKey},
-  miniscript::{
-    descriptor::{DescriptorXKey, Wildcard},
-    DescriptorPublicKey,
+    util::bip32,
+    EcdsaSighashType, Network,
   },
-  SignOptions, Wallet,
+  check_psbt, convert_xpub_slip132, decode_compact_seedqr, decode_seedqr, electrum_export,
+  encode_compact_seedqr, encode_extended_seedqr, encode_seedqr, find_address, generic_export,
+  get_passphrase_root, get_path, get_wallet, parse_address, parse_extended_seedqr,
+  path::parse_path,
+  psbt_bytes,
+  seedqr::words_to_mnemonic,
+  specter_export, Keychain, Policy, PolicyReport, PsbtFile, ScriptType, TransactionSummary,
+  VulcanError, Wallet, WalletType, DEFAULT_GAP_LIMIT,
 };
-use std::str::FromStr;
-use xyzpub::{convert_version, Version};
-
-#[derive(Debug)]
-enum WalletType {
-  SingleSig,
-  MultiSig,
+use std::{error::Error, fs, path::PathBuf, process};
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+/// Runs the vulcan signing device's wallet logic with test seeds
+#[derive(Parser)]
+#[command(name = "vulcan-cli", version)]
+struct Cli {
+  /// Testnet unless asked otherwise, so a test seed's output is never
+  /// mistaken for a mainnet wallet
+  #[arg(long, global = true, value_enum, default_value_t = NetworkArg::Testnet)]
+  network: NetworkArg,
+  #[command(subcommand)]
+  command: Command,
 }
-#[derive(Debug)]
-enum ScriptType {
-  NestedSegwit,
-  Segwit,
+
+#[derive(Clone, Copy, ValueEnum)]
+enum NetworkArg {
+  Mainnet,
+  Testnet,
+  Signet,
+  Regtest,
 }
 
-fn get_path(
-  network: &Network,
-  wallet_type: &WalletType,
-  script_type: &ScriptType,
-) -> Result<bip32::DerivationPath, bip32::Error> {
-  match network {
-    Network::Bitcoin => match wallet_type {
-      WalletType::SingleSig => match script_type {
-        ScriptType::Segwit => bip32::DerivationPath::from_str("m/84h/0h/0h"),
-        ScriptType::NestedSegwit => bip32::DerivationPath::from_str("m/49h/0h/0h"),
-      },
-      WalletType::MultiSig => match script_type {
-        ScriptType::Segwit => bip32::DerivationPath::from_str("m/48h/0h/0h/2h"),
-        ScriptType::NestedSegwit => bip32::DerivationPath::from_str("m/48h/0h/0h/1h"),
-      },
-    },
-    _ => match wallet_type {
-      WalletType::SingleSig => match script_type {
-        ScriptType::Segwit => bip32::DerivationPath::from_str("m/84h/1h/0h"),
-        ScriptType::NestedSegwit => bip32::DerivationPath::from_str("m/49h/1h/0h"),
-      },
-      WalletType::MultiSig => match script_type {
-        ScriptType::Segwit => bip32::DerivationPath::from_str("m/48h/1h/0h/2h"),
-        ScriptType::NestedSegwit => bip32::DerivationPath::from_str("m/48h/1h/0h/1h"),
-      },
-    },
+impl NetworkArg {
+  fn network(self) -> Network {
+    return match self {
+      NetworkArg::Mainnet => Network::Bitcoin,
+      NetworkArg::Testnet => Network::Testnet,
+      NetworkArg::Signet => Network::Signet,
+      NetworkArg::Regtest => Network::Regtest,
+    };
   }
 }
 
-fn _convert_xpub_slip132(
-  xpub: &bip32::ExtendedPubKey,
-  network: &Network,
-  wallet_type: &WalletType,
-  script_type: &ScriptType,
-) -> String {
-  let version = match network {
-    Network::Bitcoin => match wallet_type {
-      WalletType::SingleSig => match script_type {
-        ScriptType::Segwit => Version::Zpub,
-        ScriptType::NestedSegwit => Version::Ypub,
-      },
-      WalletType::MultiSig => match script_type {
-        ScriptType::Segwit => Version::ZpubMultisig,
-        ScriptType::NestedSegwit => Version::YpubMultisig,
-      },
-    },
-    _ => match wallet_type {
-      WalletType::SingleSig => match script_type {
-        ScriptType::Segwit => Version::Vpub,
-        ScriptType::NestedSegwit => Version::Upub,
-      },
-      WalletType::MultiSig => match script_type {
-        ScriptType::Segwit => Version::VpubMultisig,
-        ScriptType::NestedSegwit => Version::UpubMultisig,
-      },
-    },
-  };
-  return convert_version(xpub.to_string(), &version).unwrap();
+#[derive(Clone, Copy, ValueEnum)]
+enum ScriptTypeArg {
+  /// P2SH-P2WPKH, BIP49
+  Nested,
+  /// P2WPKH, BIP84
+  Segwit,
+  /// P2TR, BIP86
+  Taproot,
 }
 
-fn calc_fee(psbt: &PartiallySignedTransaction) -> u64 {
-  let mut fee = 0;
-
-  for input in &psbt.inputs {
-    if let Some(utxo) = &input.witness_utxo {
-      fee = fee + utxo.value;
-    } else if let Some(tx) = &input.non_witness_utxo {
-      for utxo in &tx.output {
-        fee = fee + utxo.value;
-      }
-    }
+impl From<ScriptTypeArg> for ScriptType {
+  fn from(arg: ScriptTypeArg) -> Self {
+    return match arg {
+      ScriptTypeArg::Nested => ScriptType::NestedSegwit,
+      ScriptTypeArg::Segwit => ScriptType::Segwit,
+      ScriptTypeArg::Taproot => ScriptType::Taproot,
+    };
   }
+}
 
-  for out in &psbt.global.unsigned_tx.output {
-    fee = fee - out.value;
-  }
+#[derive(Clone, Copy, ValueEnum)]
+enum SeedQrFormat {
+  /// Every word as its 4 digit index
+  Standard,
+  /// The entropy bytes, as hex
+  Compact,
+  /// The words, script type and account path, as digits
+  Extended,
+}
 
-  return fee;
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+  /// Specter Desktop wallet JSON
+  Specter,
+  /// The generic JSON Sparrow imports
+  Sparrow,
+  /// The account's multipath descriptor
+  Descriptor,
+  /// Electrum wallet file
+  Electrum,
 }
 
-fn calc_input(psbt: &PartiallySignedTransaction) -> u64 {
-  let mut n = 0;
+#[derive(Args)]
+struct SeedArgs {
+  /// BIP39 mnemonic of a test seed
+  #[arg(long, env = "VULCAN_MNEMONIC", hide_env_values = true)]
+  mnemonic: String,
+  /// BIP39 passphrase
+  #[arg(long, default_value = "")]
+  passphrase: String,
+}
 
-  for input in &psbt.inputs {
-    if let Some(utxo) = &input.witness_utxo {
-      n = n + utxo.value;
-    } else if let Some(tx) = &input.non_witness_utxo {
-      for utxo in &tx.output {
-        n = n + utxo.value;
-      }
-    }
+impl SeedArgs {
+  fn mnemonic(&self) -> Result<Mnemonic, VulcanError> {
+    return Ok(Mnemonic::parse_in_normalized(
+      Language::English,
+      &self.mnemonic,
+    )?);
+  }
+
+  fn root(&self, network: Network) -> Result<bip32::ExtendedPrivKey, VulcanError> {
+    return get_passphrase_root(&self.mnemonic()?, &self.passphrase, network);
   }
+}
 
-  return n;
+#[derive(Args)]
+struct AccountArgs {
+  #[command(flatten)]
+  seed: SeedArgs,
+  #[arg(long, value_enum, default_value_t = ScriptTypeArg::Segwit)]
+  script_type: ScriptTypeArg,
+  /// Account path such as m/84h/0h/0h, the single-sig account of the script
+  /// type if not given
+  #[arg(long)]
+  path: Option<String>,
 }
 
-fn calc_spend_change(
-  psbt: &PartiallySignedTransaction,
-  network: &Network,
-) -> (Vec<(Address, u64)>, Vec<(Address, u64)>) {
-  let mut spend: Vec<(Address, u64)> = vec![];
-  let mut change: Vec<(Address, u64)> = vec![];
-
-  for (i, txout) in psbt.global.unsigned_tx.output.iter().enumerate() {
-    let addr = Address::from_script(&txout.script_pubkey, *network).unwrap();
-
-    // Relying on order and derivations to decide if it's change or spend. likely unreliable
-    // TODO: find a better way to check if an output is change
-    let out = psbt.outputs.get(i).unwrap();
-    if out.bip32_derivation.len() > 0 {
-      change.push((addr, txout.value));
-    } else {
-      spend.push((addr, txout.value));
-    }
+impl AccountArgs {
+  fn path(&self, network: Network) -> Result<bip32::DerivationPath, VulcanError> {
+    return match &self.path {
+      Some(path) => parse_path(path),
+      None => get_path(&network, &WalletType::SingleSig, &self.script_type.into()),
+    };
   }
 
-  return (spend, change);
+  fn wallet(&self, network: Network) -> Result<Wallet, VulcanError> {
+    return get_wallet(
+      &self.seed.root(network)?,
+      &self.path(network)?,
+      self.script_type.into(),
+      network,
+    );
+  }
 }
 
-fn get_wallet(
-  root: &bip32::ExtendedPrivKey,
-  path: &bip32::DerivationPath,
-  network: Network,
-) -> Result<Wallet<(), MemoryDatabase>, bdk::Error> {
-  let external = {
-    let send_path = path.child(bip32::ChildNumber::from_normal_idx(0)?);
-    let desc_key = root.into_descriptor_key(None, send_path)?;
-    descriptor!(wpkh(desc_key))?
-  };
-  println!("receive: {}", external.0);
-  let internal = {
-    let change_path = path.child(bip32::ChildNumber::from_normal_idx(1)?);
-    let desc_key = root.into_descriptor_key(None, change_path)?;
-    descriptor!(wpkh(desc_key))?
-  };
-  println!("change:  {}", internal.0);
-
-  return Wallet::new_offline(external, Some(internal), network, MemoryDatabase::default());
+#[derive(Subcommand)]
+enum Command {
+  /// Prints the account's fingerprint, path, xpubs, descriptors and first
+  /// addresses
+  Derive {
+    #[command(flatten)]
+    account: AccountArgs,
+    /// Addresses to print of each chain
+    #[arg(long, default_value_t = 5)]
+    addresses: u32,
+  },
+  /// Signs a binary or base64 PSBT file if it passes the device's policy
+  Sign {
+    #[command(flatten)]
+    account: AccountArgs,
+    psbt: PathBuf,
+    /// File for the signed PSBT, in the input's encoding. Printed as base64
+    /// if not given.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+  },
+  /// Prints a PSBT's fee, spend and change outputs and policy issues
+  Analyze {
+    #[command(flatten)]
+    account: AccountArgs,
+    psbt: PathBuf,
+  },
+  /// Encodes or decodes a SeedQR
+  #[command(subcommand)]
+  Seedqr(SeedQrCommand),
+  /// Prints a wallet export
+  Export {
+    #[command(flatten)]
+    account: AccountArgs,
+    #[arg(long, value_enum)]
+    format: ExportFormat,
+  },
+  /// Searches the single-sig account of the address's script type for it
+  VerifyAddress {
+    #[command(flatten)]
+    seed: SeedArgs,
+    /// Address or bitcoin: URI
+    address: String,
+    /// Addresses to search on each chain
+    #[arg(long, default_value_t = DEFAULT_GAP_LIMIT)]
+    gap_limit: u32,
+  },
 }
 
-/// [0] Version
-/// [1] Script type
-/// [2] Word count
-/// [3:3 + word_count * 4] mnemonic seed indeces as 4-digit numbers
-/// [3 + word_count * 4:] the rest is interpreted as 3-digits per unicode char. makes up the path after the "m/"
-fn parse_extended_seedqr(qr: &str) -> Result<(Vec<u16>, bip32::DerivationPath, ScriptType), ()> {
-  if qr.len() > 127 {
-    // seedqr must be less <= 127 digits to fit in a 29x29 QRCode
-    return Err(());
-  }
+#[derive(Subcommand)]
+enum SeedQrCommand {
+  /// Prints the SeedQR data of a mnemonic
+  Encode {
+    #[command(flatten)]
+    account: AccountArgs,
+    #[arg(long, value_enum, default_value_t = SeedQrFormat::Standard)]
+    format: SeedQrFormat,
+  },
+  /// Prints the mnemonic in SeedQR data
+  Decode {
+    data: String,
+    #[arg(long, value_enum, default_value_t = SeedQrFormat::Standard)]
+    format: SeedQrFormat,
+  },
+}
 
-  let version = qr[0..1].parse::<u8>().unwrap();
-  if version != 0 {
-    return Err(());
+fn main() {
+  if let Err(err) = run(Cli::parse()) {
+    eprintln!("error: {}", err);
+    process::exit(1);
   }
+}
 
-  let script = match qr[1..2].parse::<u8>().unwrap() {
-    0 => ScriptType::NestedSegwit,
-    1 => ScriptType::Segwit,
-    // 2 => ScriptType::Taproot,
-    // error
-    _ => return Err(()),
-  };
-  let seed_word_count = match qr[2..3].parse::<u8>().unwrap() {
-    0 => bip39::WordCount::Words12,
-    1 => bip39::WordCount::Words15,
-    2 => bip39::WordCount::Words18,
-    3 => bip39::WordCount::Words21,
-    4 => bip39::WordCount::Words24,
-    // error
-    _ => return Err(()),
-  };
-
-  let count = match seed_word_count {
-    bip39::WordCount::Words12 => 12,
-    bip39::WordCount::Words15 => 15,
-    bip39::WordCount::Words18 => 18,
-    bip39::WordCount::Words21 => 21,
-    bip39::WordCount::Words24 => 24,
+fn run(cli: Cli) -> CliResult {
+  let network = cli.network.network();
+
+  return match cli.command {
+    Command::Derive { account, addresses } => derive(&account, network, addresses),
+    Command::Sign {
+      account,
+      psbt,
+      output,
+    } => sign(&account, network, &psbt, output.as_ref()),
+    Command::Analyze { account, psbt } => analyze(&account, network, &psbt),
+    Command::Seedqr(SeedQrCommand::Encode { account, format }) => {
+      seedqr_encode(&account, network, format)
+    }
+    Command::Seedqr(SeedQrCommand::Decode { data, format }) => {
+      seedqr_decode(&data, network, format)
+    }
+    Command::Export { account, format } => export(&account, network, format),
+    Command::VerifyAddress {
+      seed,
+      address,
+      gap_limit,
+    } => verify_address(&seed, network, &address, gap_limit),
   };
+}
 
-  let qr = &qr[3..];
+fn derive(account: &AccountArgs, network: Network, addresses: u32) -> CliResult {
+  let wallet = account.wallet(network)?;
+  let xpub = wallet.xpub()?;
 
-  // parse mnemonic phrase
-  let end = count * 4;
-  let indeces = &qr[0..end];
-  let mut words: Vec<u16> = vec![];
-  for i in 0..count {
-    let start = i * 4;
-    let end = start + 4;
-    words.push(indeces[start..end].parse::<u16>().unwrap());
+  println!("network: {}", network);
+  println!("fingerprint: {}", wallet.fingerprint());
+  println!("path: {}", wallet.path());
+  println!("xpub: {}", xpub);
+  println!(
+    "SLIP-132 xpub: {}",
+    convert_xpub_slip132(
+      &xpub,
+      &network,
+      &WalletType::SingleSig,
+      &wallet.script_type()
+    )
+  );
+  println!("receive descriptor: {}", wallet.descriptor(false)?);
+  println!("change descriptor: {}", wallet.descriptor(true)?);
+  println!(
+    "multipath descriptor: {}",
+    wallet.keychain_descriptor(Keychain::Multipath)?
+  );
+  for index in 0..addresses {
+    println!("receive {}: {}", index, wallet.address(false, index)?);
   }
-
-  // parse path as each char as 3-digit unicode
-  let path_chars = &qr[end..];
-  let count = path_chars.len() / 3;
-  let mut path: Vec<char> = vec![];
-  for i in 0..count {
-    let start = i * 3;
-    let end = start + 3;
-    path.push(path_chars[start..end].parse::<u8>().unwrap() as char);
+  for index in 0..addresses {
+    println!("change {}: {}", index, wallet.address(true, index)?);
   }
-  let path_str = &["m/", path.iter().collect::<String>().as_str()].join("");
-  let path = bip32::DerivationPath::from_str(path_str).unwrap();
-  return Ok((words, path, script));
+  return Ok(());
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-  let secp = Secp256k1::new();
-
-  let network = Network::Testnet;
-  println!("network: {:?}", network);
-  let wallet_type = WalletType::SingleSig;
-  println!("wallet_type: {:?}", wallet_type);
-  let script_type = ScriptType::Segwit;
-  println!("script_type: {:?}", script_type);
-  let path = get_path(&network, &wallet_type, &script_type)?;
-  println!("Derivation Path: {}", path);
-
-  println!("");
-
-  // testnet
-  // let phrase = "shed rebuild nut suffer begin estate vehicle round city sudden fence spoon";
-  // signet
-  let phrase = "typical bicycle winter insane actor chat upper lazy brother club rib speed bid word caught differ fragile venture merge seed glimpse head exile success";
-  let mnemonic = bip39::Mnemonic::parse_in(bip39::Language::English, phrase)?;
-  let xkey: ExtendedKey = mnemonic.into_extended_key()?;
-  let root = xkey.into_xprv(network).unwrap();
-  println!("root xpriv: {}", root);
-
-  let xpub = bip32::ExtendedPubKey::from_private(&secp, &root);
-  println!("root xpub: {}", xpub);
-
-  println!("");
-
-  let fingerprint = xpub.fingerprint();
-  println!("Master Fingerprint: {}", fingerprint);
-
-  println!("wif: {}", root.private_key.to_wif());
-  let wif_desc = DescriptorSecretKey::SinglePriv(DescriptorSinglePriv {
-    key: root.private_key,
-    origin: Some((fingerprint, path.clone())),
-  });
-  println!("wif descriptor: {}", wif_desc);
-
-  println!("");
-
-  let xpriv_at_path = root.derive_priv(&secp, &path)?;
-  let xpub_at_path = bip32::ExtendedPubKey::from_private(&secp, &xpriv_at_path);
-  println!("xpub:                             {}", xpub_at_path);
-  // let slip132_xpub = convert_xpub_slip132(&xpub, &network, &wallet_type, &script_type);
-
-  let specter_xpub = DescriptorPublicKey::XPub(DescriptorXKey {
-    derivation_path: bip32::DerivationPath::master(),
-    origin: Some((fingerprint, path.clone())),
-    xkey: xpub_at_path,
-    wildcard: Wildcard::None,
-  });
-  println!("specter xpub: {}", specter_xpub);
-
-  let wallet = get_wallet(&root, &path, network)?;
-
-  println!("");
-
-  let serialized_psbt = "cHNidP8BAHECAAAAAVBfV44DutAVcwBhLjMDvKtwbQrmjkH+MFjFXPUqFS9/AQAAAAD9////AkZHmAAAAAAAFgAUZjVsZYVAouW7J4+EBUkSr57hq1cQJwAAAAAAABYAFBAjFi4nxS8Zk94GX+oLLSGwP5sObP4AAAABAHECAAAAAQRANrAsHCjxffzlyW5XgWuP4zvg5uyK69HEtKp36018AAAAAAD9////AhAnAAAAAAAAFgAUe5yb+brLgwDgNAd2FhiCD6ZEJ/vjbpgAAAAAABYAFLXGhIrgQeONcX9YAuONxfsqpR8rSv4AAAEBH+NumAAAAAAAFgAUtcaEiuBB441xf1gC443F+yqlHysiBgPF+tMr9FV6qyy6i32WuHnS+wB30GZgodSYsGaNPeFM7RjiaHvrVAAAgAEAAIAAAACAAQAAAAAAAAAAIgICyh7fjg8uUNT8Kzs5cHSGTKBa4QiUNOGTd7+zkoa0AqwY4mh761QAAIABAACAAAAAgAEAAAABAAAAAAA=";
+/// Reads a binary or base64 PSBT of either version. Returns the PSBT and
+/// whether it was base64.
+fn read_psbt(path: &PathBuf) -> Result<(PsbtFile, bool), Box<dyn Error>> {
+  let contents = fs::read(path)?;
+  // binary PSBTs start with the magic bytes, anything else is base64
+  let is_base64 = !contents.starts_with(b"psbt\xff");
+  let psbt = PsbtFile::deserialize(&psbt_bytes(&contents)?)?;
+  return Ok((psbt, is_base64));
+}
 
-  println!("Unsigned PSBT: {:?}\n", serialized_psbt);
+fn print_report(report: &PolicyReport) {
+  for issue in &report.warnings {
+    eprintln!("warning: {}", issue);
+  }
+  for issue in &report.blocks {
+    eprintln!("blocked: {}", issue);
+  }
+}
 
-  let mut psbt: PartiallySignedTransaction =
-    consensus::deserialize(&base64::decode(&serialized_psbt).unwrap())?;
+fn sign(
+  account: &AccountArgs,
+  network: Network,
+  path: &PathBuf,
+  output: Option<&PathBuf>,
+) -> CliResult {
+  let wallet = account.wallet(network)?;
+  let (mut file, is_base64) = read_psbt(path)?;
+
+  let report = check_psbt(file.psbt(), &wallet, &Policy::default());
+  print_report(&report);
+  if report.is_blocked() {
+    return Err("refusing to sign transaction".into());
+  }
+  if !wallet.sign(file.psbt_mut())? {
+    return Err("no inputs to sign".into());
+  }
+  file.mark_signed(EcdsaSighashType::All.into());
 
-  let fee = calc_fee(&psbt);
-  println!("fee: {} sats", fee);
+  let bytes = file.serialize();
+  match output {
+    Some(output) if is_base64 => fs::write(output, base64::encode(&bytes))?,
+    Some(output) => fs::write(output, &bytes)?,
+    None => println!("{}", base64::encode(&bytes)),
+  }
+  return Ok(());
+}
 
-  let input = calc_input(&psbt);
-  println!("input: {} sats", input);
+fn analyze(account: &AccountArgs, network: Network, path: &PathBuf) -> CliResult {
+  let wallet = account.wallet(network)?;
+  let (file, _) = read_psbt(path)?;
+  let psbt = file.psbt();
+  let summary = TransactionSummary::new(psbt, &wallet)?;
 
-  let (spend, change) = calc_spend_change(&psbt, &network);
-  let total_spend = spend.iter().fold(0, |prev, (_, value)| prev + value);
-  let total_change = change.iter().fold(0, |prev, (_, value)| prev + value);
+  println!("PSBT version: {}", file.version());
+  println!("input: {} sats", summary.input);
+  println!("output: {} sats", summary.output);
+  println!("fee: {} sats", summary.fee);
+  println!(
+    "vsize: ~{} vB, fee rate: {:.1} sat/vB",
+    summary.vsize, summary.fee_rate
+  );
+  for (address, value) in &summary.outputs.spend {
+    println!("spend: {} {} sats", address, value);
+  }
+  for (address, value) in &summary.outputs.change {
+    println!("change: {} {} sats", address, value);
+  }
+  for (address, value) in &summary.outputs.suspicious {
+    println!("NOT our change: {} {} sats", address, value);
+  }
+  for (script, value) in &summary.outputs.data {
+    println!("data: {} {} sats", script.asm(), value);
+  }
+  for (script, value) in &summary.outputs.nonstandard {
+    println!("NON-STANDARD: {} {} sats", script.asm(), value);
+  }
 
-  println!("spend: {} sats", total_spend);
-  println!("change: {} sats", total_change);
+  let report = check_psbt(psbt, &wallet, &Policy::default());
+  print_report(&report);
+  if report.is_blocked() {
+    println!("the device would refuse to sign");
+  }
+  return Ok(());
+}
 
-  let output = total_spend + total_change;
-  println!("input - fee == output: {}", input - fee == output);
+fn seedqr_encode(account: &AccountArgs, network: Network, format: SeedQrFormat) -> CliResult {
+  let mnemonic = account.seed.mnemonic()?;
+  let data = match format {
+    SeedQrFormat::Standard => encode_seedqr(&mnemonic),
+    SeedQrFormat::Compact => encode_compact_seedqr(&mnemonic)?.to_hex(),
+    SeedQrFormat::Extended => encode_extended_seedqr(
+      &mnemonic,
+      &account.script_type.into(),
+      &account.path(network)?,
+    )?,
+  };
+  println!("{}", data);
+  return Ok(());
+}
 
-  println!("change list: {:?}", change);
-  println!("spend list: {:?}", spend);
-  println!("");
+fn seedqr_decode(data: &str, network: Network, format: SeedQrFormat) -> CliResult {
+  let data = data.trim();
+  let (mnemonic, account) = match format {
+    SeedQrFormat::Standard => (decode_seedqr(data)?, None),
+    SeedQrFormat::Compact => (decode_compact_seedqr(&Vec::<u8>::from_hex(data)?)?, None),
+    SeedQrFormat::Extended => {
+      let (words, path, script_type) = parse_extended_seedqr(data)?;
+      (words_to_mnemonic(&words)?, Some((path, script_type)))
+    }
+  };
 
-  if wallet.sign(&mut psbt, SignOptions::default())? {
-    println!(
-      "Signed PSBT: {:?}",
-      base64::encode(&consensus::serialize(&psbt))
-    );
-  } else {
-    println!("Error: could not sign transaction");
+  let secp = Secp256k1::new();
+  let root = get_passphrase_root(&mnemonic, "", network)?;
+  println!("mnemonic: {}", mnemonic);
+  println!("fingerprint: {}", root.fingerprint(&secp));
+  if let Some((path, script_type)) = account {
+    let wallet = get_wallet(&root, &path, script_type, network)?;
+    println!("script type: {:?}", script_type);
+    println!("path: {}", path);
+    println!("xpub: {}", wallet.xpub()?);
   }
+  return Ok(());
+}
 
-  let seed= "136400980811079503490561095703230934105802751813017212440282184807481683015201310078178605500063";
-  // fits in a 29x29 qr code
-  let serialized_seedqr = ["014", seed, "056052104047049104047048104"].join("");
-  println!("serialized:   {}", serialized_seedqr);
-  println!(
-    "unserialized: {:?}",
-    parse_extended_seedqr(serialized_seedqr.as_str()).unwrap()
-  );
+fn export(account: &AccountArgs, network: Network, format: ExportFormat) -> CliResult {
+  let text = match format {
+    ExportFormat::Specter => specter_export(&account.wallet(network)?, "Vulcan")?,
+    ExportFormat::Sparrow => generic_export(&account.seed.root(network)?, network)?,
+    ExportFormat::Descriptor => account
+      .wallet(network)?
+      .keychain_descriptor(Keychain::Multipath)?,
+    ExportFormat::Electrum => electrum_export(&account.wallet(network)?)?,
+  };
+  println!("{}", text);
+  return Ok(());
+}
 
-  Ok(())
+/// Looks for the address like the device does: in the single-sig account of
+/// its script type, on both chains up to the gap limit.
+fn verify_address(seed: &SeedArgs, network: Network, text: &str, gap_limit: u32) -> CliResult {
+  let address = parse_address(text, network)?;
+  let script_type =
+    address_script_type(&address).ok_or("no single-sig account receives to P2PKH addresses")?;
+  let path = get_path(&network, &WalletType::SingleSig, &script_type)?;
+  let wallet = get_wallet(&seed.root(network)?, &path, script_type, network)?;
+
+  return match find_address(&wallet, &address, gap_limit)? {
+    Some(found) => {
+      let chain = if found.change { "change" } else { "receive" };
+      println!(
+        "{} is {} address {} at {}",
+        address, chain, found.index, found
+      );
+      Ok(())
+    }
+    None => Err(
+      format!(
+        "{} not found in the first {} addresses of either chain",
+        address, gap_limit
+      )
+      .into(),
+    ),
+  };
 }
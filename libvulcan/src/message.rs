@@ -0,0 +1,442 @@
+//! Message signing: BIP137 signatures for P2PKH, P2SH-P2WPKH and P2WPKH
+//! addresses, BIP322 simple signatures for P2WPKH and P2TR addresses.
+//! https://github.com/bitcoin/bips/blob/master/bip-0137.mediawiki
+//! https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+
+use crate::{
+  base64,
+  error::VulcanError,
+  path::{coin_type, parse_path},
+};
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+};
+use bitcoin::{
+  blockdata::{opcodes, script::Builder},
+  consensus,
+  hashes::{sha256, Hash, HashEngine},
+  secp256k1::{
+    self,
+    ecdsa::{RecoverableSignature, RecoveryId},
+    KeyPair, Message, Secp256k1, XOnlyPublicKey,
+  },
+  util::{
+    address::AddressType,
+    bip32,
+    misc::signed_msg_hash,
+    schnorr::{SchnorrSig, TapTweak},
+    sighash::{Prevouts, SighashCache},
+  },
+  Address, EcdsaSig, EcdsaSighashType, Network, OutPoint, PublicKey, SchnorrSighashType, Script,
+  Transaction, TxIn, TxOut, Witness,
+};
+
+/// Coldcard's limit for messages in signing files
+const MAX_MESSAGE_LEN: usize = 240;
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+
+/// The address type a message is signed for, named like in Coldcard's
+/// signing files.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum MessageAddressType {
+  P2pkh,
+  P2shP2wpkh,
+  P2wpkh,
+  P2tr,
+}
+
+impl MessageAddressType {
+  pub fn from_name(name: &str) -> Result<Self, VulcanError> {
+    return match name {
+      "p2pkh" => Ok(MessageAddressType::P2pkh),
+      "p2sh-p2wpkh" | "p2wpkh-p2sh" => Ok(MessageAddressType::P2shP2wpkh),
+      "p2wpkh" => Ok(MessageAddressType::P2wpkh),
+      "p2tr" => Ok(MessageAddressType::P2tr),
+      _ => Err(VulcanError::UnsupportedMessageFormat),
+    };
+  }
+
+  pub fn name(&self) -> &'static str {
+    return match self {
+      MessageAddressType::P2pkh => "p2pkh",
+      MessageAddressType::P2shP2wpkh => "p2sh-p2wpkh",
+      MessageAddressType::P2wpkh => "p2wpkh",
+      MessageAddressType::P2tr => "p2tr",
+    };
+  }
+
+  /// P2WPKH addresses sign with either format, P2TR ones only with BIP322
+  /// and the others only with BIP137.
+  pub fn supports(&self, format: SignatureFormat) -> bool {
+    return matches!(
+      (self, format),
+      (MessageAddressType::P2wpkh, _)
+        | (MessageAddressType::P2tr, SignatureFormat::Bip322)
+        | (
+          MessageAddressType::P2pkh | MessageAddressType::P2shP2wpkh,
+          SignatureFormat::Bip137
+        )
+    );
+  }
+
+  /// The address type the purpose of `path` implies: BIP49, BIP84 and BIP86
+  /// paths are segwit and taproot, everything else P2PKH.
+  pub fn from_path(path: &bip32::DerivationPath) -> Self {
+    return match path.as_ref().first() {
+      Some(bip32::ChildNumber::Hardened { index: 49 }) => MessageAddressType::P2shP2wpkh,
+      Some(bip32::ChildNumber::Hardened { index: 84 }) => MessageAddressType::P2wpkh,
+      Some(bip32::ChildNumber::Hardened { index: 86 }) => MessageAddressType::P2tr,
+      _ => MessageAddressType::P2pkh,
+    };
+  }
+
+  fn address<C: secp256k1::Verification>(
+    &self,
+    secp: &Secp256k1<C>,
+    pubkey: &PublicKey,
+    network: Network,
+  ) -> Result<Address, VulcanError> {
+    return match self {
+      MessageAddressType::P2pkh => Ok(Address::p2pkh(pubkey, network)),
+      MessageAddressType::P2shP2wpkh => {
+        Address::p2shwpkh(pubkey, network).map_err(|_| VulcanError::NonStandardScript)
+      }
+      MessageAddressType::P2wpkh => {
+        Address::p2wpkh(pubkey, network).map_err(|_| VulcanError::NonStandardScript)
+      }
+      MessageAddressType::P2tr => Ok(Address::p2tr(
+        secp,
+        XOnlyPublicKey::from(pubkey.inner),
+        None,
+        network,
+      )),
+    };
+  }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SignatureFormat {
+  /// 65 byte recoverable ECDSA signature
+  Bip137,
+  /// the witness of the BIP322 `to_sign` transaction
+  Bip322,
+}
+
+impl SignatureFormat {
+  pub fn from_name(name: &str) -> Result<Self, VulcanError> {
+    return match name {
+      "bip137" => Ok(SignatureFormat::Bip137),
+      "bip322" => Ok(SignatureFormat::Bip322),
+      _ => Err(VulcanError::UnsupportedMessageFormat),
+    };
+  }
+
+  pub fn name(&self) -> &'static str {
+    return match self {
+      SignatureFormat::Bip137 => "bip137",
+      SignatureFormat::Bip322 => "bip322",
+    };
+  }
+}
+
+/// A message to sign with the key at `path`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageRequest {
+  pub message: String,
+  pub path: bip32::DerivationPath,
+  pub address_type: MessageAddressType,
+  pub format: SignatureFormat,
+}
+
+impl MessageRequest {
+  /// Signs P2TR addresses with BIP322 and everything else with BIP137, which
+  /// is what most verifiers still expect.
+  pub fn new(
+    message: &str,
+    path: bip32::DerivationPath,
+    address_type: MessageAddressType,
+  ) -> Result<Self, VulcanError> {
+    check_message(message)?;
+    let format = match address_type {
+      MessageAddressType::P2tr => SignatureFormat::Bip322,
+      _ => SignatureFormat::Bip137,
+    };
+    return Ok(MessageRequest {
+      message: message.to_string(),
+      path,
+      address_type,
+      format,
+    });
+  }
+}
+
+/// A signed message and the address it was signed for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedMessage {
+  pub message: String,
+  pub address: Address,
+  /// base64 signature
+  pub signature: String,
+}
+
+impl SignedMessage {
+  /// The armored text Coldcard writes to the SD card.
+  pub fn to_armor(&self) -> String {
+    return format!(
+      "-----BEGIN BITCOIN SIGNED MESSAGE-----\n{}\n-----BEGIN BITCOIN SIGNATURE-----\n{}\n{}\n-----END BITCOIN SIGNATURE-----\n",
+      self.message, self.address, self.signature
+    );
+  }
+}
+
+/// Parses a Coldcard message signing file: the message on the first line,
+/// then optionally the derivation path and the address type. Without a path
+/// the first receive address of the BIP44 account is used, and without an
+/// address type it follows the path's purpose. A fourth line, which Coldcard
+/// doesn't write, picks `bip137` or `bip322` where the address type allows
+/// both.
+pub fn parse_message_request(text: &str, network: Network) -> Result<MessageRequest, VulcanError> {
+  let mut lines = text.lines().map(|line| line.trim());
+  let message = lines.next().unwrap_or("");
+
+  let path = match lines.next() {
+    Some(path) if !path.is_empty() => parse_path(path)?,
+    _ => parse_path(&format!("m/44'/{}'/0'/0/0", coin_type(&network)))?,
+  };
+  let address_type = match lines.next() {
+    Some(name) if !name.is_empty() => MessageAddressType::from_name(&name.to_lowercase())?,
+    _ => MessageAddressType::from_path(&path),
+  };
+  let format = match lines.next() {
+    Some(name) if !name.is_empty() => Some(SignatureFormat::from_name(&name.to_lowercase())?),
+    _ => None,
+  };
+  if lines.any(|line| !line.is_empty()) {
+    return Err(VulcanError::BadMessage);
+  }
+
+  let mut request = MessageRequest::new(message, path, address_type)?;
+  if let Some(format) = format {
+    if !address_type.supports(format) {
+      return Err(VulcanError::UnsupportedMessageFormat);
+    }
+    request.format = format;
+  }
+  return Ok(request);
+}
+
+/// Signs the request with the key at its path.
+pub fn sign_message(
+  root: &bip32::ExtendedPrivKey,
+  request: &MessageRequest,
+) -> Result<SignedMessage, VulcanError> {
+  check_message(&request.message)?;
+  let secp = Secp256k1::new();
+  let key = root.derive_priv(&secp, &request.path)?.private_key;
+  let pubkey = PublicKey::new(secp256k1::PublicKey::from_secret_key(&secp, &key));
+  let address = request.address_type.address(&secp, &pubkey, root.network)?;
+
+  let signature = match (request.format, request.address_type) {
+    (SignatureFormat::Bip137, MessageAddressType::P2tr) => {
+      return Err(VulcanError::UnsupportedMessageFormat)
+    }
+    (SignatureFormat::Bip137, address_type) => {
+      let header = match address_type {
+        MessageAddressType::P2pkh => 31,
+        MessageAddressType::P2shP2wpkh => 35,
+        _ => 39,
+      };
+      let msg = Message::from_slice(&signed_msg_hash(&request.message))
+        .map_err(|_| VulcanError::BadMessage)?;
+      let (recovery_id, sig) = secp.sign_ecdsa_recoverable(&msg, &key).serialize_compact();
+      let mut bytes = vec![header + recovery_id.to_i32() as u8];
+      bytes.extend_from_slice(&sig);
+      bytes
+    }
+    (SignatureFormat::Bip322, MessageAddressType::P2wpkh) => {
+      let to_sign = to_sign(&to_spend(&address.script_pubkey(), &request.message));
+      let script_code = Address::p2pkh(&pubkey, root.network).script_pubkey();
+      let sighash = SighashCache::new(&to_sign)
+        .segwit_signature_hash(0, &script_code, 0, EcdsaSighashType::All)
+        .map_err(|_| VulcanError::BadMessage)?;
+      let msg = Message::from_slice(&sighash[..]).map_err(|_| VulcanError::BadMessage)?;
+      // low R like Bitcoin Core, so the signatures match its vectors
+      let sig = EcdsaSig::sighash_all(secp.sign_ecdsa_low_r(&msg, &key));
+      consensus::serialize(&Witness::from_vec(vec![sig.to_vec(), pubkey.to_bytes()]))
+    }
+    (SignatureFormat::Bip322, MessageAddressType::P2tr) => {
+      let to_spend = to_spend(&address.script_pubkey(), &request.message);
+      let to_sign = to_sign(&to_spend);
+      let sighash = SighashCache::new(&to_sign)
+        .taproot_key_spend_signature_hash(
+          0,
+          &Prevouts::All(&to_spend.output),
+          SchnorrSighashType::Default,
+        )
+        .map_err(|_| VulcanError::BadMessage)?;
+      let msg = Message::from_slice(&sighash[..]).map_err(|_| VulcanError::BadMessage)?;
+      let keypair = KeyPair::from_secret_key(&secp, key)
+        .tap_tweak(&secp, None)
+        .into_inner();
+      let sig = secp.sign_schnorr_no_aux_rand(&msg, &keypair);
+      consensus::serialize(&Witness::from_vec(vec![sig.as_ref().to_vec()]))
+    }
+    (SignatureFormat::Bip322, _) => return Err(VulcanError::UnsupportedMessageFormat),
+  };
+
+  return Ok(SignedMessage {
+    message: request.message.clone(),
+    address,
+    signature: base64::encode(&signature),
+  });
+}
+
+/// Checks a BIP137 or BIP322 simple signature of `message` by `address`.
+/// BIP137 headers don't say the same thing in every wallet, so the
+/// recovered key is checked against the address itself.
+pub fn verify_message(
+  address: &Address,
+  message: &str,
+  signature: &str,
+) -> Result<bool, VulcanError> {
+  let secp = Secp256k1::verification_only();
+  let bytes = base64::decode(signature.trim()).ok_or(VulcanError::BadSignature)?;
+
+  if bytes.len() == 65 && (27..=42).contains(&bytes[0]) {
+    let header = bytes[0] - 27;
+    let recovery_id =
+      RecoveryId::from_i32((header & 3) as i32).map_err(|_| VulcanError::BadSignature)?;
+    let sig = RecoverableSignature::from_compact(&bytes[1..], recovery_id)
+      .map_err(|_| VulcanError::BadSignature)?;
+    let msg =
+      Message::from_slice(&signed_msg_hash(message)).map_err(|_| VulcanError::BadSignature)?;
+    let pubkey = match secp.recover_ecdsa(&msg, &sig) {
+      Ok(pubkey) => PublicKey {
+        compressed: header >= 4,
+        inner: pubkey,
+      },
+      Err(_) => return Ok(false),
+    };
+    let address_type = match address.address_type() {
+      Some(AddressType::P2pkh) => MessageAddressType::P2pkh,
+      Some(AddressType::P2sh) => MessageAddressType::P2shP2wpkh,
+      Some(AddressType::P2wpkh) => MessageAddressType::P2wpkh,
+      _ => return Err(VulcanError::UnsupportedMessageFormat),
+    };
+    return match address_type.address(&secp, &pubkey, address.network) {
+      Ok(recovered) => Ok(recovered == *address),
+      Err(_) => Ok(false),
+    };
+  }
+
+  let witness: Witness = consensus::deserialize(&bytes).map_err(|_| VulcanError::BadSignature)?;
+  let stack = witness.to_vec();
+  let to_spend = to_spend(&address.script_pubkey(), message);
+  let to_sign = to_sign(&to_spend);
+  match address.address_type() {
+    Some(AddressType::P2wpkh) => {
+      if stack.len() != 2 {
+        return Ok(false);
+      }
+      let (sig, pubkey) = match (
+        EcdsaSig::from_slice(&stack[0]),
+        PublicKey::from_slice(&stack[1]),
+      ) {
+        (Ok(sig), Ok(pubkey)) if sig.hash_ty == EcdsaSighashType::All => (sig, pubkey),
+        _ => return Ok(false),
+      };
+      match Address::p2wpkh(&pubkey, address.network) {
+        Ok(owner) if owner == *address => {}
+        _ => return Ok(false),
+      }
+      let script_code = Address::p2pkh(&pubkey, address.network).script_pubkey();
+      let sighash = SighashCache::new(&to_sign)
+        .segwit_signature_hash(0, &script_code, 0, EcdsaSighashType::All)
+        .map_err(|_| VulcanError::BadSignature)?;
+      let msg = Message::from_slice(&sighash[..]).map_err(|_| VulcanError::BadSignature)?;
+      return Ok(secp.verify_ecdsa(&msg, &sig.sig, &pubkey.inner).is_ok());
+    }
+    Some(AddressType::P2tr) => {
+      if stack.len() != 1 {
+        return Ok(false);
+      }
+      let sig = match SchnorrSig::from_slice(&stack[0]) {
+        Ok(sig) => sig,
+        Err(_) => return Ok(false),
+      };
+      let output_key = XOnlyPublicKey::from_slice(&address.script_pubkey()[2..])
+        .map_err(|_| VulcanError::NonStandardScript)?;
+      let sighash = SighashCache::new(&to_sign)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&to_spend.output), sig.hash_ty)
+        .map_err(|_| VulcanError::BadSignature)?;
+      let msg = Message::from_slice(&sighash[..]).map_err(|_| VulcanError::BadSignature)?;
+      return Ok(secp.verify_schnorr(&sig.sig, &msg, &output_key).is_ok());
+    }
+    _ => return Err(VulcanError::UnsupportedMessageFormat),
+  }
+}
+
+/// The BIP322 tagged hash of `message`.
+pub fn bip322_message_hash(message: &str) -> sha256::Hash {
+  let tag = sha256::Hash::hash(BIP322_TAG);
+  let mut engine = sha256::Hash::engine();
+  engine.input(&tag);
+  engine.input(&tag);
+  engine.input(message.as_bytes());
+  return sha256::Hash::from_engine(engine);
+}
+
+/// The virtual transaction that creates an output to the signing address.
+fn to_spend(script_pubkey: &Script, message: &str) -> Transaction {
+  return Transaction {
+    version: 0,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::null(),
+      script_sig: Builder::new()
+        .push_int(0)
+        .push_slice(&bip322_message_hash(message))
+        .into_script(),
+      sequence: 0,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 0,
+      script_pubkey: script_pubkey.clone(),
+    }],
+  };
+}
+
+/// The virtual transaction whose witness is the signature.
+fn to_sign(to_spend: &Transaction) -> Transaction {
+  return Transaction {
+    version: 0,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::new(to_spend.txid(), 0),
+      script_sig: Script::new(),
+      sequence: 0,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 0,
+      script_pubkey: Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .into_script(),
+    }],
+  };
+}
+
+/// Messages have to survive being written on one line of a text file.
+fn check_message(message: &str) -> Result<(), VulcanError> {
+  if message.is_empty()
+    || message.len() > MAX_MESSAGE_LEN
+    || message.trim() != message
+    || !message.bytes().all(|b| (0x20..0x7f).contains(&b))
+  {
+    return Err(VulcanError::BadMessage);
+  }
+  return Ok(());
+}
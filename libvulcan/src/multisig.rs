@@ -0,0 +1,340 @@
+use crate::{
+  descriptor::{account_descriptor, Keychain},
+  error::VulcanError,
+  keys::parse_xpub,
+  path::parse_path,
+  psbt::{spent_output, Account},
+  types::{ScriptType, WalletType},
+  wallet::{account_child, sign_segwit_v0},
+};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use bitcoin::{
+  blockdata::{opcodes, script::Builder},
+  secp256k1::{self, Secp256k1},
+  util::{
+    bip32,
+    psbt::{Input, PartiallySignedTransaction},
+    sighash::SighashCache,
+  },
+  Address, Network, PublicKey, Script, TxOut,
+};
+use core::str::FromStr;
+
+/// Largest quorum accepted, the same cap other hardware signers use.
+pub const MAX_COSIGNERS: usize = 15;
+
+/// A multisig participant: `[fingerprint/path]xpub`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cosigner {
+  pub fingerprint: bip32::Fingerprint,
+  pub path: bip32::DerivationPath,
+  pub xpub: bip32::ExtendedPubKey,
+}
+
+impl Cosigner {
+  /// Public key at `<xpub>/<change>/<index>`
+  fn derive(
+    &self,
+    secp: &Secp256k1<secp256k1::All>,
+    change: bool,
+    index: u32,
+  ) -> Result<PublicKey, VulcanError> {
+    let child = self.xpub.derive_pub(
+      secp,
+      &[
+        bip32::ChildNumber::from_normal_idx(if change { 1 } else { 0 })?,
+        bip32::ChildNumber::from_normal_idx(index)?,
+      ],
+    )?;
+    return Ok(PublicKey::new(child.public_key));
+  }
+}
+
+impl FromStr for Cosigner {
+  type Err = VulcanError;
+
+  /// Parses a key expression such as `[d34db33f/48h/0h/0h/2h]xpub...`.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    let rest = s.strip_prefix('[').ok_or(VulcanError::BadDescriptor)?;
+    let end = rest.find(']').ok_or(VulcanError::BadDescriptor)?;
+    let (origin, xpub) = (&rest[..end], &rest[end + 1..]);
+
+    let (fingerprint, path) = match origin.find('/') {
+      Some(i) => (&origin[..i], &origin[i..]),
+      None => (origin, ""),
+    };
+    if fingerprint.len() != 8 {
+      return Err(VulcanError::BadDescriptor);
+    }
+    let fingerprint =
+      bip32::Fingerprint::from_str(fingerprint).map_err(|_| VulcanError::BadDescriptor)?;
+    let path = parse_path(&format!("m{}", path))?;
+    let xpub = parse_xpub(xpub)?;
+
+    return Ok(Cosigner {
+      fingerprint,
+      path,
+      xpub,
+    });
+  }
+}
+
+/// An M-of-N `sortedmulti` account where one of the keys is ours.
+pub struct MultisigWallet {
+  secp: Secp256k1<secp256k1::All>,
+  root: bip32::ExtendedPrivKey,
+  fingerprint: bip32::Fingerprint,
+  path: bip32::DerivationPath,
+  script_type: ScriptType,
+  network: Network,
+  threshold: usize,
+  cosigners: Vec<Cosigner>,
+}
+
+/// Builds a multisig account from our root key at `path` and the imported
+/// cosigner keys, which must include our own.
+pub fn get_multisig_wallet(
+  root: &bip32::ExtendedPrivKey,
+  path: &bip32::DerivationPath,
+  script_type: ScriptType,
+  network: Network,
+  threshold: usize,
+  cosigners: &[Cosigner],
+) -> Result<MultisigWallet, VulcanError> {
+  if script_type == ScriptType::Taproot {
+    return Err(VulcanError::UnsupportedScriptType(2));
+  }
+
+  let secp = Secp256k1::new();
+  let fingerprint = root.fingerprint(&secp);
+  let xpriv = root.derive_priv(&secp, path)?;
+  let xpub = bip32::ExtendedPubKey::from_priv(&secp, &xpriv);
+
+  let mut keys: Vec<Cosigner> = Vec::with_capacity(cosigners.len());
+  for cosigner in cosigners {
+    // xpubs only tell mainnet from everything else
+    if (cosigner.xpub.network == Network::Bitcoin) != (network == Network::Bitcoin) {
+      return Err(VulcanError::WrongNetwork);
+    }
+    if keys.iter().any(|key| key.xpub == cosigner.xpub) {
+      return Err(VulcanError::BadMultisig);
+    }
+    if cosigner.xpub == xpub {
+      // coordinators may leave out or mangle our origin, we know the real one
+      keys.push(Cosigner {
+        fingerprint,
+        path: path.clone(),
+        xpub,
+      });
+      continue;
+    }
+    keys.push(cosigner.clone());
+  }
+  // the path may be written differently, the key is what counts
+  if !keys.iter().any(|key| key.xpub == xpub) {
+    return Err(VulcanError::NotInQuorum);
+  }
+
+  if keys.len() > MAX_COSIGNERS || threshold == 0 || threshold > keys.len() {
+    return Err(VulcanError::BadMultisig);
+  }
+
+  return Ok(MultisigWallet {
+    secp,
+    root: *root,
+    fingerprint,
+    path: path.clone(),
+    script_type,
+    network,
+    threshold,
+    cosigners: keys,
+  });
+}
+
+impl MultisigWallet {
+  /// Master key fingerprint
+  pub fn fingerprint(&self) -> bip32::Fingerprint {
+    return self.fingerprint;
+  }
+
+  /// Our account derivation path
+  pub fn path(&self) -> &bip32::DerivationPath {
+    return &self.path;
+  }
+
+  pub fn script_type(&self) -> ScriptType {
+    return self.script_type;
+  }
+
+  pub fn network(&self) -> Network {
+    return self.network;
+  }
+
+  /// Signatures needed to spend
+  pub fn threshold(&self) -> usize {
+    return self.threshold;
+  }
+
+  /// Every key of the quorum, ours included
+  pub fn cosigners(&self) -> &[Cosigner] {
+    return &self.cosigners;
+  }
+
+  /// Output descriptor for the receive or change chain, with checksum.
+  pub fn descriptor(&self, change: bool) -> Result<String, VulcanError> {
+    return self.keychain_descriptor(Keychain::from_change(change));
+  }
+
+  /// Output descriptor for one or, with [`Keychain::Multipath`], both chains.
+  pub fn keychain_descriptor(&self, keychain: Keychain) -> Result<String, VulcanError> {
+    let keys: Vec<(
+      bip32::Fingerprint,
+      bip32::DerivationPath,
+      bip32::ExtendedPubKey,
+    )> = self
+      .cosigners
+      .iter()
+      .map(|cosigner| (cosigner.fingerprint, cosigner.path.clone(), cosigner.xpub))
+      .collect();
+    return account_descriptor(
+      &WalletType::MultiSig,
+      &self.script_type,
+      self.threshold,
+      &keys,
+      keychain,
+    );
+  }
+
+  /// The `sortedmulti` witness script at `<change>/<index>`
+  pub fn witness_script(&self, change: bool, index: u32) -> Result<Script, VulcanError> {
+    let mut pubkeys: Vec<PublicKey> = Vec::with_capacity(self.cosigners.len());
+    for cosigner in &self.cosigners {
+      pubkeys.push(cosigner.derive(&self.secp, change, index)?);
+    }
+    pubkeys.sort_by_key(|k| k.inner.serialize());
+
+    let mut builder = Builder::new().push_int(self.threshold as i64);
+    for pubkey in &pubkeys {
+      builder = builder.push_key(pubkey);
+    }
+    return Ok(
+      builder
+        .push_int(pubkeys.len() as i64)
+        .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+        .into_script(),
+    );
+  }
+
+  /// Address at `<change>/<index>`
+  pub fn address(&self, change: bool, index: u32) -> Result<Address, VulcanError> {
+    let witness_script = self.witness_script(change, index)?;
+    match self.script_type {
+      ScriptType::NestedSegwit => return Ok(Address::p2shwsh(&witness_script, self.network)),
+      ScriptType::Segwit => return Ok(Address::p2wsh(&witness_script, self.network)),
+      ScriptType::Taproot => return Err(VulcanError::UnsupportedScriptType(2)),
+    }
+  }
+
+  /// Finds `(change, index)` of a script from our key's derivation, then
+  /// checks the script against the full quorum.
+  fn derivation_of(
+    &self,
+    derivations: &BTreeMap<secp256k1::PublicKey, bip32::KeySource>,
+  ) -> Option<(bool, u32)> {
+    return derivations
+      .values()
+      .filter(|(fingerprint, _)| *fingerprint == self.fingerprint)
+      .find_map(|(_, path)| account_child(&self.path, path));
+  }
+
+  /// The witness script for the input if it spends from this quorum.
+  fn input_witness_script(
+    &self,
+    utxo: &TxOut,
+    input: &Input,
+  ) -> Result<Option<Script>, VulcanError> {
+    let (change, index) = match self.derivation_of(&input.bip32_derivation) {
+      Some(child) => child,
+      None => return Ok(None),
+    };
+    if self.address(change, index)?.script_pubkey() != utxo.script_pubkey {
+      return Ok(None);
+    }
+    return Ok(Some(self.witness_script(change, index)?));
+  }
+
+  /// Adds our signature to every input that spends from this quorum.
+  ///
+  /// Returns whether at least one signature was added.
+  pub fn sign(&self, psbt: &mut PartiallySignedTransaction) -> Result<bool, VulcanError> {
+    if psbt.inputs.len() != psbt.unsigned_tx.input.len() {
+      return Err(VulcanError::InconsistentPsbt);
+    }
+
+    let tx = psbt.unsigned_tx.clone();
+    let mut cache = SighashCache::new(&tx);
+    let mut signed = false;
+
+    for (i, input) in psbt.inputs.iter_mut().enumerate() {
+      let utxo = match spent_output(&tx, i, input) {
+        Ok(utxo) => utxo,
+        Err(_) => continue,
+      };
+      let witness_script = match self.input_witness_script(&utxo, input)? {
+        Some(script) => script,
+        None => continue,
+      };
+
+      let keys: Vec<(secp256k1::PublicKey, bip32::DerivationPath)> = input
+        .bip32_derivation
+        .iter()
+        .filter(|(_, (fingerprint, _))| *fingerprint == self.fingerprint)
+        .map(|(pubkey, (_, path))| (*pubkey, path.clone()))
+        .collect();
+
+      for (pubkey, path) in keys {
+        let xpriv = self.root.derive_priv(&self.secp, &path)?;
+        if secp256k1::PublicKey::from_secret_key(&self.secp, &xpriv.private_key) != pubkey {
+          continue;
+        }
+        let sig = sign_segwit_v0(
+          &self.secp,
+          &mut cache,
+          i,
+          &witness_script,
+          utxo.value,
+          &xpriv,
+        )?;
+        input.partial_sigs.insert(PublicKey::new(pubkey), sig);
+        signed = true;
+      }
+    }
+
+    return Ok(signed);
+  }
+}
+
+/// Change is re-derived from every cosigner key, so a PSBT can't pass off
+/// an output that leaves the quorum.
+impl Account for MultisigWallet {
+  fn fingerprint(&self) -> bip32::Fingerprint {
+    return self.fingerprint;
+  }
+
+  fn network(&self) -> Network {
+    return self.network;
+  }
+
+  fn path(&self) -> &bip32::DerivationPath {
+    return &self.path;
+  }
+
+  fn child(&self, path: &bip32::DerivationPath) -> Option<(bool, u32)> {
+    return account_child(&self.path, path);
+  }
+
+  fn script_pubkey(&self, change: bool, index: u32) -> Result<Script, VulcanError> {
+    return Ok(self.address(change, index)?.script_pubkey());
+  }
+}
@@ -0,0 +1,44 @@
+use crate::{
+  error::VulcanError,
+  types::{ScriptType, WalletType},
+};
+use alloc::format;
+use bitcoin::{util::bip32, Network};
+use core::str::FromStr;
+
+/// Parses a derivation path such as `m/84h/0h/0h`.
+pub fn parse_path(path: &str) -> Result<bip32::DerivationPath, VulcanError> {
+  return bip32::DerivationPath::from_str(path).map_err(|_| VulcanError::BadDerivationPath);
+}
+
+/// The BIP44 coin type of a network: `0h` on mainnet, `1h` on every test
+/// network.
+pub fn coin_type(network: &Network) -> u32 {
+  return match network {
+    Network::Bitcoin => 0,
+    _ => 1,
+  };
+}
+
+/// Returns the account derivation path for a wallet.
+///
+/// Single-sig follows BIP49/BIP84/BIP86, multisig follows BIP48. Every
+/// network other than mainnet uses coin type `1h`.
+pub fn get_path(
+  network: &Network,
+  wallet_type: &WalletType,
+  script_type: &ScriptType,
+) -> Result<bip32::DerivationPath, VulcanError> {
+  let coin = coin_type(network);
+  return match (wallet_type, script_type) {
+    (WalletType::SingleSig, ScriptType::Segwit) => parse_path(&format!("m/84h/{}h/0h", coin)),
+    (WalletType::SingleSig, ScriptType::NestedSegwit) => parse_path(&format!("m/49h/{}h/0h", coin)),
+    (WalletType::SingleSig, ScriptType::Taproot) => parse_path(&format!("m/86h/{}h/0h", coin)),
+    (WalletType::MultiSig, ScriptType::Segwit) => parse_path(&format!("m/48h/{}h/0h/2h", coin)),
+    (WalletType::MultiSig, ScriptType::NestedSegwit) => {
+      parse_path(&format!("m/48h/{}h/0h/1h", coin))
+    }
+    // BIP48 has no taproot script type yet
+    (WalletType::MultiSig, ScriptType::Taproot) => Err(VulcanError::UnsupportedScriptType(2)),
+  };
+}
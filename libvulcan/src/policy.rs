@@ -0,0 +1,288 @@
+use crate::{
+  error::VulcanError,
+  path::coin_type,
+  psbt::{calc_fee, classify_output, estimate_vsize, Account, OutputKind},
+};
+use alloc::{vec, vec::Vec};
+use bitcoin::{
+  util::{bip32, psbt::PartiallySignedTransaction},
+  Address, Network,
+};
+use core::fmt;
+
+/// Limits the policy checker holds a PSBT to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+  /// Fee rates above this are shown as a warning, in sat/vB
+  pub warn_fee_rate: f64,
+  /// Fee rates above this can't be signed, in sat/vB
+  pub max_fee_rate: f64,
+  /// Fees above this share of the amount sent are shown as a warning, in %
+  pub warn_fee_percent: u64,
+  /// Fees above this share of the amount sent can't be signed, in %
+  pub max_fee_percent: u64,
+  /// Fees above this can't be signed whatever the rate, in sats
+  pub max_fee_sats: u64,
+  /// Segwit v0 inputs without the full previous transaction can't be
+  /// signed, otherwise they're shown as a warning
+  pub require_non_witness_utxo: bool,
+}
+
+impl Default for Policy {
+  fn default() -> Self {
+    return Policy {
+      warn_fee_rate: 100.0,
+      max_fee_rate: 1000.0,
+      warn_fee_percent: 10,
+      max_fee_percent: 50,
+      max_fee_sats: 10_000_000,
+      require_non_witness_utxo: true,
+    };
+  }
+}
+
+/// Something about a PSBT the user should know before signing it. Indexes
+/// refer to inputs or outputs of the unsigned transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyIssue {
+  /// The PSBT can't be checked at all
+  Invalid(VulcanError),
+  /// Input asks for a sighash type other than ALL
+  NonDefaultSighash(usize, u32),
+  /// Segwit v0 input without the full previous transaction. Its amount is
+  /// unverified, which allows the fee overpayment attack.
+  MissingNonWitnessUtxo(usize),
+  /// Input with no derivation for our fingerprint
+  ForeignInput(usize),
+  /// Global xpub, by fingerprint, encoded for another network
+  WrongNetworkXpub(bip32::Fingerprint),
+  /// Input whose UTXO our key derives with another network's coin type
+  WrongNetworkInput(usize),
+  /// None of the inputs are ours
+  NothingToSign,
+  /// Fee rate in sat/vB
+  HighFeeRate(u64),
+  /// Fee rate in sat/vB
+  AbsurdFeeRate(u64),
+  /// Fee as a share of the amount sent, in %
+  HighFee(u64),
+  /// Fee as a share of the amount sent, in %
+  AbsurdFee(u64),
+  /// Fee in sats
+  AbsurdFeeAmount(u64),
+  /// nLockTime is a unix timestamp instead of a block height
+  TimestampLockTime(u32),
+  /// nSequence enables a relative timelock
+  UnusualSequence(usize, u32),
+  /// OP_RETURN output
+  DataOutput(usize),
+  /// Output script with no address representation
+  NonStandardOutput(usize),
+  /// Output claims to be our change but doesn't re-derive
+  SuspiciousChange(usize),
+}
+
+impl fmt::Display for PolicyIssue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PolicyIssue::Invalid(err) => write!(f, "invalid PSBT: {}", err),
+      PolicyIssue::NonDefaultSighash(i, ty) => write!(f, "input {} sighash {:#x}", i, ty),
+      PolicyIssue::MissingNonWitnessUtxo(i) => write!(f, "input {} amount unverified", i),
+      PolicyIssue::ForeignInput(i) => write!(f, "input {} isn't ours", i),
+      PolicyIssue::WrongNetworkXpub(fp) => write!(f, "xpub {} wrong network", fp),
+      PolicyIssue::WrongNetworkInput(i) => write!(f, "input {} wrong network", i),
+      PolicyIssue::NothingToSign => write!(f, "no inputs to sign"),
+      PolicyIssue::HighFeeRate(rate) => write!(f, "high fee rate {} sat/vB", rate),
+      PolicyIssue::AbsurdFeeRate(rate) => write!(f, "absurd fee rate {} sat/vB", rate),
+      PolicyIssue::HighFee(percent) => write!(f, "fee is {}% of amount", percent),
+      PolicyIssue::AbsurdFee(percent) => write!(f, "absurd fee {}% of amount", percent),
+      PolicyIssue::AbsurdFeeAmount(fee) => write!(f, "absurd fee {} sats", fee),
+      PolicyIssue::TimestampLockTime(time) => write!(f, "locked until time {}", time),
+      PolicyIssue::UnusualSequence(i, seq) => write!(f, "input {} sequence {:#x}", i, seq),
+      PolicyIssue::DataOutput(i) => write!(f, "output {} is OP_RETURN", i),
+      PolicyIssue::NonStandardOutput(i) => write!(f, "output {} non-standard", i),
+      PolicyIssue::SuspiciousChange(i) => write!(f, "output {} fake change", i),
+    }
+  }
+}
+
+/// Result of checking a PSBT. Anything in `blocks` means it must not be
+/// signed, `warnings` need the user's approval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyReport {
+  pub warnings: Vec<PolicyIssue>,
+  pub blocks: Vec<PolicyIssue>,
+}
+
+impl PolicyReport {
+  pub fn is_blocked(&self) -> bool {
+    return !self.blocks.is_empty();
+  }
+}
+
+// BIP68: a set bit 31 disables the relative timelock, the low 16 bits are
+// its length
+const SEQUENCE_DISABLE_FLAG: u32 = 1 << 31;
+const SEQUENCE_LOCKTIME_MASK: u32 = 0xffff;
+const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+/// Whether the coin type step of a BIP44 style path is another network's.
+/// Paths without one, or with another coin's, can't tell.
+fn other_network_path(path: &bip32::DerivationPath, network: &Network) -> bool {
+  return match path.as_ref().get(1) {
+    Some(bip32::ChildNumber::Hardened { index }) if *index <= 1 => *index != coin_type(network),
+    _ => false,
+  };
+}
+
+/// Inspects a PSBT before `account` signs it.
+pub fn check_psbt<A: Account + ?Sized>(
+  psbt: &PartiallySignedTransaction,
+  account: &A,
+  policy: &Policy,
+) -> PolicyReport {
+  let mut report = PolicyReport {
+    warnings: vec![],
+    blocks: vec![],
+  };
+  let tx = &psbt.unsigned_tx;
+  if psbt.inputs.len() != tx.input.len() || psbt.outputs.len() != tx.output.len() {
+    report
+      .blocks
+      .push(PolicyIssue::Invalid(VulcanError::InconsistentPsbt));
+    return report;
+  }
+
+  let network = account.network();
+  for xpub in psbt.xpub.keys() {
+    if (xpub.network == Network::Bitcoin) != (network == Network::Bitcoin) {
+      report
+        .blocks
+        .push(PolicyIssue::WrongNetworkXpub(xpub.fingerprint()));
+    }
+  }
+
+  let fingerprint = account.fingerprint();
+  let mut ours = 0;
+  for (i, input) in psbt.inputs.iter().enumerate() {
+    if let Some(ty) = input.sighash_type {
+      // 0 is SIGHASH_DEFAULT for taproot, 1 is SIGHASH_ALL
+      if ty.to_u32() > 1 {
+        report
+          .blocks
+          .push(PolicyIssue::NonDefaultSighash(i, ty.to_u32()));
+      }
+    }
+
+    let is_taproot = match &input.witness_utxo {
+      Some(utxo) => utxo.script_pubkey.is_v1_p2tr(),
+      None => false,
+    };
+    // taproot sighashes commit to every amount, so it doesn't need the tx
+    if input.witness_utxo.is_some() && input.non_witness_utxo.is_none() && !is_taproot {
+      if policy.require_non_witness_utxo {
+        report.blocks.push(PolicyIssue::MissingNonWitnessUtxo(i));
+      } else {
+        report.warnings.push(PolicyIssue::MissingNonWitnessUtxo(i));
+      }
+    }
+
+    let ecdsa = input.bip32_derivation.values();
+    let taproot = input.tap_key_origins.values().map(|(_, source)| source);
+    let paths: Vec<&bip32::DerivationPath> = ecdsa
+      .chain(taproot)
+      .filter(|(fp, _)| *fp == fingerprint)
+      .map(|(_, path)| path)
+      .collect();
+    if paths.is_empty() {
+      report.warnings.push(PolicyIssue::ForeignInput(i));
+    } else {
+      ours += 1;
+      if paths.iter().any(|path| other_network_path(path, &network)) {
+        report.blocks.push(PolicyIssue::WrongNetworkInput(i));
+      }
+    }
+
+    // relative timelocks only apply from version 2 on
+    let sequence = tx.input[i].sequence;
+    if tx.version >= 2
+      && sequence & SEQUENCE_DISABLE_FLAG == 0
+      && sequence & SEQUENCE_LOCKTIME_MASK != 0
+    {
+      report
+        .warnings
+        .push(PolicyIssue::UnusualSequence(i, sequence));
+    }
+  }
+  if ours == 0 {
+    report.blocks.push(PolicyIssue::NothingToSign);
+  }
+
+  if tx.lock_time >= LOCKTIME_THRESHOLD {
+    report
+      .warnings
+      .push(PolicyIssue::TimestampLockTime(tx.lock_time));
+  }
+
+  // what leaves the wallet, change isn't counted
+  let mut sent: u64 = 0;
+  for (i, (txout, output)) in tx.output.iter().zip(psbt.outputs.iter()).enumerate() {
+    if txout.script_pubkey.is_op_return() {
+      report.warnings.push(PolicyIssue::DataOutput(i));
+      sent = sent.saturating_add(txout.value);
+      continue;
+    }
+    if Address::from_script(&txout.script_pubkey, account.network()).is_none() {
+      report.blocks.push(PolicyIssue::NonStandardOutput(i));
+      continue;
+    }
+    match classify_output(account, txout, output) {
+      Ok(OutputKind::Spend) => sent = sent.saturating_add(txout.value),
+      Ok(OutputKind::Change) => {}
+      Ok(OutputKind::Suspicious) => report.blocks.push(PolicyIssue::SuspiciousChange(i)),
+      Err(err) => report.blocks.push(PolicyIssue::Invalid(err)),
+    }
+  }
+
+  let (fee, vsize) = match calc_fee(psbt).and_then(|fee| Ok((fee, estimate_vsize(psbt)?))) {
+    Ok(result) => result,
+    Err(err) => {
+      report.blocks.push(PolicyIssue::Invalid(err));
+      return report;
+    }
+  };
+
+  let fee_rate = fee as f64 / vsize as f64;
+  if fee_rate > policy.max_fee_rate {
+    report
+      .blocks
+      .push(PolicyIssue::AbsurdFeeRate(fee_rate as u64));
+  } else if fee_rate > policy.warn_fee_rate {
+    report
+      .warnings
+      .push(PolicyIssue::HighFeeRate(fee_rate as u64));
+  }
+
+  // a self-transfer only moves change, compare against everything then
+  let sent = if sent == 0 {
+    tx.output
+      .iter()
+      .fold(0u64, |sum, out| sum.saturating_add(out.value))
+  } else {
+    sent
+  };
+  // nothing sent, no percentage
+  if let Some(percent) = fee.saturating_mul(100).checked_div(sent) {
+    if percent > policy.max_fee_percent {
+      report.blocks.push(PolicyIssue::AbsurdFee(percent));
+    } else if percent >= policy.warn_fee_percent {
+      report.warnings.push(PolicyIssue::HighFee(percent));
+    }
+  }
+  // a low rate on a large transaction can still burn a lot
+  if fee > policy.max_fee_sats {
+    report.blocks.push(PolicyIssue::AbsurdFeeAmount(fee));
+  }
+
+  return report;
+}
@@ -0,0 +1,330 @@
+use crate::{base64, error::VulcanError};
+use alloc::{string::String, vec, vec::Vec};
+use bitcoin::{
+  util::{
+    bip32,
+    psbt::{self, PartiallySignedTransaction},
+  },
+  Address, Network, Script, Transaction, TxOut,
+};
+
+const MAGIC: &[u8] = b"psbt\xff";
+
+/// The serialized PSBT in a file. Binary PSBTs start with the magic bytes,
+/// anything else is read as base64.
+pub fn psbt_bytes(contents: &[u8]) -> Result<Vec<u8>, VulcanError> {
+  if contents.starts_with(MAGIC) {
+    return Ok(contents.to_vec());
+  }
+  let text = core::str::from_utf8(contents).map_err(|_| VulcanError::MalformedPsbt)?;
+  return base64::decode(text.trim()).ok_or(VulcanError::MalformedPsbt);
+}
+
+/// A serialized PSBT as base64, which every coordinator reads from files.
+pub fn psbt_base64(bytes: &[u8]) -> String {
+  return base64::encode(bytes);
+}
+
+/// A wallet that can tell its own outputs apart by re-deriving them.
+pub trait Account {
+  /// Master key fingerprint
+  fn fingerprint(&self) -> bip32::Fingerprint;
+
+  fn network(&self) -> Network;
+
+  /// Account derivation path
+  fn path(&self) -> &bip32::DerivationPath;
+
+  /// `(change, index)` if `path` is a receive or change address of the
+  /// account.
+  fn child(&self, path: &bip32::DerivationPath) -> Option<(bool, u32)>;
+
+  /// The `script_pubkey` at `<change>/<index>`
+  fn script_pubkey(&self, change: bool, index: u32) -> Result<Script, VulcanError>;
+
+  /// Whether the PSBT output pays to this account.
+  fn owns_output(&self, txout: &TxOut, output: &psbt::Output) -> Result<bool, VulcanError> {
+    return Ok(classify_output(self, txout, output)? == OutputKind::Change);
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputKind {
+  /// Pays someone else
+  Spend,
+  /// Re-derives to the exact script under our account
+  Change,
+  /// Has a derivation with our fingerprint, but the script doesn't match
+  Suspicious,
+}
+
+/// Decides whether an output is change. The PSBT's derivations are only
+/// hints, an output is change if one of the paths it claims under our
+/// fingerprint re-derives to its exact `script_pubkey`.
+pub fn classify_output<A: Account + ?Sized>(
+  account: &A,
+  txout: &TxOut,
+  output: &psbt::Output,
+) -> Result<OutputKind, VulcanError> {
+  let fingerprint = account.fingerprint();
+  let ecdsa = output.bip32_derivation.values();
+  let taproot = output.tap_key_origins.values().map(|(_, source)| source);
+  let paths: Vec<&bip32::DerivationPath> = ecdsa
+    .chain(taproot)
+    .filter(|(fp, _)| *fp == fingerprint)
+    .map(|(_, path)| path)
+    .collect();
+
+  if paths.is_empty() {
+    return Ok(OutputKind::Spend);
+  }
+  for path in paths {
+    if let Some((change, index)) = account.child(path) {
+      if account.script_pubkey(change, index)? == txout.script_pubkey {
+        return Ok(OutputKind::Change);
+      }
+    }
+  }
+  return Ok(OutputKind::Suspicious);
+}
+
+/// Outputs of a transaction sorted by who they pay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outputs {
+  pub spend: Vec<(Address, u64)>,
+  pub change: Vec<(Address, u64)>,
+  /// Outputs that claim to be ours but aren't, show these as spends with a
+  /// warning
+  pub suspicious: Vec<(Address, u64)>,
+  /// `OP_RETURN` outputs, they carry data instead of paying anyone
+  pub data: Vec<(Script, u64)>,
+  /// Outputs to scripts no address encodes, the signing policy blocks these
+  pub nonstandard: Vec<(Script, u64)>,
+}
+
+/// The output spent by input `index`.
+///
+/// A `non_witness_utxo` must hash to the txid of the outpoint, and agree with
+/// the `witness_utxo` if both are given.
+pub fn spent_output(
+  tx: &Transaction,
+  index: usize,
+  input: &psbt::Input,
+) -> Result<TxOut, VulcanError> {
+  let outpoint = tx
+    .input
+    .get(index)
+    .ok_or(VulcanError::InconsistentPsbt)?
+    .previous_output;
+
+  if let Some(prev_tx) = &input.non_witness_utxo {
+    if prev_tx.txid() != outpoint.txid {
+      return Err(VulcanError::UtxoMismatch(index));
+    }
+    let utxo = prev_tx
+      .output
+      .get(outpoint.vout as usize)
+      .ok_or(VulcanError::UtxoMismatch(index))?;
+    match &input.witness_utxo {
+      Some(witness_utxo) if witness_utxo != utxo => return Err(VulcanError::UtxoMismatch(index)),
+      _ => return Ok(utxo.clone()),
+    }
+  }
+  match &input.witness_utxo {
+    Some(utxo) => return Ok(utxo.clone()),
+    None => return Err(VulcanError::MissingUtxo(index)),
+  }
+}
+
+/// Sums the value of every input minus the value of every output.
+pub fn calc_fee(psbt: &PartiallySignedTransaction) -> Result<u64, VulcanError> {
+  let mut fee = calc_input(psbt)?;
+
+  for out in &psbt.unsigned_tx.output {
+    fee = fee
+      .checked_sub(out.value)
+      .ok_or(VulcanError::InconsistentPsbt)?;
+  }
+
+  return Ok(fee);
+}
+
+/// Sums the value of every input.
+pub fn calc_input(psbt: &PartiallySignedTransaction) -> Result<u64, VulcanError> {
+  if psbt.inputs.len() != psbt.unsigned_tx.input.len() {
+    return Err(VulcanError::InconsistentPsbt);
+  }
+
+  let mut n: u64 = 0;
+  for (i, input) in psbt.inputs.iter().enumerate() {
+    let utxo = spent_output(&psbt.unsigned_tx, i, input)?;
+    n = n
+      .checked_add(utxo.value)
+      .ok_or(VulcanError::InconsistentPsbt)?;
+  }
+
+  return Ok(n);
+}
+
+/// Estimated weight an input adds once signed, on top of its outpoint,
+/// sequence and empty script_sig already counted in the unsigned tx.
+fn signed_input_weight(utxo: &TxOut, input: &psbt::Input) -> u64 {
+  const SIG: u64 = 1 + 72;
+  const PUBKEY: u64 = 1 + 33;
+  // script_sig bytes count 4 times, witness bytes once
+  let spk = &utxo.script_pubkey;
+  if spk.is_v0_p2wpkh() {
+    return 1 + SIG + PUBKEY;
+  }
+  if spk.is_v1_p2tr() {
+    return 1 + 1 + 64;
+  }
+  if spk.is_v0_p2wsh() {
+    return multisig_witness_weight(input);
+  }
+  if spk.is_p2sh() {
+    match &input.redeem_script {
+      Some(redeem_script) if redeem_script.is_v0_p2wpkh() => {
+        return (1 + 22) * 4 + 1 + SIG + PUBKEY;
+      }
+      Some(redeem_script) if redeem_script.is_v0_p2wsh() => {
+        return (1 + 34) * 4 + multisig_witness_weight(input);
+      }
+      _ => {}
+    }
+  }
+  // legacy p2pkh
+  return (SIG + PUBKEY) * 4;
+}
+
+/// Witness of a `multi` script: the dummy element, M signatures and the
+/// script itself.
+fn multisig_witness_weight(input: &psbt::Input) -> u64 {
+  let script = match &input.witness_script {
+    Some(script) => script,
+    // unknown, assume a single signature
+    None => return 1 + 1 + 73 + 1 + 34,
+  };
+  // OP_1 .. OP_16 as the first opcode is the threshold
+  let m = match script.as_bytes().first() {
+    Some(op) if (0x51..=0x60).contains(op) => (op - 0x50) as u64,
+    _ => 1,
+  };
+  let len = script.len() as u64;
+  let len_prefix = if len < 0xfd { 1 } else { 3 };
+  return 1 + 1 + m * 73 + len_prefix + len;
+}
+
+/// Estimated virtual size of the transaction once every input is signed.
+pub fn estimate_vsize(psbt: &PartiallySignedTransaction) -> Result<u64, VulcanError> {
+  if psbt.inputs.len() != psbt.unsigned_tx.input.len() {
+    return Err(VulcanError::InconsistentPsbt);
+  }
+
+  let tx = &psbt.unsigned_tx;
+  let mut weight = tx.weight() as u64;
+  let mut segwit = false;
+  for (i, input) in psbt.inputs.iter().enumerate() {
+    let utxo = spent_output(tx, i, input)?;
+    weight += signed_input_weight(&utxo, input);
+    segwit |= utxo.script_pubkey.is_witness_program() || input.redeem_script.is_some();
+  }
+  if segwit {
+    // marker and flag
+    weight += 2;
+  }
+  return Ok(weight.div_ceil(4));
+}
+
+/// Everything the user should see about a transaction before signing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionSummary {
+  /// Total value of the spent outputs
+  pub input: u64,
+  /// Total value of the new outputs
+  pub output: u64,
+  pub fee: u64,
+  /// Estimated virtual size once signed
+  pub vsize: u64,
+  /// sat/vB at the estimated size
+  pub fee_rate: f64,
+  pub outputs: Outputs,
+}
+
+impl TransactionSummary {
+  pub fn new<A: Account + ?Sized>(
+    psbt: &PartiallySignedTransaction,
+    account: &A,
+  ) -> Result<Self, VulcanError> {
+    if psbt.inputs.len() != psbt.unsigned_tx.input.len() {
+      return Err(VulcanError::InconsistentPsbt);
+    }
+
+    let tx = &psbt.unsigned_tx;
+    let input = calc_input(psbt)?;
+    let mut output: u64 = 0;
+    for out in &tx.output {
+      output = output
+        .checked_add(out.value)
+        .ok_or(VulcanError::InconsistentPsbt)?;
+    }
+    let fee = input
+      .checked_sub(output)
+      .ok_or(VulcanError::InconsistentPsbt)?;
+    let vsize = estimate_vsize(psbt)?;
+
+    return Ok(TransactionSummary {
+      input,
+      output,
+      fee,
+      vsize,
+      fee_rate: fee as f64 / vsize as f64,
+      outputs: calc_spend_change(psbt, account)?,
+    });
+  }
+}
+
+/// Splits the outputs of a transaction into spend, change, suspicious, data
+/// and non-standard outputs of `account`.
+pub fn calc_spend_change<A: Account + ?Sized>(
+  psbt: &PartiallySignedTransaction,
+  account: &A,
+) -> Result<Outputs, VulcanError> {
+  let mut outputs = Outputs {
+    spend: vec![],
+    change: vec![],
+    suspicious: vec![],
+    data: vec![],
+    nonstandard: vec![],
+  };
+
+  if psbt.outputs.len() != psbt.unsigned_tx.output.len() {
+    return Err(VulcanError::InconsistentPsbt);
+  }
+
+  for (txout, out) in psbt.unsigned_tx.output.iter().zip(psbt.outputs.iter()) {
+    if txout.script_pubkey.is_op_return() {
+      outputs
+        .data
+        .push((txout.script_pubkey.clone(), txout.value));
+      continue;
+    }
+    let addr = match Address::from_script(&txout.script_pubkey, account.network()) {
+      Some(addr) => addr,
+      None => {
+        outputs
+          .nonstandard
+          .push((txout.script_pubkey.clone(), txout.value));
+        continue;
+      }
+    };
+
+    match classify_output(account, txout, out)? {
+      OutputKind::Spend => outputs.spend.push((addr, txout.value)),
+      OutputKind::Change => outputs.change.push((addr, txout.value)),
+      OutputKind::Suspicious => outputs.suspicious.push((addr, txout.value)),
+    }
+  }
+
+  return Ok(outputs);
+}
@@ -0,0 +1,555 @@
+use crate::error::VulcanError;
+use alloc::{vec, vec::Vec};
+use bitcoin::{
+  consensus,
+  hashes::Hash,
+  util::psbt::{PartiallySignedTransaction, PsbtSighashType},
+  OutPoint, Script, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use core::convert::TryInto;
+
+const MAGIC: &[u8] = b"psbt\xff";
+
+const PSBT_GLOBAL_UNSIGNED_TX: u64 = 0x00;
+const PSBT_GLOBAL_TX_VERSION: u64 = 0x02;
+const PSBT_GLOBAL_FALLBACK_LOCKTIME: u64 = 0x03;
+const PSBT_GLOBAL_INPUT_COUNT: u64 = 0x04;
+const PSBT_GLOBAL_OUTPUT_COUNT: u64 = 0x05;
+const PSBT_GLOBAL_TX_MODIFIABLE: u64 = 0x06;
+const PSBT_GLOBAL_VERSION: u64 = 0xfb;
+
+const PSBT_IN_PREVIOUS_TXID: u64 = 0x0e;
+const PSBT_IN_OUTPUT_INDEX: u64 = 0x0f;
+const PSBT_IN_SEQUENCE: u64 = 0x10;
+const PSBT_IN_REQUIRED_TIME_LOCKTIME: u64 = 0x11;
+const PSBT_IN_REQUIRED_HEIGHT_LOCKTIME: u64 = 0x12;
+
+const PSBT_OUT_AMOUNT: u64 = 0x03;
+const PSBT_OUT_SCRIPT: u64 = 0x04;
+
+const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+/// `PSBT_GLOBAL_TX_MODIFIABLE` bits
+const INPUTS_MODIFIABLE: u8 = 0x01;
+const OUTPUTS_MODIFIABLE: u8 = 0x02;
+const HAS_SIGHASH_SINGLE: u8 = 0x04;
+
+const SIGHASH_NONE: u32 = 0x02;
+const SIGHASH_SINGLE: u32 = 0x03;
+const SIGHASH_ANYONECANPAY: u32 = 0x80;
+
+/// Key-value pairs of one PSBT map, in the order they were read
+type Map = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Locktime an input needs, `PSBT_IN_REQUIRED_TIME_LOCKTIME` and
+/// `PSBT_IN_REQUIRED_HEIGHT_LOCKTIME`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RequiredLockTime {
+  /// Unix timestamp, at least 500000000
+  pub time: Option<u32>,
+  /// Block height, below 500000000
+  pub height: Option<u32>,
+}
+
+/// A BIP370 PSBT.
+///
+/// The transaction is rebuilt from the per-input and per-output fields into
+/// a v0 [`PartiallySignedTransaction`], so it can be analysed and signed
+/// like any other PSBT. The v2 fields that have no place in v0 are kept
+/// next to it and written back by [`PsbtV2::serialize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PsbtV2 {
+  pub psbt: PartiallySignedTransaction,
+  /// Locktime if no input requires one
+  pub fallback_locktime: Option<u32>,
+  /// `PSBT_GLOBAL_TX_MODIFIABLE` flags
+  pub tx_modifiable: Option<u8>,
+  /// One entry per input
+  pub locktimes: Vec<RequiredLockTime>,
+}
+
+/// A PSBT of either version. Version 2 keeps the fields a v0 PSBT has no
+/// place for, so they're written back after signing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PsbtFile {
+  V0(PartiallySignedTransaction),
+  V2(PsbtV2),
+}
+
+impl PsbtFile {
+  /// Parses a serialized PSBT of either version.
+  pub fn deserialize(bytes: &[u8]) -> Result<Self, VulcanError> {
+    return match psbt_version(bytes)? {
+      2 => Ok(PsbtFile::V2(PsbtV2::deserialize(bytes)?)),
+      _ => consensus::deserialize(bytes)
+        .map(PsbtFile::V0)
+        .map_err(|_| VulcanError::MalformedPsbt),
+    };
+  }
+
+  pub fn psbt(&self) -> &PartiallySignedTransaction {
+    return match self {
+      PsbtFile::V0(psbt) => psbt,
+      PsbtFile::V2(psbt) => &psbt.psbt,
+    };
+  }
+
+  pub fn psbt_mut(&mut self) -> &mut PartiallySignedTransaction {
+    return match self {
+      PsbtFile::V0(psbt) => psbt,
+      PsbtFile::V2(psbt) => &mut psbt.psbt,
+    };
+  }
+
+  pub fn version(&self) -> u32 {
+    return match self {
+      PsbtFile::V0(_) => 0,
+      PsbtFile::V2(_) => 2,
+    };
+  }
+
+  /// Records a signature made with `sighash`, see [`PsbtV2::mark_signed`].
+  /// A v0 PSBT has nothing to record.
+  pub fn mark_signed(&mut self, sighash: PsbtSighashType) {
+    if let PsbtFile::V2(psbt) = self {
+      psbt.mark_signed(sighash);
+    }
+  }
+
+  /// Serializes in the version it was read as.
+  pub fn serialize(&self) -> Vec<u8> {
+    return match self {
+      PsbtFile::V0(psbt) => consensus::serialize(psbt),
+      PsbtFile::V2(psbt) => psbt.serialize(),
+    };
+  }
+}
+
+/// The `PSBT_GLOBAL_VERSION` of a serialized PSBT, 0 if it has none.
+pub fn psbt_version(bytes: &[u8]) -> Result<u32, VulcanError> {
+  let mut pos = read_magic(bytes)?;
+  let global = read_map(bytes, &mut pos)?;
+  return match find(&global, PSBT_GLOBAL_VERSION) {
+    Some(value) => read_u32(value),
+    None => Ok(0),
+  };
+}
+
+/// Picks the transaction locktime as described in BIP370. Height is
+/// preferred when every input that has a requirement accepts a height.
+pub fn determine_locktime(
+  fallback: Option<u32>,
+  locktimes: &[RequiredLockTime],
+) -> Result<u32, VulcanError> {
+  let required: Vec<&RequiredLockTime> = locktimes
+    .iter()
+    .filter(|lock| lock.time.is_some() || lock.height.is_some())
+    .collect();
+  if required.is_empty() {
+    return Ok(fallback.unwrap_or(0));
+  }
+  if let Some(heights) = required
+    .iter()
+    .map(|lock| lock.height)
+    .collect::<Option<Vec<u32>>>()
+  {
+    return Ok(heights.into_iter().max().unwrap_or(0));
+  }
+  if let Some(times) = required
+    .iter()
+    .map(|lock| lock.time)
+    .collect::<Option<Vec<u32>>>()
+  {
+    return Ok(times.into_iter().max().unwrap_or(0));
+  }
+  // one input wants a height, another a timestamp
+  return Err(VulcanError::InconsistentPsbt);
+}
+
+impl PsbtV2 {
+  /// Parses a version 2 PSBT. Use [`psbt_version`] to tell v0 and v2 apart.
+  pub fn deserialize(bytes: &[u8]) -> Result<Self, VulcanError> {
+    let mut pos = read_magic(bytes)?;
+    let global = read_map(bytes, &mut pos)?;
+
+    match find(&global, PSBT_GLOBAL_VERSION) {
+      Some(value) => match read_u32(value)? {
+        2 => {}
+        version => return Err(VulcanError::UnsupportedVersion(version.min(255) as u8)),
+      },
+      None => return Err(VulcanError::UnsupportedVersion(0)),
+    }
+    if find(&global, PSBT_GLOBAL_UNSIGNED_TX).is_some() {
+      return Err(VulcanError::MalformedPsbt);
+    }
+    let version =
+      read_u32(find(&global, PSBT_GLOBAL_TX_VERSION).ok_or(VulcanError::MalformedPsbt)?)?;
+    let input_count = read_count(find(&global, PSBT_GLOBAL_INPUT_COUNT))?;
+    let output_count = read_count(find(&global, PSBT_GLOBAL_OUTPUT_COUNT))?;
+    let fallback_locktime = match find(&global, PSBT_GLOBAL_FALLBACK_LOCKTIME) {
+      Some(value) => Some(read_u32(value)?),
+      None => None,
+    };
+    let tx_modifiable = match find(&global, PSBT_GLOBAL_TX_MODIFIABLE) {
+      Some([flags]) => Some(*flags),
+      Some(_) => return Err(VulcanError::MalformedPsbt),
+      None => None,
+    };
+
+    let mut inputs: Vec<Map> = vec![];
+    let mut txins: Vec<TxIn> = vec![];
+    let mut locktimes: Vec<RequiredLockTime> = vec![];
+    for _ in 0..input_count {
+      let map = read_map(bytes, &mut pos)?;
+      let txid = find(&map, PSBT_IN_PREVIOUS_TXID).ok_or(VulcanError::MalformedPsbt)?;
+      let txid = Txid::from_slice(txid).map_err(|_| VulcanError::MalformedPsbt)?;
+      let vout = read_u32(find(&map, PSBT_IN_OUTPUT_INDEX).ok_or(VulcanError::MalformedPsbt)?)?;
+      let sequence = match find(&map, PSBT_IN_SEQUENCE) {
+        Some(value) => read_u32(value)?,
+        None => 0xffffffff,
+      };
+      let time = match find(&map, PSBT_IN_REQUIRED_TIME_LOCKTIME) {
+        Some(value) => match read_u32(value)? {
+          time if time >= LOCKTIME_THRESHOLD => Some(time),
+          _ => return Err(VulcanError::MalformedPsbt),
+        },
+        None => None,
+      };
+      let height = match find(&map, PSBT_IN_REQUIRED_HEIGHT_LOCKTIME) {
+        Some(value) => match read_u32(value)? {
+          height if height < LOCKTIME_THRESHOLD => Some(height),
+          _ => return Err(VulcanError::MalformedPsbt),
+        },
+        None => None,
+      };
+
+      txins.push(TxIn {
+        previous_output: OutPoint::new(txid, vout),
+        script_sig: Script::new(),
+        sequence,
+        witness: Witness::default(),
+      });
+      locktimes.push(RequiredLockTime { time, height });
+      inputs.push(map);
+    }
+
+    let mut outputs: Vec<Map> = vec![];
+    let mut txouts: Vec<TxOut> = vec![];
+    for _ in 0..output_count {
+      let map = read_map(bytes, &mut pos)?;
+      let amount = find(&map, PSBT_OUT_AMOUNT).ok_or(VulcanError::MalformedPsbt)?;
+      let amount: [u8; 8] = amount.try_into().map_err(|_| VulcanError::MalformedPsbt)?;
+      let amount = i64::from_le_bytes(amount);
+      if amount < 0 {
+        return Err(VulcanError::MalformedPsbt);
+      }
+      let script = find(&map, PSBT_OUT_SCRIPT).ok_or(VulcanError::MalformedPsbt)?;
+
+      txouts.push(TxOut {
+        value: amount as u64,
+        script_pubkey: Script::from(script.to_vec()),
+      });
+      outputs.push(map);
+    }
+    if pos != bytes.len() {
+      return Err(VulcanError::MalformedPsbt);
+    }
+
+    let tx = Transaction {
+      version: version as i32,
+      lock_time: determine_locktime(fallback_locktime, &locktimes)?,
+      input: txins,
+      output: txouts,
+    };
+
+    // hand everything that isn't v2 specific to the v0 parser
+    let mut global: Map = global
+      .into_iter()
+      .filter(|(key, _)| !is_v2_global(key))
+      .collect();
+    global.insert(
+      0,
+      (
+        vec![PSBT_GLOBAL_UNSIGNED_TX as u8],
+        consensus::serialize(&tx),
+      ),
+    );
+    let inputs: Vec<Map> = inputs
+      .into_iter()
+      .map(|map| {
+        map
+          .into_iter()
+          .filter(|(key, _)| !is_v2_input(key))
+          .collect()
+      })
+      .collect();
+    let outputs: Vec<Map> = outputs
+      .into_iter()
+      .map(|map| {
+        map
+          .into_iter()
+          .filter(|(key, _)| !is_v2_output(key))
+          .collect()
+      })
+      .collect();
+    let v0 = write_psbt(&global, &inputs, &outputs);
+    let psbt = consensus::deserialize(&v0).map_err(|_| VulcanError::MalformedPsbt)?;
+
+    return Ok(PsbtV2 {
+      psbt,
+      fallback_locktime,
+      tx_modifiable,
+      locktimes,
+    });
+  }
+
+  /// Serializes as a version 2 PSBT.
+  pub fn serialize(&self) -> Vec<u8> {
+    let v0 = consensus::serialize(&self.psbt);
+    let tx = &self.psbt.unsigned_tx;
+
+    // rust-bitcoin wrote these maps itself, reading them back can't fail
+    let mut pos = MAGIC.len();
+    let mut global = read_map(&v0, &mut pos).unwrap_or_default();
+    let mut inputs: Vec<Map> = vec![];
+    for _ in 0..self.psbt.inputs.len() {
+      inputs.push(read_map(&v0, &mut pos).unwrap_or_default());
+    }
+    let mut outputs: Vec<Map> = vec![];
+    for _ in 0..self.psbt.outputs.len() {
+      outputs.push(read_map(&v0, &mut pos).unwrap_or_default());
+    }
+
+    global.retain(|(key, _)| key_type(key) != Some(PSBT_GLOBAL_UNSIGNED_TX) && !is_v2_global(key));
+    global.push(pair(
+      PSBT_GLOBAL_TX_VERSION,
+      tx.version.to_le_bytes().to_vec(),
+    ));
+    if let Some(locktime) = self.fallback_locktime {
+      global.push(pair(
+        PSBT_GLOBAL_FALLBACK_LOCKTIME,
+        locktime.to_le_bytes().to_vec(),
+      ));
+    }
+    global.push(pair(
+      PSBT_GLOBAL_INPUT_COUNT,
+      compact_size(tx.input.len() as u64),
+    ));
+    global.push(pair(
+      PSBT_GLOBAL_OUTPUT_COUNT,
+      compact_size(tx.output.len() as u64),
+    ));
+    if let Some(flags) = self.tx_modifiable {
+      global.push(pair(PSBT_GLOBAL_TX_MODIFIABLE, vec![flags]));
+    }
+    global.push(pair(PSBT_GLOBAL_VERSION, 2u32.to_le_bytes().to_vec()));
+    global.sort();
+
+    for (i, (map, txin)) in inputs.iter_mut().zip(tx.input.iter()).enumerate() {
+      map.push(pair(
+        PSBT_IN_PREVIOUS_TXID,
+        txin.previous_output.txid[..].to_vec(),
+      ));
+      map.push(pair(
+        PSBT_IN_OUTPUT_INDEX,
+        txin.previous_output.vout.to_le_bytes().to_vec(),
+      ));
+      if txin.sequence != 0xffffffff {
+        map.push(pair(PSBT_IN_SEQUENCE, txin.sequence.to_le_bytes().to_vec()));
+      }
+      let lock = self.locktimes.get(i).copied().unwrap_or_default();
+      if let Some(time) = lock.time {
+        map.push(pair(
+          PSBT_IN_REQUIRED_TIME_LOCKTIME,
+          time.to_le_bytes().to_vec(),
+        ));
+      }
+      if let Some(height) = lock.height {
+        map.push(pair(
+          PSBT_IN_REQUIRED_HEIGHT_LOCKTIME,
+          height.to_le_bytes().to_vec(),
+        ));
+      }
+      map.sort();
+    }
+
+    for (map, txout) in outputs.iter_mut().zip(tx.output.iter()) {
+      map.push(pair(
+        PSBT_OUT_AMOUNT,
+        (txout.value as i64).to_le_bytes().to_vec(),
+      ));
+      map.push(pair(PSBT_OUT_SCRIPT, txout.script_pubkey.to_bytes()));
+      map.sort();
+    }
+
+    return write_psbt(&global, &inputs, &outputs);
+  }
+
+  /// Converts a v0 PSBT. Always possible, the transaction's locktime
+  /// becomes the fallback locktime.
+  pub fn from_v0(psbt: PartiallySignedTransaction) -> Self {
+    let lock_time = psbt.unsigned_tx.lock_time;
+    let locktimes = vec![RequiredLockTime::default(); psbt.inputs.len()];
+    return PsbtV2 {
+      psbt,
+      fallback_locktime: if lock_time == 0 {
+        None
+      } else {
+        Some(lock_time)
+      },
+      tx_modifiable: None,
+      locktimes,
+    };
+  }
+
+  /// Updates `PSBT_GLOBAL_TX_MODIFIABLE` after signing with `sighash` as
+  /// BIP370 requires: inputs stay modifiable only for ANYONECANPAY, outputs
+  /// only for NONE, and SINGLE is recorded.
+  pub fn mark_signed(&mut self, sighash: PsbtSighashType) {
+    let sighash = sighash.to_u32();
+    if let Some(flags) = self.tx_modifiable.as_mut() {
+      if sighash & SIGHASH_ANYONECANPAY == 0 {
+        *flags &= !INPUTS_MODIFIABLE;
+      }
+      match sighash & 0x1f {
+        SIGHASH_NONE => {}
+        SIGHASH_SINGLE => *flags |= HAS_SIGHASH_SINGLE,
+        _ => *flags &= !OUTPUTS_MODIFIABLE,
+      }
+    }
+  }
+
+  /// Drops the v2 fields. The locktime was already determined when parsing,
+  /// so the v0 PSBT describes the same transaction.
+  pub fn into_v0(self) -> PartiallySignedTransaction {
+    return self.psbt;
+  }
+}
+
+fn is_v2_global(key: &[u8]) -> bool {
+  return matches!(
+    key_type(key),
+    Some(PSBT_GLOBAL_TX_VERSION..=PSBT_GLOBAL_TX_MODIFIABLE) | Some(PSBT_GLOBAL_VERSION)
+  );
+}
+
+fn is_v2_input(key: &[u8]) -> bool {
+  return matches!(
+    key_type(key),
+    Some(PSBT_IN_PREVIOUS_TXID..=PSBT_IN_REQUIRED_HEIGHT_LOCKTIME)
+  );
+}
+
+fn is_v2_output(key: &[u8]) -> bool {
+  return matches!(key_type(key), Some(PSBT_OUT_AMOUNT) | Some(PSBT_OUT_SCRIPT));
+}
+
+/// A pair whose key is just the type
+fn pair(key_type: u64, value: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
+  return (compact_size(key_type), value);
+}
+
+/// The value of the first pair whose key is exactly `key_type`
+fn find(map: &Map, key_type: u64) -> Option<&[u8]> {
+  let key = compact_size(key_type);
+  return map
+    .iter()
+    .find(|(k, _)| *k == key)
+    .map(|(_, value)| value.as_slice());
+}
+
+fn key_type(key: &[u8]) -> Option<u64> {
+  let mut pos = 0;
+  return read_compact_size(key, &mut pos).ok();
+}
+
+fn read_magic(bytes: &[u8]) -> Result<usize, VulcanError> {
+  if !bytes.starts_with(MAGIC) {
+    return Err(VulcanError::MalformedPsbt);
+  }
+  return Ok(MAGIC.len());
+}
+
+fn read_u32(value: &[u8]) -> Result<u32, VulcanError> {
+  let value: [u8; 4] = value.try_into().map_err(|_| VulcanError::MalformedPsbt)?;
+  return Ok(u32::from_le_bytes(value));
+}
+
+fn read_count(value: Option<&[u8]>) -> Result<usize, VulcanError> {
+  let value = value.ok_or(VulcanError::MalformedPsbt)?;
+  let mut pos = 0;
+  let count = read_compact_size(value, &mut pos)?;
+  if pos != value.len() {
+    return Err(VulcanError::MalformedPsbt);
+  }
+  return Ok(count as usize);
+}
+
+fn read_compact_size(bytes: &[u8], pos: &mut usize) -> Result<u64, VulcanError> {
+  let first = *bytes.get(*pos).ok_or(VulcanError::MalformedPsbt)?;
+  let len = match first {
+    0xfd => 2,
+    0xfe => 4,
+    0xff => 8,
+    n => {
+      *pos += 1;
+      return Ok(n as u64);
+    }
+  };
+  let start = *pos + 1;
+  let digits = bytes
+    .get(start..start + len)
+    .ok_or(VulcanError::MalformedPsbt)?;
+  *pos = start + len;
+  let mut buf = [0u8; 8];
+  buf[..len].copy_from_slice(digits);
+  return Ok(u64::from_le_bytes(buf));
+}
+
+fn compact_size(n: u64) -> Vec<u8> {
+  return match n {
+    0..=0xfc => vec![n as u8],
+    0xfd..=0xffff => [&[0xfd][..], &(n as u16).to_le_bytes()].concat(),
+    0x10000..=0xffffffff => [&[0xfe][..], &(n as u32).to_le_bytes()].concat(),
+    _ => [&[0xff][..], &n.to_le_bytes()].concat(),
+  };
+}
+
+/// Reads pairs up to the 0x00 separator. Duplicate keys are invalid.
+fn read_map(bytes: &[u8], pos: &mut usize) -> Result<Map, VulcanError> {
+  let mut map: Map = vec![];
+  loop {
+    let key_len = read_compact_size(bytes, pos)? as usize;
+    if key_len == 0 {
+      return Ok(map);
+    }
+    let key = read_bytes(bytes, pos, key_len)?;
+    let value_len = read_compact_size(bytes, pos)? as usize;
+    let value = read_bytes(bytes, pos, value_len)?;
+    if map.iter().any(|(k, _)| *k == key) {
+      return Err(VulcanError::MalformedPsbt);
+    }
+    map.push((key, value));
+  }
+}
+
+fn read_bytes(bytes: &[u8], pos: &mut usize, len: usize) -> Result<Vec<u8>, VulcanError> {
+  let end = pos.checked_add(len).ok_or(VulcanError::MalformedPsbt)?;
+  let slice = bytes.get(*pos..end).ok_or(VulcanError::MalformedPsbt)?;
+  *pos = end;
+  return Ok(slice.to_vec());
+}
+
+fn write_psbt(global: &Map, inputs: &[Map], outputs: &[Map]) -> Vec<u8> {
+  let mut out = MAGIC.to_vec();
+  for map in core::iter::once(global).chain(inputs).chain(outputs) {
+    for (key, value) in map {
+      out.extend(compact_size(key.len() as u64));
+      out.extend(key);
+      out.extend(compact_size(value.len() as u64));
+      out.extend(value);
+    }
+    out.push(0x00);
+  }
+  return out;
+}
@@ -0,0 +1,196 @@
+use crate::{descriptor::format_path, error::VulcanError, path::parse_path, types::ScriptType};
+use alloc::{format, string::String, vec, vec::Vec};
+use bip39;
+use bitcoin::util::bip32;
+
+/// An extended seedqr must be <= 127 digits to fit in a 29x29 QRCode
+const MAX_EXTENDED_DIGITS: usize = 127;
+
+/// [0] Version
+/// [1] Script type
+/// [2] Word count
+/// [3:3 + word_count * 4] mnemonic seed indeces as 4-digit numbers
+/// [3 + word_count * 4:] the rest is interpreted as 3-digits per unicode char. makes up the path after the "m/"
+pub fn parse_extended_seedqr(
+  qr: &str,
+) -> Result<(Vec<u16>, bip32::DerivationPath, ScriptType), VulcanError> {
+  if qr.len() > MAX_EXTENDED_DIGITS {
+    return Err(VulcanError::QrTooLarge(qr.len()));
+  }
+  // only ascii digits from here on, so byte slicing can't split a char
+  if qr.len() < 3 || !qr.bytes().all(|b| b.is_ascii_digit()) {
+    return Err(VulcanError::MalformedQr);
+  }
+
+  let version = parse_digits(&qr[0..1])? as u8;
+  if version != 0 {
+    return Err(VulcanError::UnsupportedVersion(version));
+  }
+
+  let script = match parse_digits(&qr[1..2])? as u8 {
+    0 => ScriptType::NestedSegwit,
+    1 => ScriptType::Segwit,
+    2 => ScriptType::Taproot,
+    code => return Err(VulcanError::UnsupportedScriptType(code)),
+  };
+  let count = match parse_digits(&qr[2..3])? {
+    0 => 12,
+    1 => 15,
+    2 => 18,
+    3 => 21,
+    4 => 24,
+    code => return Err(VulcanError::BadWordCount(code as usize)),
+  };
+
+  let qr = &qr[3..];
+
+  // parse mnemonic phrase
+  let end = count * 4;
+  if qr.len() < end {
+    return Err(VulcanError::MalformedQr);
+  }
+  let indeces = &qr[0..end];
+  let mut words: Vec<u16> = vec![];
+  for i in 0..count {
+    let start = i * 4;
+    let end = start + 4;
+    let index = parse_digits(&indeces[start..end])?;
+    if index > 2047 {
+      return Err(VulcanError::MalformedQr);
+    }
+    words.push(index);
+  }
+
+  // parse path as each char as 3-digit unicode
+  let path_chars = &qr[end..];
+  if !path_chars.len().is_multiple_of(3) {
+    return Err(VulcanError::MalformedQr);
+  }
+  let count = path_chars.len() / 3;
+  let mut path: Vec<char> = vec![];
+  for i in 0..count {
+    let start = i * 3;
+    let end = start + 3;
+    let c = parse_digits(&path_chars[start..end])?;
+    if c > 127 {
+      // paths are plain ascii
+      return Err(VulcanError::BadDerivationPath);
+    }
+    path.push(c as u8 as char);
+  }
+  let path_str = &["m/", path.iter().collect::<String>().as_str()].join("");
+  let path = parse_path(path_str)?;
+  return Ok((words, path, script));
+}
+
+/// Serializes a mnemonic, script type and account path in the format
+/// [`parse_extended_seedqr`] reads. Hardened steps are written as `h`.
+pub fn encode_extended_seedqr(
+  mnemonic: &bip39::Mnemonic,
+  script_type: &ScriptType,
+  path: &bip32::DerivationPath,
+) -> Result<String, VulcanError> {
+  let version = 0;
+  let script = match script_type {
+    ScriptType::NestedSegwit => 0,
+    ScriptType::Segwit => 1,
+    ScriptType::Taproot => 2,
+  };
+  let count = match mnemonic.word_count() {
+    12 => 0,
+    15 => 1,
+    18 => 2,
+    21 => 3,
+    24 => 4,
+    count => return Err(VulcanError::BadWordCount(count)),
+  };
+
+  let mut qr = format!("{}{}{}", version, script, count);
+  qr.push_str(&encode_seedqr(mnemonic));
+
+  for c in format_path(path).chars() {
+    qr.push_str(&format!("{:03}", c as u32));
+  }
+
+  if qr.len() > MAX_EXTENDED_DIGITS {
+    return Err(VulcanError::QrTooLarge(qr.len()));
+  }
+  return Ok(qr);
+}
+
+/// Parses a run of ascii digits. Callers make sure it's at most 4 digits long.
+fn parse_digits(digits: &str) -> Result<u16, VulcanError> {
+  return digits.parse::<u16>().map_err(|_| VulcanError::MalformedQr);
+}
+
+/// Looks up the words of the mnemonic from their BIP39 indexes.
+pub fn words_to_mnemonic(words: &[u16]) -> Result<bip39::Mnemonic, VulcanError> {
+  let wordlist = bip39::Language::English.word_list();
+  let mut phrase: Vec<&str> = vec![];
+  for (i, index) in words.iter().enumerate() {
+    match wordlist.get(*index as usize) {
+      Some(word) => phrase.push(word),
+      None => return Err(bip39::Error::UnknownWord(i).into()),
+    }
+  }
+  let phrase = phrase.join(" ");
+  return Ok(bip39::Mnemonic::parse_in_normalized(
+    bip39::Language::English,
+    &phrase,
+  )?);
+}
+
+/// Encodes a mnemonic as a Standard SeedQR: every word as its 4-digit, zero
+/// padded BIP39 index. The result is meant for a numeric mode QR code.
+pub fn encode_seedqr(mnemonic: &bip39::Mnemonic) -> String {
+  let mut digits = String::with_capacity(mnemonic.word_count() * 4);
+  for index in mnemonic.word_indices() {
+    digits.push_str(&format!("{:04}", index));
+  }
+  return digits;
+}
+
+/// Decodes a Standard SeedQR. Accepts 12, 15, 18, 21 or 24 words.
+pub fn decode_seedqr(qr: &str) -> Result<bip39::Mnemonic, VulcanError> {
+  if !qr.bytes().all(|b| b.is_ascii_digit()) || !qr.len().is_multiple_of(4) {
+    return Err(VulcanError::MalformedQr);
+  }
+  let count = qr.len() / 4;
+  match count {
+    12 | 15 | 18 | 21 | 24 => {}
+    _ => return Err(VulcanError::BadWordCount(count)),
+  }
+
+  let mut words: Vec<u16> = vec![];
+  for i in 0..count {
+    let start = i * 4;
+    let end = start + 4;
+    let index = parse_digits(&qr[start..end])?;
+    if index > 2047 {
+      return Err(VulcanError::MalformedQr);
+    }
+    words.push(index);
+  }
+  return words_to_mnemonic(&words);
+}
+
+/// Encodes a mnemonic as a CompactSeedQR: the raw entropy bytes, meant for a
+/// byte mode QR code. The spec only covers 12 and 24 word mnemonics.
+pub fn encode_compact_seedqr(mnemonic: &bip39::Mnemonic) -> Result<Vec<u8>, VulcanError> {
+  let count = mnemonic.word_count();
+  if count != 12 && count != 24 {
+    return Err(VulcanError::BadWordCount(count));
+  }
+  let (entropy, len) = mnemonic.to_entropy_array();
+  return Ok(entropy[0..len].to_vec());
+}
+
+/// Decodes a CompactSeedQR. 16 bytes are a 12 word mnemonic, 32 bytes a 24
+/// word one.
+pub fn decode_compact_seedqr(qr: &[u8]) -> Result<bip39::Mnemonic, VulcanError> {
+  match qr.len() {
+    16 | 32 => {}
+    _ => return Err(VulcanError::MalformedQr),
+  }
+  return Ok(bip39::Mnemonic::from_entropy(qr)?);
+}
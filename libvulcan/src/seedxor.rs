@@ -0,0 +1,68 @@
+//! Coldcard's Seed XOR: a mnemonic is split into 2 to 4 parts of the same
+//! length whose entropies XOR to the original's. Every part is a valid BIP39
+//! mnemonic, so each can be used as a decoy wallet on its own.
+//! https://seedxor.com
+
+use crate::error::VulcanError;
+use alloc::vec::Vec;
+use bip39::{Language, Mnemonic};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+
+pub const MIN_SEED_XOR_PARTS: usize = 2;
+pub const MAX_SEED_XOR_PARTS: usize = 4;
+
+/// Splits `mnemonic` into `parts` mnemonics of the same length. All but the
+/// last part are `SHA256(random || entropy || i)`, so a weak RNG alone
+/// doesn't give the parts away, and the last one XORs them back to the
+/// mnemonic.
+pub fn seed_xor_split(
+  mnemonic: &Mnemonic,
+  parts: usize,
+  random: &[u8],
+) -> Result<Vec<Mnemonic>, VulcanError> {
+  if !(MIN_SEED_XOR_PARTS..=MAX_SEED_XOR_PARTS).contains(&parts) {
+    return Err(VulcanError::BadSeedXor);
+  }
+  if random.len() < 32 {
+    return Err(VulcanError::BadEntropyLength(random.len()));
+  }
+  let (entropy, len) = mnemonic.to_entropy_array();
+  let entropy = &entropy[..len];
+
+  let mut last = entropy.to_vec();
+  let mut split = Vec::with_capacity(parts);
+  for i in 0..(parts - 1) {
+    let mut engine = sha256::Hash::engine();
+    engine.input(random);
+    engine.input(entropy);
+    engine.input(&[i as u8]);
+    let part = sha256::Hash::from_engine(engine);
+    for (byte, mask) in last.iter_mut().zip(part.iter()) {
+      *byte ^= mask;
+    }
+    split.push(Mnemonic::from_entropy_in(Language::English, &part[..len])?);
+  }
+  split.push(Mnemonic::from_entropy_in(Language::English, &last)?);
+  return Ok(split);
+}
+
+/// The mnemonic the parts were split from. The parts can be in any order.
+pub fn seed_xor_combine(parts: &[Mnemonic]) -> Result<Mnemonic, VulcanError> {
+  if !(MIN_SEED_XOR_PARTS..=MAX_SEED_XOR_PARTS).contains(&parts.len()) {
+    return Err(VulcanError::BadSeedXor);
+  }
+  let (mut entropy, len) = parts[0].to_entropy_array();
+  for part in &parts[1..] {
+    let (part, part_len) = part.to_entropy_array();
+    if part_len != len {
+      return Err(VulcanError::BadSeedXor);
+    }
+    for (byte, mask) in entropy[..len].iter_mut().zip(part.iter()) {
+      *byte ^= mask;
+    }
+  }
+  return Ok(Mnemonic::from_entropy_in(
+    Language::English,
+    &entropy[..len],
+  )?);
+}
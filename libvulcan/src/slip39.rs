@@ -0,0 +1,596 @@
+//! SLIP-39 Shamir backups: the master secret is encrypted with the
+//! passphrase, then split into groups of mnemonic shares so that any
+//! `group_threshold` groups, each with its own member threshold, recover it.
+//! https://github.com/satoshilabs/slips/blob/master/slip-0039.md
+//!
+//! The master secret is what BIP32 uses as the seed. Sharing the seed of a
+//! BIP39 mnemonic gives back the same keys, Trezor derives from the
+//! recovered master secret the same way.
+
+mod wordlist;
+
+use crate::error::VulcanError;
+use alloc::{vec, vec::Vec};
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use core::{fmt, str::FromStr};
+
+pub use self::wordlist::WORDLIST;
+
+const RADIX_BITS: usize = 10;
+const CHECKSUM_WORDS: usize = 3;
+/// identifier, flags, group and member parameters, and the checksum
+const METADATA_WORDS: usize = 7;
+const MIN_STRENGTH_BYTES: usize = 16;
+const MAX_SHARE_COUNT: u8 = 16;
+const DIGEST_LEN: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const BASE_ITERATIONS: u32 = 10000;
+const ROUNDS: u8 = 4;
+const CUSTOMIZATION: &[u8] = b"shamir";
+const CUSTOMIZATION_EXTENDABLE: &[u8] = b"shamir_extendable";
+
+/// One mnemonic share.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+  /// Random 15 bit identifier shared by every share of a backup
+  pub identifier: u16,
+  /// Whether the identifier is left out of the encryption, so more shares
+  /// can be made for the same master secret later
+  pub extendable: bool,
+  /// PBKDF2 uses `10000 << iteration_exponent` iterations in total
+  pub iteration_exponent: u8,
+  pub group_index: u8,
+  pub group_threshold: u8,
+  pub group_count: u8,
+  pub member_index: u8,
+  pub member_threshold: u8,
+  pub value: Vec<u8>,
+}
+
+impl Share {
+  /// The share's words.
+  pub fn words(&self) -> Vec<&'static str> {
+    let mut data: Vec<u16> = Vec::with_capacity(self.word_count());
+    let id = (self.identifier as u32) << 5
+      | (self.extendable as u32) << 4
+      | self.iteration_exponent as u32;
+    let params = (self.group_index as u32) << 16
+      | (self.group_threshold as u32 - 1) << 12
+      | (self.group_count as u32 - 1) << 8
+      | (self.member_index as u32) << 4
+      | (self.member_threshold as u32 - 1);
+    for value in [id, params].iter() {
+      data.push((value >> 10) as u16);
+      data.push((value & 0x3ff) as u16);
+    }
+
+    // the value is left-padded with zero bits to whole words
+    let value_words = (self.value.len() * 8).div_ceil(RADIX_BITS);
+    let mut acc: u32 = 0;
+    let mut bits = value_words * RADIX_BITS - self.value.len() * 8;
+    for byte in &self.value {
+      acc = (acc << 8 | *byte as u32) & 0xffff;
+      bits += 8;
+      if bits >= RADIX_BITS {
+        bits -= RADIX_BITS;
+        data.push((acc >> bits) as u16 & 0x3ff);
+      }
+    }
+
+    let checksum = rs1024_checksum(customization(self.extendable), &data);
+    data.extend_from_slice(&checksum);
+    return data.iter().map(|i| WORDLIST[*i as usize]).collect();
+  }
+
+  fn word_count(&self) -> usize {
+    return METADATA_WORDS + (self.value.len() * 8).div_ceil(RADIX_BITS);
+  }
+
+  /// Whether `other` belongs to the same backup.
+  fn same_backup(&self, other: &Share) -> bool {
+    return self.identifier == other.identifier
+      && self.extendable == other.extendable
+      && self.iteration_exponent == other.iteration_exponent
+      && self.group_threshold == other.group_threshold
+      && self.group_count == other.group_count
+      && self.value.len() == other.value.len();
+  }
+}
+
+impl fmt::Display for Share {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.words().join(" "))
+  }
+}
+
+impl FromStr for Share {
+  type Err = VulcanError;
+
+  /// Parses a share's words, checking its checksum and padding.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut data: Vec<u16> = Vec::new();
+    for word in s.split_whitespace() {
+      let word = word.to_lowercase();
+      let index = WORDLIST
+        .binary_search(&word.as_str())
+        .map_err(|_| VulcanError::BadShare)?;
+      data.push(index as u16);
+    }
+    let min_words = METADATA_WORDS + (MIN_STRENGTH_BYTES * 8).div_ceil(RADIX_BITS);
+    if data.len() < min_words {
+      return Err(VulcanError::BadShare);
+    }
+    let padding = RADIX_BITS * (data.len() - METADATA_WORDS) % 16;
+    if padding > 8 {
+      return Err(VulcanError::BadShare);
+    }
+
+    let id = (data[0] as u32) << 10 | data[1] as u32;
+    let extendable = id >> 4 & 1 == 1;
+    if rs1024_polymod(customization(extendable), &data) != 1 {
+      return Err(VulcanError::BadShare);
+    }
+    let params = (data[2] as u32) << 10 | data[3] as u32;
+
+    let value_data = &data[4..data.len() - CHECKSUM_WORDS];
+    let mut value = Vec::with_capacity((value_data.len() * RADIX_BITS - padding) / 8);
+    let mut acc: u32 = 0;
+    let mut bits: usize = 0;
+    for (i, word) in value_data.iter().enumerate() {
+      acc = acc << RADIX_BITS | *word as u32;
+      bits += RADIX_BITS;
+      if i == 0 {
+        // padding bits must be zero
+        if acc >> (RADIX_BITS - padding) != 0 {
+          return Err(VulcanError::BadShare);
+        }
+        bits -= padding;
+      }
+      while bits >= 8 {
+        bits -= 8;
+        value.push((acc >> bits) as u8);
+      }
+      acc &= (1 << bits) - 1;
+    }
+
+    let share = Share {
+      identifier: (id >> 5) as u16,
+      extendable,
+      iteration_exponent: (id & 0xf) as u8,
+      group_index: (params >> 16) as u8,
+      group_threshold: (params >> 12 & 0xf) as u8 + 1,
+      group_count: (params >> 8 & 0xf) as u8 + 1,
+      member_index: (params >> 4 & 0xf) as u8,
+      member_threshold: (params & 0xf) as u8 + 1,
+      value,
+    };
+    if share.group_threshold > share.group_count {
+      return Err(VulcanError::BadShare);
+    }
+    return Ok(share);
+  }
+}
+
+/// Deterministic random bytes: HMAC-SHA256 of a counter, keyed with the
+/// caller's entropy.
+pub(crate) struct RandomBytes<'a> {
+  key: &'a [u8],
+  counter: u32,
+}
+
+impl<'a> RandomBytes<'a> {
+  pub(crate) fn new(key: &'a [u8]) -> Self {
+    return RandomBytes { key, counter: 0 };
+  }
+
+  fn fill(&mut self, bytes: &mut [u8]) {
+    for chunk in bytes.chunks_mut(32) {
+      let mut engine = hmac::HmacEngine::<sha256::Hash>::new(self.key);
+      engine.input(&self.counter.to_be_bytes());
+      self.counter += 1;
+      let block = hmac::Hmac::<sha256::Hash>::from_engine(engine).into_inner();
+      chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+  }
+
+  pub(crate) fn bytes(&mut self, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    self.fill(&mut bytes);
+    return bytes;
+  }
+}
+
+/// Splits `master_secret` into shares: `groups` lists each group's
+/// `(member_threshold, member_count)`, and any `group_threshold` groups
+/// recover the secret. `random` seeds the identifier and the random
+/// polynomial coefficients, use at least 32 bytes from a real RNG.
+pub fn split_master_secret(
+  master_secret: &[u8],
+  passphrase: &str,
+  group_threshold: u8,
+  groups: &[(u8, u8)],
+  iteration_exponent: u8,
+  extendable: bool,
+  random: &[u8],
+) -> Result<Vec<Vec<Share>>, VulcanError> {
+  if master_secret.len() < MIN_STRENGTH_BYTES || !master_secret.len().is_multiple_of(2) {
+    return Err(VulcanError::BadEntropyLength(master_secret.len()));
+  }
+  if random.len() < 32 {
+    return Err(VulcanError::BadEntropyLength(random.len()));
+  }
+  if iteration_exponent > 15 || groups.len() > MAX_SHARE_COUNT as usize {
+    return Err(VulcanError::BadShamirGroups);
+  }
+  if group_threshold == 0 || group_threshold as usize > groups.len() {
+    return Err(VulcanError::BadShamirGroups);
+  }
+  for (member_threshold, member_count) in groups {
+    // a 1-of-n group would be n copies of the same share
+    if *member_threshold == 1 && *member_count > 1 {
+      return Err(VulcanError::BadShamirGroups);
+    }
+  }
+
+  let mut random = RandomBytes::new(random);
+  let id = random.bytes(2);
+  let identifier = u16::from_be_bytes([id[0], id[1]]) & 0x7fff;
+  let ems = encrypt(
+    master_secret,
+    passphrase,
+    iteration_exponent,
+    identifier,
+    extendable,
+  );
+
+  let group_shares = split_secret(group_threshold, groups.len() as u8, &ems, &mut random)?;
+  let mut shares = Vec::with_capacity(groups.len());
+  for ((member_threshold, member_count), (group_index, group_secret)) in
+    groups.iter().zip(group_shares)
+  {
+    let members = split_secret(*member_threshold, *member_count, &group_secret, &mut random)?;
+    shares.push(
+      members
+        .into_iter()
+        .map(|(member_index, value)| Share {
+          identifier,
+          extendable,
+          iteration_exponent,
+          group_index,
+          group_threshold,
+          group_count: groups.len() as u8,
+          member_index,
+          member_threshold: *member_threshold,
+          value,
+        })
+        .collect(),
+    );
+  }
+  return Ok(shares);
+}
+
+/// Recovers the master secret once the shares complete `group_threshold`
+/// groups. Shares of incomplete groups and extra shares are ignored, so
+/// shares can be added one at a time until this stops returning
+/// [`VulcanError::InsufficientShares`].
+pub fn combine_shares(shares: &[Share], passphrase: &str) -> Result<Vec<u8>, VulcanError> {
+  let first = shares.first().ok_or(VulcanError::InsufficientShares)?;
+  if shares.iter().any(|share| !first.same_backup(share)) {
+    return Err(VulcanError::ShareMismatch);
+  }
+
+  let mut group_shares: Vec<(u8, Vec<u8>)> = Vec::new();
+  for group_index in 0..first.group_count {
+    let members: Vec<&Share> = shares
+      .iter()
+      .filter(|share| share.group_index == group_index)
+      .collect();
+    let member_threshold = match members.first() {
+      Some(share) => share.member_threshold,
+      None => continue,
+    };
+    if members
+      .iter()
+      .any(|share| share.member_threshold != member_threshold)
+    {
+      return Err(VulcanError::ShareMismatch);
+    }
+
+    let mut distinct: Vec<(u8, &[u8])> = Vec::new();
+    for share in members {
+      match distinct.iter().find(|(x, _)| *x == share.member_index) {
+        Some((_, value)) if *value != share.value.as_slice() => {
+          return Err(VulcanError::ShareMismatch)
+        }
+        Some(_) => {}
+        None => distinct.push((share.member_index, &share.value)),
+      }
+    }
+    if distinct.len() >= member_threshold as usize {
+      distinct.truncate(member_threshold as usize);
+      group_shares.push((group_index, recover_secret(member_threshold, &distinct)?));
+    }
+  }
+
+  if group_shares.len() < first.group_threshold as usize {
+    return Err(VulcanError::InsufficientShares);
+  }
+  group_shares.truncate(first.group_threshold as usize);
+  let group_shares: Vec<(u8, &[u8])> = group_shares
+    .iter()
+    .map(|(x, value)| (*x, value.as_slice()))
+    .collect();
+  let ems = recover_secret(first.group_threshold, &group_shares)?;
+  return Ok(decrypt(
+    &ems,
+    passphrase,
+    first.iteration_exponent,
+    first.identifier,
+    first.extendable,
+  ));
+}
+
+/// Parses the shares' words and recovers the master secret.
+pub fn combine_mnemonics(mnemonics: &[&str], passphrase: &str) -> Result<Vec<u8>, VulcanError> {
+  let shares = mnemonics
+    .iter()
+    .map(|mnemonic| Share::from_str(mnemonic))
+    .collect::<Result<Vec<Share>, VulcanError>>()?;
+  return combine_shares(&shares, passphrase);
+}
+
+/// Every wordlist word starting with `prefix`, for completing typed words.
+pub fn slip39_words_by_prefix(prefix: &str) -> &'static [&'static str] {
+  let start = WORDLIST.partition_point(|word| *word < prefix);
+  let len = WORDLIST[start..]
+    .iter()
+    .take_while(|word| word.starts_with(prefix))
+    .count();
+  return &WORDLIST[start..start + len];
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+  return if extendable {
+    CUSTOMIZATION_EXTENDABLE
+  } else {
+    CUSTOMIZATION
+  };
+}
+
+fn rs1024_polymod(customization: &[u8], data: &[u16]) -> u32 {
+  const GEN: [u32; 10] = [
+    0xe0e040, 0x1c1c080, 0x3838100, 0x7070200, 0xe0e0009, 0x1c0c2412, 0x38086c24, 0x3090fc48,
+    0x21b1f890, 0x3f3f120,
+  ];
+  let mut chk: u32 = 1;
+  for value in customization
+    .iter()
+    .map(|b| *b as u32)
+    .chain(data.iter().map(|w| *w as u32))
+  {
+    let b = chk >> 20;
+    chk = (chk & 0xfffff) << 10 ^ value;
+    for (i, gen) in GEN.iter().enumerate() {
+      if b >> i & 1 == 1 {
+        chk ^= gen;
+      }
+    }
+  }
+  return chk;
+}
+
+fn rs1024_checksum(customization: &[u8], data: &[u16]) -> [u16; CHECKSUM_WORDS] {
+  let mut values = data.to_vec();
+  values.extend_from_slice(&[0; CHECKSUM_WORDS]);
+  let polymod = rs1024_polymod(customization, &values) ^ 1;
+  return [
+    (polymod >> 20 & 0x3ff) as u16,
+    (polymod >> 10 & 0x3ff) as u16,
+    (polymod & 0x3ff) as u16,
+  ];
+}
+
+/// Multiplication in GF(256) with the Rijndael polynomial.
+fn gf_mul(a: u8, b: u8) -> u8 {
+  let (mut a, mut b, mut product) = (a, b, 0u8);
+  while b != 0 {
+    if b & 1 == 1 {
+      product ^= a;
+    }
+    let carry = a & 0x80;
+    a <<= 1;
+    if carry != 0 {
+      a ^= 0x1b;
+    }
+    b >>= 1;
+  }
+  return product;
+}
+
+fn gf_inv(a: u8) -> u8 {
+  // a^254, since a^255 = 1
+  let (mut result, mut base, mut exp) = (1u8, a, 254u8);
+  while exp > 0 {
+    if exp & 1 == 1 {
+      result = gf_mul(result, base);
+    }
+    base = gf_mul(base, base);
+    exp >>= 1;
+  }
+  return result;
+}
+
+/// Evaluates the polynomial through `shares` at `x`, bytewise.
+fn interpolate(shares: &[(u8, &[u8])], x: u8) -> Result<Vec<u8>, VulcanError> {
+  let len = shares
+    .first()
+    .ok_or(VulcanError::InsufficientShares)?
+    .1
+    .len();
+  for (i, (xi, value)) in shares.iter().enumerate() {
+    if value.len() != len || shares[..i].iter().any(|(xj, _)| xj == xi) {
+      return Err(VulcanError::ShareMismatch);
+    }
+    if *xi == x {
+      return Ok(value.to_vec());
+    }
+  }
+
+  let mut result = vec![0u8; len];
+  for (xi, value) in shares {
+    // Lagrange basis polynomial of xi at x
+    let (mut num, mut den) = (1u8, 1u8);
+    for (xj, _) in shares.iter().filter(|(xj, _)| xj != xi) {
+      num = gf_mul(num, x ^ xj);
+      den = gf_mul(den, xi ^ xj);
+    }
+    let basis = gf_mul(num, gf_inv(den));
+    for (r, y) in result.iter_mut().zip(value.iter()) {
+      *r ^= gf_mul(*y, basis);
+    }
+  }
+  return Ok(result);
+}
+
+fn share_digest(random: &[u8], secret: &[u8]) -> [u8; DIGEST_LEN] {
+  let mut engine = hmac::HmacEngine::<sha256::Hash>::new(random);
+  engine.input(secret);
+  let hmac = hmac::Hmac::<sha256::Hash>::from_engine(engine).into_inner();
+  let mut digest = [0u8; DIGEST_LEN];
+  digest.copy_from_slice(&hmac[..DIGEST_LEN]);
+  return digest;
+}
+
+fn split_secret(
+  threshold: u8,
+  count: u8,
+  secret: &[u8],
+  random: &mut RandomBytes,
+) -> Result<Vec<(u8, Vec<u8>)>, VulcanError> {
+  if threshold == 0 || threshold > count || count > MAX_SHARE_COUNT {
+    return Err(VulcanError::BadShamirGroups);
+  }
+  if threshold == 1 {
+    return Ok((0..count).map(|i| (i, secret.to_vec())).collect());
+  }
+
+  // threshold - 2 random shares, plus the digest and the secret itself fix
+  // the polynomial
+  let mut shares: Vec<(u8, Vec<u8>)> = (0..threshold - 2)
+    .map(|i| (i, random.bytes(secret.len())))
+    .collect();
+  let random_part = random.bytes(secret.len() - DIGEST_LEN);
+  let mut digest_share = share_digest(&random_part, secret).to_vec();
+  digest_share.extend_from_slice(&random_part);
+
+  let mut base: Vec<(u8, &[u8])> = shares.iter().map(|(x, v)| (*x, v.as_slice())).collect();
+  base.push((DIGEST_INDEX, &digest_share));
+  base.push((SECRET_INDEX, secret));
+  let mut rest = Vec::with_capacity((count - threshold + 2) as usize);
+  for i in threshold - 2..count {
+    rest.push((i, interpolate(&base, i)?));
+  }
+  shares.extend(rest);
+  return Ok(shares);
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, &[u8])]) -> Result<Vec<u8>, VulcanError> {
+  if threshold == 1 {
+    return Ok(shares[0].1.to_vec());
+  }
+  let secret = interpolate(shares, SECRET_INDEX)?;
+  let digest_share = interpolate(shares, DIGEST_INDEX)?;
+  if digest_share[..DIGEST_LEN] != share_digest(&digest_share[DIGEST_LEN..], &secret) {
+    return Err(VulcanError::ShareMismatch);
+  }
+  return Ok(secret);
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) {
+  for (block, chunk) in out.chunks_mut(32).enumerate() {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(password);
+    engine.input(salt);
+    engine.input(&(block as u32 + 1).to_be_bytes());
+    let mut u = hmac::Hmac::<sha256::Hash>::from_engine(engine).into_inner();
+    let mut t = u;
+    for _ in 1..iterations {
+      let mut engine = hmac::HmacEngine::<sha256::Hash>::new(password);
+      engine.input(&u);
+      u = hmac::Hmac::<sha256::Hash>::from_engine(engine).into_inner();
+      for (t, u) in t.iter_mut().zip(u.iter()) {
+        *t ^= u;
+      }
+    }
+    chunk.copy_from_slice(&t[..chunk.len()]);
+  }
+}
+
+/// The four round Feistel network that encrypts the master secret, run
+/// forwards to encrypt and backwards to decrypt.
+fn feistel(
+  secret: &[u8],
+  passphrase: &str,
+  iteration_exponent: u8,
+  identifier: u16,
+  extendable: bool,
+  rounds: impl Iterator<Item = u8>,
+) -> Vec<u8> {
+  let mut salt = Vec::new();
+  if !extendable {
+    salt.extend_from_slice(CUSTOMIZATION);
+    salt.extend_from_slice(&identifier.to_be_bytes());
+  }
+  let iterations = (BASE_ITERATIONS << iteration_exponent) / ROUNDS as u32;
+
+  let half = secret.len() / 2;
+  let (mut l, mut r) = (secret[..half].to_vec(), secret[half..].to_vec());
+  for i in rounds {
+    let mut password = vec![i];
+    password.extend_from_slice(passphrase.as_bytes());
+    let mut round_salt = salt.clone();
+    round_salt.extend_from_slice(&r);
+    let mut f = vec![0u8; half];
+    pbkdf2_sha256(&password, &round_salt, iterations, &mut f);
+    for (f, l) in f.iter_mut().zip(l.iter()) {
+      *f ^= l;
+    }
+    l = core::mem::replace(&mut r, f);
+  }
+  r.extend_from_slice(&l);
+  return r;
+}
+
+fn encrypt(
+  master_secret: &[u8],
+  passphrase: &str,
+  iteration_exponent: u8,
+  identifier: u16,
+  extendable: bool,
+) -> Vec<u8> {
+  return feistel(
+    master_secret,
+    passphrase,
+    iteration_exponent,
+    identifier,
+    extendable,
+    0..ROUNDS,
+  );
+}
+
+fn decrypt(
+  ems: &[u8],
+  passphrase: &str,
+  iteration_exponent: u8,
+  identifier: u16,
+  extendable: bool,
+) -> Vec<u8> {
+  return feistel(
+    ems,
+    passphrase,
+    iteration_exponent,
+    identifier,
+    extendable,
+    (0..ROUNDS).rev(),
+  );
+}
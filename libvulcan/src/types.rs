@@ -0,0 +1,17 @@
+/// Whether the account is a single-sig or a multisig cosigner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WalletType {
+  SingleSig,
+  MultiSig,
+}
+
+/// The output script the account receives to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptType {
+  /// P2SH-P2WPKH (BIP49) or P2SH-P2WSH
+  NestedSegwit,
+  /// P2WPKH (BIP84) or P2WSH
+  Segwit,
+  /// P2TR key path spend (BIP86)
+  Taproot,
+}
@@ -0,0 +1,300 @@
+use crate::{
+  descriptor::{account_descriptor, Keychain},
+  error::VulcanError,
+  psbt::{spent_output, Account},
+  types::{ScriptType, WalletType},
+};
+use alloc::{string::String, vec::Vec};
+use bitcoin::{
+  secp256k1::{self, Secp256k1, XOnlyPublicKey},
+  util::{
+    bip32,
+    psbt::{Input, PartiallySignedTransaction},
+    schnorr::TapTweak,
+    sighash::{Prevouts, SighashCache},
+  },
+  Address, EcdsaSig, EcdsaSighashType, Network, PublicKey, SchnorrSig, SchnorrSighashType, Script,
+  Transaction, TxOut,
+};
+
+/// A single-sig account that can derive addresses and sign PSBTs.
+pub struct Wallet {
+  secp: Secp256k1<secp256k1::All>,
+  root: bip32::ExtendedPrivKey,
+  fingerprint: bip32::Fingerprint,
+  path: bip32::DerivationPath,
+  script_type: ScriptType,
+  network: Network,
+}
+
+/// Builds the account at `path` from the root key.
+pub fn get_wallet(
+  root: &bip32::ExtendedPrivKey,
+  path: &bip32::DerivationPath,
+  script_type: ScriptType,
+  network: Network,
+) -> Result<Wallet, VulcanError> {
+  let secp = Secp256k1::new();
+  let fingerprint = root.fingerprint(&secp);
+
+  // make sure the account can be derived before handing out the wallet
+  root.derive_priv(&secp, path)?;
+
+  return Ok(Wallet {
+    secp,
+    root: *root,
+    fingerprint,
+    path: path.clone(),
+    script_type,
+    network,
+  });
+}
+
+/// Signs a segwit v0 input with SIGHASH_ALL.
+pub(crate) fn sign_segwit_v0(
+  secp: &Secp256k1<secp256k1::All>,
+  cache: &mut SighashCache<&Transaction>,
+  index: usize,
+  script_code: &Script,
+  value: u64,
+  xpriv: &bip32::ExtendedPrivKey,
+) -> Result<EcdsaSig, VulcanError> {
+  let sighash = cache
+    .segwit_signature_hash(index, script_code, value, EcdsaSighashType::All)
+    .map_err(|_| VulcanError::InconsistentPsbt)?;
+  let msg =
+    secp256k1::Message::from_slice(&sighash[..]).map_err(|_| VulcanError::InconsistentPsbt)?;
+  let sig = secp.sign_ecdsa(&msg, &xpriv.private_key);
+  return Ok(EcdsaSig::sighash_all(sig));
+}
+
+/// Splits `<account>/<change>/<index>` into `(change, index)`. `None` if the
+/// path isn't a receive or change address of the account.
+pub(crate) fn account_child(
+  account: &bip32::DerivationPath,
+  path: &bip32::DerivationPath,
+) -> Option<(bool, u32)> {
+  let account = account.as_ref();
+  let path = path.as_ref();
+  if path.len() != account.len() + 2 || !path.starts_with(account) {
+    return None;
+  }
+  let change = match path[account.len()] {
+    bip32::ChildNumber::Normal { index: 0 } => false,
+    bip32::ChildNumber::Normal { index: 1 } => true,
+    _ => return None,
+  };
+  match path[account.len() + 1] {
+    bip32::ChildNumber::Normal { index } => return Some((change, index)),
+    _ => return None,
+  }
+}
+
+/// The BIP143 script code if the output pays to `pubkey` as P2WPKH, either
+/// native or wrapped in P2SH.
+fn p2wpkh_script_code(utxo: &TxOut, input: &Input, pubkey: &PublicKey) -> Option<Script> {
+  let wpkh = pubkey.wpubkey_hash()?;
+  let program = Script::new_v0_p2wpkh(&wpkh);
+  if utxo.script_pubkey == program {
+    return Some(Script::new_p2pkh(&pubkey.pubkey_hash()));
+  }
+  match &input.redeem_script {
+    Some(redeem_script)
+      if *redeem_script == program
+        && utxo.script_pubkey == Script::new_p2sh(&program.script_hash()) =>
+    {
+      return Some(Script::new_p2pkh(&pubkey.pubkey_hash()));
+    }
+    _ => return None,
+  }
+}
+
+impl Wallet {
+  /// Master key fingerprint
+  pub fn fingerprint(&self) -> bip32::Fingerprint {
+    return self.fingerprint;
+  }
+
+  /// Account derivation path
+  pub fn path(&self) -> &bip32::DerivationPath {
+    return &self.path;
+  }
+
+  pub fn script_type(&self) -> ScriptType {
+    return self.script_type;
+  }
+
+  pub fn network(&self) -> Network {
+    return self.network;
+  }
+
+  /// Account xpub
+  pub fn xpub(&self) -> Result<bip32::ExtendedPubKey, VulcanError> {
+    let xpriv = self.root.derive_priv(&self.secp, &self.path)?;
+    return Ok(bip32::ExtendedPubKey::from_priv(&self.secp, &xpriv));
+  }
+
+  /// Output descriptor for the receive or change chain, with checksum.
+  pub fn descriptor(&self, change: bool) -> Result<String, VulcanError> {
+    return self.keychain_descriptor(Keychain::from_change(change));
+  }
+
+  /// Output descriptor for one or, with [`Keychain::Multipath`], both chains.
+  pub fn keychain_descriptor(&self, keychain: Keychain) -> Result<String, VulcanError> {
+    let key = (self.fingerprint, self.path.clone(), self.xpub()?);
+    return account_descriptor(
+      &WalletType::SingleSig,
+      &self.script_type,
+      1,
+      &[key],
+      keychain,
+    );
+  }
+
+  /// Address at `<path>/<change>/<index>`
+  pub fn address(&self, change: bool, index: u32) -> Result<Address, VulcanError> {
+    let path = self.path.extend([
+      bip32::ChildNumber::from_normal_idx(if change { 1 } else { 0 })?,
+      bip32::ChildNumber::from_normal_idx(index)?,
+    ]);
+    let xpriv = self.root.derive_priv(&self.secp, &path)?;
+    let pubkey = xpriv.to_priv().public_key(&self.secp);
+    match self.script_type {
+      ScriptType::NestedSegwit => {
+        return Address::p2shwpkh(&pubkey, self.network)
+          .map_err(|_| VulcanError::NonStandardScript);
+      }
+      ScriptType::Segwit => {
+        return Address::p2wpkh(&pubkey, self.network).map_err(|_| VulcanError::NonStandardScript);
+      }
+      ScriptType::Taproot => {
+        let internal_key = XOnlyPublicKey::from(pubkey.inner);
+        return Ok(Address::p2tr(&self.secp, internal_key, None, self.network));
+      }
+    }
+  }
+
+  /// Signs every input that has a derivation for our fingerprint.
+  ///
+  /// P2WPKH and P2SH-P2WPKH inputs get an ECDSA signature in `partial_sigs`,
+  /// BIP86 taproot inputs a key path signature in `tap_key_sig`. Returns
+  /// whether at least one signature was added.
+  pub fn sign(&self, psbt: &mut PartiallySignedTransaction) -> Result<bool, VulcanError> {
+    if psbt.inputs.len() != psbt.unsigned_tx.input.len() {
+      return Err(VulcanError::InconsistentPsbt);
+    }
+
+    let tx = psbt.unsigned_tx.clone();
+    let mut cache = SighashCache::new(&tx);
+    let mut signed = false;
+
+    // taproot sighashes commit to every spent output
+    let prevouts: Option<Vec<TxOut>> = psbt
+      .inputs
+      .iter()
+      .enumerate()
+      .map(|(i, input)| spent_output(&tx, i, input).ok())
+      .collect();
+
+    for (i, input) in psbt.inputs.iter_mut().enumerate() {
+      let utxo = match spent_output(&tx, i, input) {
+        Ok(utxo) => utxo,
+        // nothing trustworthy to commit to
+        Err(_) => continue,
+      };
+
+      let keys: Vec<(secp256k1::PublicKey, bip32::DerivationPath)> = input
+        .bip32_derivation
+        .iter()
+        .filter(|(_, (fingerprint, _))| *fingerprint == self.fingerprint)
+        .map(|(pubkey, (_, path))| (*pubkey, path.clone()))
+        .collect();
+
+      for (pubkey, path) in keys {
+        let xpriv = self.root.derive_priv(&self.secp, &path)?;
+        if secp256k1::PublicKey::from_secret_key(&self.secp, &xpriv.private_key) != pubkey {
+          continue;
+        }
+
+        let pubkey = PublicKey::new(pubkey);
+        let script_code = match p2wpkh_script_code(&utxo, input, &pubkey) {
+          Some(script_code) => script_code,
+          None => continue,
+        };
+        let sig = sign_segwit_v0(&self.secp, &mut cache, i, &script_code, utxo.value, &xpriv)?;
+        input.partial_sigs.insert(pubkey, sig);
+        signed = true;
+      }
+
+      // key path spends only, the internal key has no leaf hashes
+      let internal_key = match input.tap_internal_key {
+        Some(key) => key,
+        None => continue,
+      };
+      // BIP86 outputs commit to no script tree, a merkle root from the PSBT
+      // would sign for scripts nobody checked
+      if input.tap_merkle_root.is_some() {
+        continue;
+      }
+      if utxo.script_pubkey != Script::new_v1_p2tr(&self.secp, internal_key, None) {
+        continue;
+      }
+      let path = match input.tap_key_origins.get(&internal_key) {
+        Some((leaves, (fingerprint, path)))
+          if leaves.is_empty() && *fingerprint == self.fingerprint =>
+        {
+          path.clone()
+        }
+        _ => continue,
+      };
+      let prevouts = match &prevouts {
+        Some(prevouts) => prevouts,
+        None => continue,
+      };
+
+      let xpriv = self.root.derive_priv(&self.secp, &path)?;
+      let keypair = secp256k1::KeyPair::from_secret_key(&self.secp, xpriv.private_key);
+      if XOnlyPublicKey::from_keypair(&keypair) != internal_key {
+        continue;
+      }
+      let keypair = keypair.tap_tweak(&self.secp, None).into_inner();
+
+      let sighash = cache
+        .taproot_key_spend_signature_hash(i, &Prevouts::All(prevouts), SchnorrSighashType::Default)
+        .map_err(|_| VulcanError::InconsistentPsbt)?;
+      let msg =
+        secp256k1::Message::from_slice(&sighash[..]).map_err(|_| VulcanError::InconsistentPsbt)?;
+      let sig = self.secp.sign_schnorr_no_aux_rand(&msg, &keypair);
+
+      input.tap_key_sig = Some(SchnorrSig {
+        sig,
+        hash_ty: SchnorrSighashType::Default,
+      });
+      signed = true;
+    }
+
+    return Ok(signed);
+  }
+}
+
+impl Account for Wallet {
+  fn fingerprint(&self) -> bip32::Fingerprint {
+    return self.fingerprint;
+  }
+
+  fn network(&self) -> Network {
+    return self.network;
+  }
+
+  fn path(&self) -> &bip32::DerivationPath {
+    return &self.path;
+  }
+
+  fn child(&self, path: &bip32::DerivationPath) -> Option<(bool, u32)> {
+    return account_child(&self.path, path);
+  }
+
+  fn script_pubkey(&self, change: bool, index: u32) -> Result<Script, VulcanError> {
+    return Ok(self.address(change, index)?.script_pubkey());
+  }
+}
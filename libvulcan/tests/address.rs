@@ -0,0 +1,138 @@
+//! Receive and change address search, with the BIP84 and BIP86 vectors for
+//! the mnemonic "abandon abandon ... about".
+
+use common::{cosigner, root, wallet, MNEMONIC};
+use libvulcan::{
+  address_script_type,
+  bitcoin::{util::bip32::DerivationPath, Network},
+  find_address, get_multisig_wallet, get_path, get_wallet, parse_address, ScriptType, VulcanError,
+  WalletType, DEFAULT_GAP_LIMIT,
+};
+use std::str::FromStr;
+
+mod common;
+
+const COSIGNER: &str = "forum undo fragile fade shy sign arrest garment culture tube off merit";
+
+#[test]
+fn single_sig() {
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit);
+  let receive = parse_address(
+    "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+    Network::Bitcoin,
+  )
+  .unwrap();
+  let found = find_address(&w, &receive, DEFAULT_GAP_LIMIT)
+    .unwrap()
+    .unwrap();
+  assert!(!found.change);
+  assert_eq!(found.index, 1);
+  assert_eq!(found.to_string(), "m/84h/0h/0h/0/1");
+
+  let change = parse_address(
+    "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+    Network::Bitcoin,
+  )
+  .unwrap();
+  let found = find_address(&w, &change, DEFAULT_GAP_LIMIT)
+    .unwrap()
+    .unwrap();
+  assert!(found.change);
+  assert_eq!(found.to_string(), "m/84h/0h/0h/1/0");
+
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Taproot);
+  let taproot = parse_address(
+    "bc1p3qkhfews2uk44qtvauqyr2ttdsw7svhkl9nkm9s9c3x4ax5h60wqwruhk7",
+    Network::Bitcoin,
+  )
+  .unwrap();
+  assert_eq!(address_script_type(&taproot), Some(ScriptType::Taproot));
+  let found = find_address(&w, &taproot, DEFAULT_GAP_LIMIT)
+    .unwrap()
+    .unwrap();
+  assert_eq!(found.to_string(), "m/86h/0h/0h/1/0");
+}
+
+#[test]
+fn gap_limit() {
+  let w = wallet(MNEMONIC, Network::Testnet, ScriptType::Segwit);
+  let address = w.address(false, 17).unwrap();
+  let found = find_address(&w, &address, 18).unwrap().unwrap();
+  assert_eq!(found.to_string(), "m/84h/1h/0h/0/17");
+  assert_eq!(find_address(&w, &address, 17), Ok(None));
+
+  // another account of the same seed isn't this one
+  let other = get_wallet(
+    &root(MNEMONIC, Network::Testnet),
+    &DerivationPath::from_str("m/84h/1h/1h").unwrap(),
+    ScriptType::Segwit,
+    Network::Testnet,
+  )
+  .unwrap();
+  assert_eq!(
+    find_address(&w, &other.address(false, 0).unwrap(), 100),
+    Ok(None)
+  );
+}
+
+#[test]
+fn multisig() {
+  let path = get_path(
+    &Network::Testnet,
+    &WalletType::MultiSig,
+    &ScriptType::Segwit,
+  )
+  .unwrap();
+  let w = get_multisig_wallet(
+    &root(MNEMONIC, Network::Testnet),
+    &path,
+    ScriptType::Segwit,
+    Network::Testnet,
+    2,
+    &[cosigner(MNEMONIC, &path), cosigner(COSIGNER, &path)],
+  )
+  .unwrap();
+
+  let address = w.address(true, 3).unwrap();
+  let text = format!("bitcoin:{}?amount=0.1", address.to_string().to_uppercase());
+  let scanned = parse_address(&text, Network::Testnet).unwrap();
+  let found = find_address(&w, &scanned, DEFAULT_GAP_LIMIT)
+    .unwrap()
+    .unwrap();
+  assert_eq!(found.to_string(), "m/48h/1h/0h/2h/1/3");
+
+  // our own single-sig address isn't in the multisig wallet
+  let single = wallet(MNEMONIC, Network::Testnet, ScriptType::Segwit);
+  assert_eq!(
+    find_address(&w, &single.address(false, 0).unwrap(), DEFAULT_GAP_LIMIT),
+    Ok(None)
+  );
+}
+
+#[test]
+fn bad_addresses() {
+  assert_eq!(
+    parse_address("bc1qnotanaddress", Network::Bitcoin),
+    Err(VulcanError::BadAddress)
+  );
+  assert_eq!(
+    parse_address(
+      "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+      Network::Testnet
+    ),
+    Err(VulcanError::WrongNetwork)
+  );
+  let legacy = parse_address("1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA", Network::Bitcoin).unwrap();
+  assert_eq!(address_script_type(&legacy), None);
+
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit);
+  let testnet = parse_address(
+    "tb1q6rz28mcfaxtmd6v789l9rrlrusdprr9pqcpvkl",
+    Network::Testnet,
+  )
+  .unwrap();
+  assert_eq!(
+    find_address(&w, &testnet, DEFAULT_GAP_LIMIT),
+    Err(VulcanError::WrongNetwork)
+  );
+}
@@ -0,0 +1,119 @@
+//! Test vectors from BIP85:
+//! https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki
+
+use libvulcan::{
+  bip85_hex, bip85_mnemonic, bip85_wif, bip85_xprv,
+  bitcoin::{hashes::hex::ToHex, util::bip32::ExtendedPrivKey},
+  derive_entropy, Bip85Application, VulcanError,
+};
+use std::str::FromStr;
+
+const MASTER: &str = "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb";
+
+fn root() -> ExtendedPrivKey {
+  return ExtendedPrivKey::from_str(MASTER).unwrap();
+}
+
+#[test]
+fn entropy() {
+  assert_eq!(
+    derive_entropy(&root(), &[0, 0]).unwrap().to_hex(),
+    "efecfbccffea313214232d29e71563d941229afb4338c21f9517c41aaa0d16f00b83d2a09ef747e7a64e8e2bd5a14869e693da66ce94ac2da570ab7ee48618f7"
+  );
+  assert_eq!(
+    derive_entropy(&root(), &[0, 1]).unwrap().to_hex(),
+    "70c6e3e8ebee8dc4c0dbba66076819bb8c09672527c4277ca8729532ad711872218f826919f6b67218adde99018a6df9095ab2b58d803b5b93ec9802085a690e"
+  );
+  // indexes are hardened, so they must fit in 31 bits
+  assert!(derive_entropy(&root(), &[0, 1 << 31]).is_err());
+}
+
+#[test]
+fn bip39() {
+  let vectors = [
+    (
+      12,
+      "6250b68daf746d12a24d58b4787a714b",
+      "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose",
+    ),
+    (
+      18,
+      "938033ed8b12698449d4bbca3c853c66b293ea1b1ce9d9dc",
+      "near account window bike charge season chef number sketch tomorrow excuse sniff circle vital hockey outdoor supply token",
+    ),
+    (
+      24,
+      "ae131e2312cdc61331542efe0d1077bac5ea803adf24b313a4f0e48e9c51f37f",
+      "puppy ocean match cereal symbol another shed magic wrap hammer bulb intact gadget divorce twin tonight reason outdoor destroy simple truth cigar social volcano",
+    ),
+  ];
+  for (words, entropy, phrase) in vectors.iter() {
+    let mnemonic = bip85_mnemonic(&root(), *words, 0).unwrap();
+    assert_eq!(mnemonic.to_entropy().to_hex(), *entropy);
+    assert_eq!(mnemonic.to_string(), *phrase);
+    assert_eq!(
+      Bip85Application::Bip39(*words).derive(&root(), 0).unwrap(),
+      *phrase
+    );
+  }
+
+  // other indexes give other seeds
+  assert_ne!(
+    bip85_mnemonic(&root(), 12, 1).unwrap().to_string(),
+    vectors[0].2
+  );
+  assert_eq!(
+    bip85_mnemonic(&root(), 15, 0),
+    Err(VulcanError::BadWordCount(15))
+  );
+}
+
+#[test]
+fn wif() {
+  let key = bip85_wif(&root(), 0).unwrap();
+  assert_eq!(
+    key.inner.secret_bytes().to_hex(),
+    "7040bb53104f27367f317558e78a994ada7296c6fde36a364e5baf206e502bb1"
+  );
+  assert_eq!(
+    key.to_wif(),
+    "Kzyv4uF39d4Jrw2W7UryTHwZr1zQVNk4dAFyqE6BuMrMh1Za7uhp"
+  );
+  assert_eq!(
+    Bip85Application::Wif.derive(&root(), 0).unwrap(),
+    key.to_wif()
+  );
+}
+
+#[test]
+fn xprv() {
+  let xprv = bip85_xprv(&root(), 0).unwrap();
+  assert_eq!(
+    xprv.private_key.secret_bytes().to_hex(),
+    "ead0b33988a616cf6a497f1c169d9e92562604e38305ccd3fc96f2252c177682"
+  );
+  assert_eq!(
+    xprv.to_string(),
+    "xprv9s21ZrQH143K2srSbCSg4m4kLvPMzcWydgmKEnMmoZUurYuBuYG46c6P71UGXMzmriLzCCBvKQWBUv3vPB3m1SATMhp3uEjXHJ42jFg7myX"
+  );
+  assert_eq!(
+    Bip85Application::Xprv.derive(&root(), 0).unwrap(),
+    xprv.to_string()
+  );
+}
+
+#[test]
+fn hex() {
+  let hex = "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f878555d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c";
+  assert_eq!(bip85_hex(&root(), 64, 0).unwrap().to_hex(), hex);
+  assert_eq!(Bip85Application::Hex(64).derive(&root(), 0).unwrap(), hex);
+  assert_eq!(bip85_hex(&root(), 16, 0).unwrap().len(), 16);
+  assert_eq!(
+    bip85_hex(&root(), 15, 0),
+    Err(VulcanError::BadEntropyLength(15))
+  );
+  assert_eq!(
+    bip85_hex(&root(), 65, 0),
+    Err(VulcanError::BadEntropyLength(65))
+  );
+}
@@ -0,0 +1,208 @@
+//! Runs the `vulcan-cli` binary with the BIP84 test vector seed.
+
+use common::{key_origin, testnet_wallet, MNEMONIC};
+use libvulcan::{
+  bitcoin::{
+    util::psbt::PartiallySignedTransaction, OutPoint, PublicKey, Script, Transaction, TxIn, TxOut,
+    Witness,
+  },
+  PsbtV2, RequiredLockTime,
+};
+use std::{env, fs, process::Command};
+
+mod common;
+
+fn cli(args: &[&str]) -> (bool, String, String) {
+  return cli_with(MNEMONIC, args);
+}
+
+fn cli_with(mnemonic: &str, args: &[&str]) -> (bool, String, String) {
+  let output = Command::new(env!("CARGO_BIN_EXE_vulcan-cli"))
+    .args(args)
+    .env("VULCAN_MNEMONIC", mnemonic)
+    .output()
+    .unwrap();
+  return (
+    output.status.success(),
+    String::from_utf8(output.stdout).unwrap(),
+    String::from_utf8(output.stderr).unwrap(),
+  );
+}
+
+#[test]
+fn derive() {
+  let (ok, out, _) = cli(&["--network", "mainnet", "derive", "--addresses", "1"]);
+  assert!(ok);
+  assert!(out.contains("fingerprint: 73c5da0a"));
+  assert!(out.contains("zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs"));
+  assert!(out.contains("receive 0: bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"));
+  assert!(out.contains("change 0: bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el"));
+
+  // testnet unless asked otherwise
+  let (ok, out, _) = cli(&["derive", "--addresses", "0"]);
+  assert!(ok);
+  assert!(out.contains("path: m/84'/1'/0'"));
+}
+
+#[test]
+fn seedqr() {
+  let (ok, out, _) = cli(&["seedqr", "encode"]);
+  assert!(ok);
+  assert_eq!(
+    out.trim(),
+    "000000000000000000000000000000000000000000000003"
+  );
+
+  let (ok, out, _) = cli(&["seedqr", "decode", "--format", "compact", &"00".repeat(16)]);
+  assert!(ok);
+  assert!(out.contains(&format!("mnemonic: {}", MNEMONIC)));
+
+  let (_, extended, _) = cli(&[
+    "--network",
+    "mainnet",
+    "seedqr",
+    "encode",
+    "--format",
+    "extended",
+  ]);
+  let (ok, out, _) = cli(&[
+    "--network",
+    "mainnet",
+    "seedqr",
+    "decode",
+    "--format",
+    "extended",
+    extended.trim(),
+  ]);
+  assert!(ok);
+  assert!(out.contains("path: m/84'/0'/0'"));
+}
+
+#[test]
+fn verify_address() {
+  let (ok, out, _) = cli(&[
+    "--network",
+    "mainnet",
+    "verify-address",
+    "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+  ]);
+  assert!(ok);
+  assert!(out.contains("change address 0 at m/84h/0h/0h/1/0"));
+
+  let (ok, _, err) = cli(&[
+    "verify-address",
+    "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+  ]);
+  assert!(!ok);
+  assert!(err.starts_with("error:"));
+}
+
+#[test]
+fn sign() {
+  // a testnet spend from another seed's BIP84 account, with change
+  let mnemonic = "typical bicycle winter insane actor chat upper lazy brother club rib speed bid word caught differ fragile venture merge seed glimpse head exile success";
+  let psbt = "cHNidP8BAHECAAAAAVBfV44DutAVcwBhLjMDvKtwbQrmjkH+MFjFXPUqFS9/AQAAAAD9////AkZHmAAAAAAAFgAUZjVsZYVAouW7J4+EBUkSr57hq1cQJwAAAAAAABYAFBAjFi4nxS8Zk94GX+oLLSGwP5sObP4AAAABAHECAAAAAQRANrAsHCjxffzlyW5XgWuP4zvg5uyK69HEtKp36018AAAAAAD9////AhAnAAAAAAAAFgAUe5yb+brLgwDgNAd2FhiCD6ZEJ/vjbpgAAAAAABYAFLXGhIrgQeONcX9YAuONxfsqpR8rSv4AAAEBH+NumAAAAAAAFgAUtcaEiuBB441xf1gC443F+yqlHysiBgPF+tMr9FV6qyy6i32WuHnS+wB30GZgodSYsGaNPeFM7RjiaHvrVAAAgAEAAIAAAACAAQAAAAAAAAAAIgICyh7fjg8uUNT8Kzs5cHSGTKBa4QiUNOGTd7+zkoa0AqwY4mh761QAAIABAACAAAAAgAEAAAABAAAAAAA=";
+  let path = env::temp_dir().join("vulcan-cli-test.psbt");
+  fs::write(&path, psbt).unwrap();
+  let path = path.to_str().unwrap();
+
+  let (ok, out, _) = cli_with(mnemonic, &["--network", "testnet", "analyze", path]);
+  assert!(ok);
+  assert!(out.contains("fee: 141 sats"));
+  assert!(out.contains("spend: tb1qzq33vt38c5h3ny77qe075zedyxcrlxcws3v0n8 10000 sats"));
+  assert!(out.contains("change: tb1qvc6kcev9gz3wtwe837zq2jgj470wr26hpk0mnk 9979718 sats"));
+
+  let (ok, out, _) = cli_with(mnemonic, &["--network", "testnet", "sign", path]);
+  assert!(ok);
+  assert!(out.starts_with("cHNidP8"));
+  assert!(out.trim().len() > psbt.len());
+
+  // on mainnet neither the input nor the change is ours
+  let (ok, _, err) = cli_with(mnemonic, &["--network", "mainnet", "sign", path]);
+  assert!(!ok);
+  assert!(err.contains("blocked: input 0 wrong network"));
+  assert!(err.contains("blocked: output 0 fake change"));
+
+  // nothing of the other seed's to sign
+  let (ok, _, _) = cli(&["--network", "testnet", "sign", path]);
+  assert!(!ok);
+  fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn sign_v2_keeps_v2_fields() {
+  let wallet = testnet_wallet(MNEMONIC);
+
+  let prev = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![],
+    output: vec![TxOut {
+      value: 100_000,
+      script_pubkey: wallet.address(false, 0).unwrap().script_pubkey(),
+    }],
+  };
+  let tx = Transaction {
+    version: 2,
+    lock_time: 10000,
+    input: vec![TxIn {
+      previous_output: OutPoint::new(prev.txid(), 0),
+      script_sig: Script::new(),
+      sequence: 0xfffffffd,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 99_000,
+      script_pubkey: wallet.address(false, 1).unwrap().script_pubkey(),
+    }],
+  };
+  let mut v2 = PsbtV2::from_v0(PartiallySignedTransaction::from_unsigned_tx(tx).unwrap());
+  let (key, origin) = key_origin(MNEMONIC, "m/84'/1'/0'/0/0");
+  v2.psbt.inputs[0].witness_utxo = Some(prev.output[0].clone());
+  v2.psbt.inputs[0].non_witness_utxo = Some(prev);
+  v2.psbt.inputs[0].bip32_derivation.insert(key, origin);
+  // inputs and outputs can still be added, the input allows either locktime
+  v2.fallback_locktime = None;
+  v2.tx_modifiable = Some(0x03);
+  v2.locktimes = vec![RequiredLockTime {
+    time: Some(1657088652),
+    height: Some(10000),
+  }];
+
+  let input = env::temp_dir().join("vulcan-cli-test-v2.psbt");
+  let output = env::temp_dir().join("vulcan-cli-test-v2-signed.psbt");
+  fs::write(&input, v2.serialize()).unwrap();
+
+  let (ok, _, _) = cli(&[
+    "--network",
+    "testnet",
+    "sign",
+    input.to_str().unwrap(),
+    "--output",
+    output.to_str().unwrap(),
+  ]);
+  assert!(ok);
+
+  let signed = PsbtV2::deserialize(&fs::read(&output).unwrap()).unwrap();
+  assert!(signed.psbt.inputs[0]
+    .partial_sigs
+    .contains_key(&PublicKey::new(key)));
+  // the SIGHASH_ALL signature fixes the inputs and outputs
+  assert_eq!(signed.tx_modifiable, Some(0x00));
+  assert_eq!(signed.locktimes, v2.locktimes);
+  assert_eq!(signed.fallback_locktime, None);
+  assert_eq!(signed.psbt.unsigned_tx, v2.psbt.unsigned_tx);
+  fs::remove_file(input).unwrap();
+  fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn export() {
+  let (ok, out, _) = cli(&["--network", "mainnet", "export", "--format", "descriptor"]);
+  assert!(ok);
+  assert!(out.starts_with("wpkh([73c5da0a/84h/0h/0h]xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/<0;1>/*)"));
+
+  let (ok, out, _) = cli(&["--network", "mainnet", "export", "--format", "sparrow"]);
+  assert!(ok);
+  assert!(out.contains("\"xfp\": \"73C5DA0A\""));
+}
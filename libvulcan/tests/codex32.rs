@@ -0,0 +1,149 @@
+//! Test vectors from BIP-93.
+
+use libvulcan::{
+  bitcoin::{hashes::hex::ToHex, util::bip32::Fingerprint},
+  codex32_combine, codex32_identifier, codex32_split, Codex32Share, VulcanError,
+};
+use std::str::FromStr;
+
+fn share(s: &str) -> Codex32Share {
+  return Codex32Share::from_str(s).unwrap();
+}
+
+#[test]
+fn secrets() {
+  let secret = share("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw");
+  assert_eq!(secret.threshold, 0);
+  assert_eq!(secret.identifier, "test");
+  assert_eq!(secret.index, 's');
+  assert_eq!(
+    codex32_combine(&[secret]).unwrap().to_hex(),
+    "318c6318c6318c6318c6318c6318c631"
+  );
+
+  let secret = share("ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyqqtum9pgv99ycma");
+  let seed = codex32_combine(std::slice::from_ref(&secret)).unwrap();
+  assert_eq!(
+    seed.to_hex(),
+    "ffeeddccbbaa99887766554433221100ffeeddccbbaa99887766554433221100"
+  );
+  // the padding bits are zero, so encoding the seed gives the same string
+  assert_eq!(Codex32Share::from_seed(&seed, "leet").unwrap(), secret);
+
+  // 512 bit seeds have the long checksum
+  let long = "MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK";
+  let secret = share(long);
+  assert_eq!(codex32_combine(std::slice::from_ref(&secret)).unwrap().to_hex(), "dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9");
+  assert_eq!(secret.to_string(), long.to_lowercase());
+}
+
+#[test]
+fn shares() {
+  let a = share("MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM");
+  let c = share("MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN");
+  assert_eq!(a.threshold, 2);
+  assert_eq!(a.identifier, "name");
+  assert_eq!(
+    codex32_combine(&[a.clone(), c.clone()]).unwrap().to_hex(),
+    "d1808e096b35b209ca12132b264662a5"
+  );
+  assert_eq!(
+    codex32_combine(std::slice::from_ref(&a)),
+    Err(VulcanError::InsufficientShares)
+  );
+  assert_eq!(
+    codex32_combine(&[a.clone(), a.clone()]),
+    Err(VulcanError::InsufficientShares)
+  );
+
+  // the secret share and another share of the same polynomial
+  let s = share("MS12NAMES6XQGUZTTXKEQNJSJZV4JV3NZ5K3KWGSPHUH6EVW");
+  let d = share("MS12NAMEDLL4F8JLH4E5VDVULDLFXU2JHDNLSM97XVENRXEG");
+  assert_eq!(
+    codex32_combine(&[d.clone(), a.clone()]).unwrap(),
+    codex32_combine(&[s]).unwrap()
+  );
+  assert_eq!(
+    codex32_combine(&[c, d]).unwrap().to_hex(),
+    "d1808e096b35b209ca12132b264662a5"
+  );
+
+  let other = share("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw");
+  assert_eq!(
+    codex32_combine(&[a, other]),
+    Err(VulcanError::ShareMismatch)
+  );
+}
+
+#[test]
+fn split_and_combine() {
+  let seed: Vec<u8> = (0..64).collect();
+  let shares = codex32_split(&seed, "cash", 3, 5, &[3u8; 32]).unwrap();
+  assert_eq!(shares.len(), 5);
+  let indexes: String = shares.iter().map(|share| share.index).collect();
+  assert_eq!(indexes, "acdef");
+
+  for share in &shares {
+    assert_eq!(share.threshold, 3);
+    let text = share.to_string();
+    assert!(text.starts_with("ms13cash"));
+    assert_eq!(text.len(), 127);
+    assert_eq!(
+      Codex32Share::from_str(&text.to_uppercase()).unwrap(),
+      *share
+    );
+  }
+
+  assert_eq!(
+    codex32_combine(&[shares[4].clone(), shares[1].clone(), shares[2].clone()]).unwrap(),
+    seed
+  );
+  assert_eq!(
+    codex32_combine(&shares[..2]),
+    Err(VulcanError::InsufficientShares)
+  );
+
+  let seed = [7u8; 16];
+  let shares = codex32_split(&seed, "vlcn", 2, 3, &[4u8; 32]).unwrap();
+  assert_eq!(shares[0].to_string().len(), 48);
+  assert_eq!(codex32_combine(&shares[1..]).unwrap(), seed);
+
+  assert_eq!(
+    codex32_split(&seed, "vlcn", 1, 3, &[4u8; 32]),
+    Err(VulcanError::BadShamirGroups)
+  );
+  assert_eq!(
+    codex32_split(&seed, "vlcn", 3, 2, &[4u8; 32]),
+    Err(VulcanError::BadShamirGroups)
+  );
+  assert_eq!(
+    codex32_split(&seed, "vlcb", 2, 3, &[4u8; 32]),
+    Err(VulcanError::BadCodex32)
+  );
+}
+
+#[test]
+fn identifier() {
+  let fingerprint = Fingerprint::from(&[0x73, 0xc5, 0xda, 0x0a][..]);
+  assert_eq!(codex32_identifier(&fingerprint), "w0za");
+}
+
+#[test]
+fn invalid_strings() {
+  for s in [
+    // bad checksum
+    "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlx",
+    // mixed case
+    "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4NZVCA9CMCZLW",
+    // not an ms string
+    "bc10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw",
+    // unsplit but not the secret
+    "ms10testaxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw",
+    "ms1",
+    "",
+  ]
+  .iter()
+  {
+    assert_eq!(Codex32Share::from_str(s), Err(VulcanError::BadCodex32));
+  }
+}
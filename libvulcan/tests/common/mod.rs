@@ -0,0 +1,74 @@
+//! Keys and wallets shared by the integration tests. Each test file uses a
+//! different part of them.
+#![allow(dead_code)]
+
+use libvulcan::{
+  bip39::{Language, Mnemonic},
+  bitcoin::{
+    secp256k1::{PublicKey, Secp256k1},
+    util::{
+      bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey, KeySource},
+      psbt::PartiallySignedTransaction,
+    },
+    Network,
+  },
+  get_path, get_root, get_wallet, Cosigner, ScriptType, Wallet, WalletType,
+};
+use std::str::FromStr;
+
+pub const MNEMONIC: &str =
+  "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+/// Signers of the multisig tests, `MNEMONIC` first
+pub const MNEMONICS: [&str; 3] = [
+  MNEMONIC,
+  "forum undo fragile fade shy sign arrest garment culture tube off merit",
+  "approve fruit lens brass ring actual stool coin doll boss strong rate",
+];
+
+pub fn root(phrase: &str, network: Network) -> ExtendedPrivKey {
+  let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase).unwrap();
+  return get_root(&mnemonic, network).unwrap();
+}
+
+/// The single-sig account of `phrase` at its default path.
+pub fn wallet(phrase: &str, network: Network, script_type: ScriptType) -> Wallet {
+  let path = get_path(&network, &WalletType::SingleSig, &script_type).unwrap();
+  return get_wallet(&root(phrase, network), &path, script_type, network).unwrap();
+}
+
+/// The BIP84 testnet account of `phrase` the PSBT tests spend from.
+pub fn testnet_wallet(phrase: &str) -> Wallet {
+  return wallet(phrase, Network::Testnet, ScriptType::Segwit);
+}
+
+/// `phrase`'s testnet key at `path` as a multisig cosigner.
+pub fn cosigner(phrase: &str, path: &DerivationPath) -> Cosigner {
+  let secp = Secp256k1::new();
+  let root = root(phrase, Network::Testnet);
+  let xpriv = root.derive_priv(&secp, path).unwrap();
+  return Cosigner {
+    fingerprint: root.fingerprint(&secp),
+    path: path.clone(),
+    xpub: ExtendedPubKey::from_priv(&secp, &xpriv),
+  };
+}
+
+/// `phrase`'s testnet key at `path`, with the origin a PSBT records for it.
+pub fn key_origin(phrase: &str, path: &str) -> (PublicKey, KeySource) {
+  let secp = Secp256k1::new();
+  let root = root(phrase, Network::Testnet);
+  let path = DerivationPath::from_str(path).unwrap();
+  let pubkey = root
+    .derive_priv(&secp, &path)
+    .unwrap()
+    .to_priv()
+    .public_key(&secp)
+    .inner;
+  return (pubkey, (root.fingerprint(&secp), path));
+}
+
+/// Adds the derivation of `phrase`'s key at `path` to output `i`.
+pub fn claim(psbt: &mut PartiallySignedTransaction, i: usize, phrase: &str, path: &str) {
+  let (pubkey, origin) = key_origin(phrase, path);
+  psbt.outputs[i].bip32_derivation.insert(pubkey, origin);
+}
@@ -0,0 +1,171 @@
+use common::{cosigner, root, MNEMONICS};
+use libvulcan::{
+  bitcoin::{
+    util::{base58, bip32::DerivationPath},
+    Network,
+  },
+  get_path, parse_multisig_config, MultisigConfig, ScriptType, VulcanError, WalletType,
+};
+use std::str::FromStr;
+
+mod common;
+
+/// `(fingerprint, Vpub)` of every signer at m/48h/1h/0h/2h
+fn keys() -> Vec<(String, String)> {
+  let path = get_path(
+    &Network::Testnet,
+    &WalletType::MultiSig,
+    &ScriptType::Segwit,
+  )
+  .unwrap();
+  return MNEMONICS
+    .iter()
+    .map(|phrase| {
+      let cosigner = cosigner(phrase, &path);
+      let mut data = cosigner.xpub.encode();
+      data[0..4].copy_from_slice(&[0x02, 0x57, 0x54, 0x83]);
+      (
+        cosigner.fingerprint.to_string().to_uppercase(),
+        base58::check_encode_slice(&data),
+      )
+    })
+    .collect();
+}
+
+fn coldcard_file(policy: &str, format: &str) -> String {
+  let mut file = format!(
+    "# Coldcard Multisig setup file (created by Sparrow)\n#\nName: Test Wallet\nPolicy: {}\nDerivation: m/48'/1'/0'/2'\nFormat: {}\n\n",
+    policy, format
+  );
+  for (fingerprint, xpub) in keys() {
+    file.push_str(&format!("{}: {}\n", fingerprint, xpub));
+  }
+  return file;
+}
+
+#[test]
+fn coldcard_text_format() {
+  let config = parse_multisig_config(&coldcard_file("2 of 3", "P2WSH")).unwrap();
+  assert_eq!(config.name, "Test Wallet");
+  assert_eq!(config.threshold, 2);
+  assert_eq!(config.script_type, ScriptType::Segwit);
+  assert_eq!(config.cosigners.len(), 3);
+  assert_eq!(config.cosigners[0].fingerprint.to_string(), "73c5da0a");
+  assert_eq!(config.cosigners[0].path.to_string(), "m/48'/1'/0'/2'");
+
+  let config = parse_multisig_config(&coldcard_file("2 of 3", "P2SH-P2WSH")).unwrap();
+  assert_eq!(config.script_type, ScriptType::NestedSegwit);
+}
+
+#[test]
+fn text_round_trip() {
+  let config = parse_multisig_config(&coldcard_file("2 of 3", "P2SH-P2WSH")).unwrap();
+  assert_eq!(parse_multisig_config(&config.to_string()).unwrap(), config);
+}
+
+#[test]
+fn register_and_round_trip_descriptor() {
+  let config = parse_multisig_config(&coldcard_file("2 of 3", "P2WSH")).unwrap();
+  let wallets: Vec<_> = MNEMONICS
+    .iter()
+    .map(|phrase| {
+      config
+        .register(&root(phrase, Network::Testnet), Network::Testnet)
+        .unwrap()
+    })
+    .collect();
+  for w in &wallets[1..] {
+    assert_eq!(
+      w.address(false, 0).unwrap(),
+      wallets[0].address(false, 0).unwrap()
+    );
+  }
+
+  // a descriptor export of the same wallet parses to the same quorum
+  let desc = wallets[0].descriptor(false).unwrap();
+  let from_desc = parse_multisig_config(&desc).unwrap();
+  assert_eq!(from_desc.threshold, 2);
+  assert_eq!(from_desc.script_type, ScriptType::Segwit);
+  assert_eq!(from_desc.cosigners, config.cosigners);
+
+  let json = format!(
+    "{{\"label\": \"Specter Wallet\", \"blockheight\": 0, \"descriptor\": \"{}\"}}",
+    desc
+  );
+  let from_json = parse_multisig_config(&json).unwrap();
+  assert_eq!(from_json.name, "Specter Wallet");
+  assert_eq!(from_json.cosigners, config.cosigners);
+
+  let multipath = desc.split('#').next().unwrap().replace("/0/*", "/<0;1>/*");
+  assert_eq!(
+    parse_multisig_config(&multipath).unwrap().cosigners,
+    config.cosigners
+  );
+}
+
+#[test]
+fn rejects_bad_configs() {
+  // policy doesn't match the keys
+  assert_eq!(
+    parse_multisig_config(&coldcard_file("2 of 4", "P2WSH")),
+    Err(VulcanError::BadMultisig)
+  );
+  assert_eq!(
+    parse_multisig_config(&coldcard_file("4 of 3", "P2WSH")),
+    Err(VulcanError::BadMultisig)
+  );
+  // legacy p2sh
+  assert_eq!(
+    parse_multisig_config(&coldcard_file("2 of 3", "P2SH")),
+    Err(VulcanError::BadMultisig)
+  );
+
+  let config = parse_multisig_config(&coldcard_file("2 of 3", "P2WSH")).unwrap();
+  let w = config
+    .register(&root(MNEMONICS[0], Network::Testnet), Network::Testnet)
+    .unwrap();
+  let desc = w.descriptor(false).unwrap();
+  let (body, _) = desc.split_at(desc.len() - 8);
+  assert_eq!(
+    parse_multisig_config(&format!("{}qqqqqqqq", body)),
+    Err(VulcanError::BadDescriptor)
+  );
+
+  let stranger = root(
+    "vocal tray giggle tool duck letter category pattern train magnet excite swamp",
+    Network::Testnet,
+  );
+  assert_eq!(
+    config.register(&stranger, Network::Testnet).err(),
+    Some(VulcanError::NotInQuorum)
+  );
+}
+
+#[test]
+fn register_two_accounts_of_one_seed() {
+  let account = |path: &str| DerivationPath::from_str(path).unwrap();
+  let config = MultisigConfig {
+    name: "Two Accounts".to_string(),
+    script_type: ScriptType::Segwit,
+    threshold: 2,
+    cosigners: vec![
+      cosigner(MNEMONICS[0], &account("m/48h/1h/1h/2h")),
+      cosigner(MNEMONICS[0], &account("m/48h/1h/0h/2h")),
+      cosigner(MNEMONICS[1], &account("m/48h/1h/0h/2h")),
+    ],
+  };
+  let w = config
+    .register(&root(MNEMONICS[0], Network::Testnet), Network::Testnet)
+    .unwrap();
+  assert_eq!(w.path().to_string(), "m/48'/1'/1'/2'");
+  assert_eq!(w.cosigners().len(), 3);
+
+  // our fingerprint with a key that isn't ours at that path
+  let mut config = config;
+  config.cosigners[0].xpub = config.cosigners[2].xpub;
+  config.cosigners.truncate(2);
+  let w = config
+    .register(&root(MNEMONICS[0], Network::Testnet), Network::Testnet)
+    .unwrap();
+  assert_eq!(w.path().to_string(), "m/48'/1'/0'/2'");
+}
@@ -0,0 +1,175 @@
+use common::{root, MNEMONIC};
+use libvulcan::{
+  account_descriptor,
+  bitcoin::{secp256k1::Secp256k1, util::bip32::ExtendedPubKey, Network},
+  convert_xpub_slip132,
+  descriptor::checksum,
+  get_path, get_wallet, parse_xpub, Keychain, ScriptType, VulcanError, WalletType,
+};
+
+mod common;
+
+const OTHER: &str = "forum undo fragile fade shy sign arrest garment culture tube off merit";
+
+fn account_xpub(
+  phrase: &str,
+  network: Network,
+  wallet_type: WalletType,
+  script_type: ScriptType,
+) -> ExtendedPubKey {
+  let secp = Secp256k1::new();
+  let path = get_path(&network, &wallet_type, &script_type).unwrap();
+  let xpriv = root(phrase, network).derive_priv(&secp, &path).unwrap();
+  return ExtendedPubKey::from_priv(&secp, &xpriv);
+}
+
+/// Splits off the checksum after making sure it's right.
+fn body(desc: &str) -> &str {
+  let (body, sum) = desc.split_at(desc.len() - 9);
+  assert_eq!(sum, format!("#{}", checksum(body).unwrap()));
+  return body;
+}
+
+#[test]
+fn multipath_single_sig() {
+  let expected = [
+    (
+      ScriptType::NestedSegwit,
+      "sh(wpkh([73c5da0a/49h/0h/0h]",
+      "))",
+    ),
+    (ScriptType::Segwit, "wpkh([73c5da0a/84h/0h/0h]", ")"),
+    (ScriptType::Taproot, "tr([73c5da0a/86h/0h/0h]", ")"),
+  ];
+  for (script_type, prefix, close) in expected.iter() {
+    let path = get_path(&Network::Bitcoin, &WalletType::SingleSig, script_type).unwrap();
+    let w = get_wallet(
+      &root(MNEMONIC, Network::Bitcoin),
+      &path,
+      *script_type,
+      Network::Bitcoin,
+    )
+    .unwrap();
+    let xpub = w.xpub().unwrap();
+
+    let desc = w.keychain_descriptor(Keychain::Multipath).unwrap();
+    assert_eq!(body(&desc), format!("{}{}/<0;1>/*{}", prefix, xpub, close));
+
+    // expanding the multipath gives the single chain descriptors
+    let receive = w.keychain_descriptor(Keychain::Receive).unwrap();
+    assert_eq!(receive, w.descriptor(false).unwrap());
+    assert_eq!(body(&receive), body(&desc).replace("<0;1>", "0"));
+    let change = w.keychain_descriptor(Keychain::Change).unwrap();
+    assert_eq!(change, w.descriptor(true).unwrap());
+    assert_eq!(body(&change), body(&desc).replace("<0;1>", "1"));
+  }
+}
+
+#[test]
+fn multipath_multisig() {
+  let secp = Secp256k1::new();
+  let path = get_path(
+    &Network::Bitcoin,
+    &WalletType::MultiSig,
+    &ScriptType::NestedSegwit,
+  )
+  .unwrap();
+  let keys: Vec<_> = [MNEMONIC, OTHER]
+    .iter()
+    .map(|phrase| {
+      let root = root(phrase, Network::Bitcoin);
+      let xpub = account_xpub(
+        phrase,
+        Network::Bitcoin,
+        WalletType::MultiSig,
+        ScriptType::NestedSegwit,
+      );
+      (root.fingerprint(&secp), path.clone(), xpub)
+    })
+    .collect();
+
+  let desc = account_descriptor(
+    &WalletType::MultiSig,
+    &ScriptType::NestedSegwit,
+    2,
+    &keys,
+    Keychain::Multipath,
+  )
+  .unwrap();
+  assert_eq!(
+    body(&desc),
+    format!(
+      "sh(wsh(sortedmulti(2,[73c5da0a/48h/0h/0h/1h]{}/<0;1>/*,[{}/48h/0h/0h/1h]{}/<0;1>/*)))",
+      keys[0].2, keys[1].0, keys[1].2
+    )
+  );
+
+  assert_eq!(
+    account_descriptor(
+      &WalletType::MultiSig,
+      &ScriptType::Segwit,
+      3,
+      &keys,
+      Keychain::Receive
+    ),
+    Err(VulcanError::BadDescriptor)
+  );
+}
+
+#[test]
+fn slip132() {
+  // BIP49 and BIP84 vectors
+  let xpub = account_xpub(
+    MNEMONIC,
+    Network::Bitcoin,
+    WalletType::SingleSig,
+    ScriptType::NestedSegwit,
+  );
+  assert_eq!(
+    convert_xpub_slip132(&xpub, &Network::Bitcoin, &WalletType::SingleSig, &ScriptType::NestedSegwit),
+    "ypub6Ww3ibxVfGzLrAH1PNcjyAWenMTbbAosGNB6VvmSEgytSER9azLDWCxoJwW7Ke7icmizBMXrzBx9979FfaHxHcrArf3zbeJJJUZPf663zsP"
+  );
+  let xpub = account_xpub(
+    MNEMONIC,
+    Network::Bitcoin,
+    WalletType::SingleSig,
+    ScriptType::Segwit,
+  );
+  assert_eq!(
+    convert_xpub_slip132(&xpub, &Network::Bitcoin, &WalletType::SingleSig, &ScriptType::Segwit),
+    "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs"
+  );
+
+  let prefixes = [
+    (
+      Network::Bitcoin,
+      WalletType::SingleSig,
+      ["ypub", "zpub", "xpub"],
+    ),
+    (Network::Bitcoin, WalletType::MultiSig, ["Ypub", "Zpub", ""]),
+    (
+      Network::Testnet,
+      WalletType::SingleSig,
+      ["upub", "vpub", "tpub"],
+    ),
+    (Network::Testnet, WalletType::MultiSig, ["Upub", "Vpub", ""]),
+  ];
+  let script_types = [
+    ScriptType::NestedSegwit,
+    ScriptType::Segwit,
+    ScriptType::Taproot,
+  ];
+  for (network, wallet_type, prefixes) in prefixes.iter() {
+    for (script_type, prefix) in script_types.iter().zip(prefixes.iter()) {
+      if prefix.is_empty() {
+        // no BIP48 path for taproot
+        continue;
+      }
+      let xpub = account_xpub(MNEMONIC, *network, *wallet_type, *script_type);
+      let converted = convert_xpub_slip132(&xpub, network, wallet_type, script_type);
+      assert!(converted.starts_with(prefix), "{}", converted);
+      // the key itself is unchanged
+      assert_eq!(parse_xpub(&converted).unwrap(), xpub);
+    }
+  }
+}
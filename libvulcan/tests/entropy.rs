@@ -0,0 +1,156 @@
+//! The expected dice entropy is the SHA256 of the rolls, so every vector can
+//! be checked with `printf <rolls> | sha256sum`. Coin flips are the entropy
+//! bits themselves, as in SeedSigner. Final words are checked against the
+//! BIP39 vectors.
+
+use libvulcan::{
+  bip39::{self, Language, Mnemonic},
+  bitcoin::hashes::hex::ToHex,
+  final_words, generate_mnemonic, EntropySource, VulcanError,
+};
+
+fn repeat(pattern: &str, len: usize) -> String {
+  return pattern.chars().cycle().take(len).collect();
+}
+
+#[test]
+fn dice() {
+  let rolls = repeat("123456", 50);
+  let mnemonic = generate_mnemonic(EntropySource::Dice, &rolls, None).unwrap();
+  assert_eq!(mnemonic.word_count(), 12);
+  assert_eq!(
+    mnemonic.to_entropy().to_hex(),
+    "ee72ae915a4e6ea7ccbeb8e5e5eecef2"
+  );
+
+  let rolls = repeat("123456", 99);
+  let mnemonic = generate_mnemonic(EntropySource::Dice, &rolls, None).unwrap();
+  assert_eq!(mnemonic.word_count(), 24);
+  assert_eq!(
+    mnemonic.to_entropy().to_hex(),
+    "5588d3630bd19f6375b7bd922457af34ea9c74f00807566a1cf808e445dc8c20"
+  );
+
+  assert_eq!(EntropySource::Dice.bits(50), 129);
+  assert_eq!(EntropySource::Dice.bits(99), 255);
+  assert_eq!(
+    generate_mnemonic(EntropySource::Dice, &repeat("0", 50), None),
+    Err(VulcanError::BadEntropy)
+  );
+  assert_eq!(
+    generate_mnemonic(EntropySource::Dice, &repeat("1", 51), None),
+    Err(VulcanError::BadEntropyLength(51))
+  );
+}
+
+#[test]
+fn coin_flips() {
+  let flips = repeat("01", 128);
+  let mnemonic = generate_mnemonic(EntropySource::Coin, &flips, None).unwrap();
+  assert_eq!(mnemonic.word_count(), 12);
+  assert_eq!(
+    mnemonic.to_entropy().to_hex(),
+    "55555555555555555555555555555555"
+  );
+
+  let flips = repeat("100", 256);
+  let mnemonic = generate_mnemonic(EntropySource::Coin, &flips, None).unwrap();
+  assert_eq!(mnemonic.word_count(), 24);
+  assert_eq!(
+    mnemonic.to_entropy().to_hex(),
+    "9249249249249249249249249249249249249249249249249249249249249249"
+  );
+
+  assert_eq!(EntropySource::Coin.bits(256), 256);
+  assert_eq!(
+    generate_mnemonic(EntropySource::Coin, &repeat("012", 128), None),
+    Err(VulcanError::BadEntropy)
+  );
+  assert_eq!(
+    EntropySource::Coin.inputs(18),
+    Err(VulcanError::BadWordCount(18))
+  );
+}
+
+#[test]
+fn coin_flips_match_seedsigner() {
+  // SeedSigner reads the flips as one binary number, so the BIP39 vectors'
+  // entropy written out in bits gives their mnemonics
+  let flips = repeat("01111111", 128);
+  let mnemonic = generate_mnemonic(EntropySource::Coin, &flips, None).unwrap();
+  assert_eq!(
+    mnemonic.to_string(),
+    "legal winner thank year wave sausage worth useful legal winner thank yellow"
+  );
+
+  let flips = repeat("10000000", 256);
+  let mnemonic = generate_mnemonic(EntropySource::Coin, &flips, None).unwrap();
+  assert_eq!(
+    mnemonic.to_string(),
+    "letter advice cage absurd amount doctor acoustic avoid letter advice cage \
+     absurd amount doctor acoustic avoid letter advice cage absurd amount doctor \
+     acoustic bless"
+  );
+}
+
+#[test]
+fn chip_entropy() {
+  let rolls = repeat("123456", 50);
+  let chip: Vec<u8> = (0..32).collect();
+  let mnemonic = generate_mnemonic(EntropySource::Dice, &rolls, Some(&chip)).unwrap();
+  // SHA256(SHA256(rolls) || chip)
+  assert_eq!(
+    mnemonic.to_entropy().to_hex(),
+    "63231174b902fea2e25065975d683b25"
+  );
+  assert_ne!(
+    mnemonic,
+    generate_mnemonic(EntropySource::Dice, &rolls, None).unwrap()
+  );
+}
+
+#[test]
+fn final_word() {
+  let abandon = ["abandon"; 23];
+  let zoo = ["zoo"; 23];
+  let legal = "legal winner thank year wave sausage worth useful legal winner thank";
+  let legal: Vec<&str> = legal.split(' ').collect();
+
+  // the BIP39 vectors' last words are among them
+  let vectors: [(&[&str], usize, &str); 5] = [
+    (&abandon[..11], 128, "about"),
+    (&abandon, 8, "art"),
+    (&zoo[..11], 128, "wrong"),
+    (&zoo, 8, "vote"),
+    (&legal, 128, "yellow"),
+  ];
+  for (words, count, last) in vectors.iter() {
+    let last_words = final_words(words).unwrap();
+    assert_eq!(last_words.len(), *count);
+    assert!(last_words.contains(last));
+
+    // and they're exactly the words with a valid checksum
+    let valid: Vec<&str> = Language::English
+      .word_list()
+      .iter()
+      .copied()
+      .filter(|word| {
+        let phrase = format!("{} {}", words.join(" "), word);
+        Mnemonic::parse_in_normalized(Language::English, &phrase).is_ok()
+      })
+      .collect();
+    assert_eq!(last_words, valid);
+  }
+
+  assert_eq!(final_words(&abandon[..14]).unwrap().len(), 64);
+  assert_eq!(
+    final_words(&abandon[..12]),
+    Err(VulcanError::BadWordCount(12))
+  );
+  let mut typo = abandon;
+  typo[3] = "abandom";
+  assert_eq!(
+    final_words(&typo),
+    Err(VulcanError::Mnemonic(bip39::Error::UnknownWord(3)))
+  );
+}
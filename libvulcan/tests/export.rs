@@ -0,0 +1,56 @@
+use common::{root, wallet, MNEMONIC};
+use libvulcan::{
+  bitcoin::Network, electrum_export, generic_export, specter_export, ScriptType, VulcanError,
+};
+
+mod common;
+
+#[test]
+fn specter() {
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit);
+  let json = specter_export(&w, "My \"Vulcan\"").unwrap();
+  assert!(json.starts_with("{\"label\": \"My \\\"Vulcan\\\"\", \"blockheight\": 0,"));
+  assert!(json.contains(&format!(
+    "\"descriptor\": \"{}\"",
+    w.descriptor(false).unwrap()
+  )));
+  assert!(json.contains("wpkh([73c5da0a/84h/0h/0h]xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)#"));
+}
+
+#[test]
+fn generic() {
+  let json = generic_export(&root(MNEMONIC, Network::Bitcoin), Network::Bitcoin).unwrap();
+  assert!(json.starts_with("{\"chain\": \"BTC\", \"xfp\": \"73C5DA0A\", \"account\": 0,"));
+  for section in ["bip49", "bip84", "bip86", "bip48_1", "bip48_2"].iter() {
+    assert!(json.contains(&format!("\"{}\": {{\"name\": ", section)));
+  }
+  // BIP49, BIP84 and BIP86 vectors
+  assert!(json.contains("\"deriv\": \"m/49'/0'/0'\""));
+  assert!(json.contains("\"_pub\": \"ypub6Ww3ibxVfGzLrAH1PNcjyAWenMTbbAosGNB6VvmSEgytSER9azLDWCxoJwW7Ke7icmizBMXrzBx9979FfaHxHcrArf3zbeJJJUZPf663zsP\""));
+  assert!(json.contains("\"first\": \"37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf\""));
+  assert!(json.contains("\"_pub\": \"zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs\""));
+  assert!(json.contains("\"first\": \"bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu\""));
+  assert!(
+    json.contains("\"first\": \"bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr\"")
+  );
+  assert!(json.contains("\"deriv\": \"m/48'/0'/0'/1'\""));
+  assert!(json.contains("\"deriv\": \"m/48'/0'/0'/2'\", \"xpub\": \"xpub"));
+  assert!(json.contains("\"_pub\": \"Zpub"));
+
+  let json = generic_export(&root(MNEMONIC, Network::Bitcoin), Network::Testnet).unwrap();
+  assert!(json.starts_with("{\"chain\": \"XTN\""));
+  assert!(json.contains("\"_pub\": \"vpub"));
+}
+
+#[test]
+fn electrum() {
+  let json = electrum_export(&wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit)).unwrap();
+  assert_eq!(
+    json,
+    "{\"keystore\": {\"type\": \"bip32\", \"xpub\": \"zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs\", \"derivation\": \"m/84'/0'/0'\", \"root_fingerprint\": \"73c5da0a\", \"label\": \"Vulcan\"}, \"wallet_type\": \"standard\", \"use_encryption\": false, \"seed_version\": 17}"
+  );
+  assert_eq!(
+    electrum_export(&wallet(MNEMONIC, Network::Bitcoin, ScriptType::Taproot)),
+    Err(VulcanError::UnsupportedScriptType(2))
+  );
+}
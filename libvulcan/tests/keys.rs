@@ -0,0 +1,57 @@
+//! BIP39 test vectors, which all use the passphrase "TREZOR":
+//! https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+
+use libvulcan::{
+  bip39::{Language, Mnemonic},
+  bitcoin::{secp256k1::Secp256k1, Network},
+  get_passphrase_root, get_root,
+};
+
+fn mnemonic(phrase: &str) -> Mnemonic {
+  return Mnemonic::parse_in_normalized(Language::English, phrase).unwrap();
+}
+
+#[test]
+fn passphrase_roots() {
+  let vectors = [
+    (
+      "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+      "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF",
+    ),
+    (
+      "legal winner thank year wave sausage worth useful legal winner thank yellow",
+      "xprv9s21ZrQH143K2gA81bYFHqU68xz1cX2APaSq5tt6MFSLeXnCKV1RVUJt9FWNTbrrryem4ZckN8k4Ls1H6nwdvDTvnV7zEXs2HgPezuVccsq",
+    ),
+    (
+      "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+      "xprv9s21ZrQH143K2shfP28KM3nr5Ap1SXjz8gc2rAqqMEynmjt6o1qboCDpxckqXavCwdnYds6yBHZGKHv7ef2eTXy461PXUjBFQg6PrwY4Gzq",
+    ),
+    (
+      "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+      "xprv9s21ZrQH143K2V4oox4M8Zmhi2Fjx5XK4Lf7GKRvPSgydU3mjZuKGCTg7UPiBUD7ydVPvSLtg9hjp7MQTYsW67rZHAXeccqYqrsx8LcXnyd",
+    ),
+  ];
+  for (phrase, xprv) in vectors.iter() {
+    let root = get_passphrase_root(&mnemonic(phrase), "TREZOR", Network::Bitcoin).unwrap();
+    assert_eq!(root.to_string(), *xprv);
+  }
+}
+
+#[test]
+fn fingerprints() {
+  let secp = Secp256k1::new();
+  let m = mnemonic(
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+  );
+
+  // no passphrase is the empty passphrase
+  let root = get_root(&m, Network::Bitcoin).unwrap();
+  assert_eq!(get_passphrase_root(&m, "", Network::Bitcoin).unwrap(), root);
+  assert_eq!(root.fingerprint(&secp).to_string(), "73c5da0a");
+
+  // every passphrase, even a typo, opens a different wallet
+  let trezor = get_passphrase_root(&m, "TREZOR", Network::Bitcoin).unwrap();
+  let typo = get_passphrase_root(&m, "TREZOP", Network::Bitcoin).unwrap();
+  assert_ne!(trezor.fingerprint(&secp), root.fingerprint(&secp));
+  assert_ne!(trezor.fingerprint(&secp), typo.fingerprint(&secp));
+}
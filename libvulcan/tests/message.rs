@@ -0,0 +1,207 @@
+//! Test vectors from BIP322:
+//! https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+
+use common::{root, MNEMONIC};
+use libvulcan::{
+  bitcoin::{
+    hashes::hex::ToHex,
+    secp256k1::Secp256k1,
+    util::{
+      bip32::{ChainCode, ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint},
+      misc::MessageSignature,
+    },
+    Address, Network, PrivateKey,
+  },
+  message::bip322_message_hash,
+  parse_message_request, sign_message, verify_message, MessageAddressType, MessageRequest,
+  SignatureFormat, VulcanError,
+};
+use std::str::FromStr;
+
+mod common;
+
+const WIF: &str = "L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k";
+const P2WPKH: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+const P2TR: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+/// A root whose own key is the vectors' private key, so `m` signs with it.
+fn key_root() -> ExtendedPrivKey {
+  let key = PrivateKey::from_wif(WIF).unwrap();
+  return ExtendedPrivKey {
+    network: Network::Bitcoin,
+    depth: 0,
+    parent_fingerprint: Fingerprint::default(),
+    child_number: ChildNumber::from_normal_idx(0).unwrap(),
+    private_key: key.inner,
+    chain_code: ChainCode::from(&[0u8; 32][..]),
+  };
+}
+
+#[test]
+fn message_hash() {
+  assert_eq!(
+    bip322_message_hash("").to_hex(),
+    "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+  );
+  assert_eq!(
+    bip322_message_hash("Hello World").to_hex(),
+    "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+  );
+}
+
+#[test]
+fn bip322_p2wpkh() {
+  let address = Address::from_str(P2WPKH).unwrap();
+  let empty = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+  let hello = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+  assert_eq!(verify_message(&address, "", empty), Ok(true));
+  assert_eq!(verify_message(&address, "Hello World", hello), Ok(true));
+  assert_eq!(verify_message(&address, "Hello World", empty), Ok(false));
+  assert_eq!(verify_message(&address, "", hello), Ok(false));
+
+  let mut request = MessageRequest::new(
+    "Hello World",
+    DerivationPath::master(),
+    MessageAddressType::P2wpkh,
+  )
+  .unwrap();
+  request.format = SignatureFormat::Bip322;
+  let signed = sign_message(&key_root(), &request).unwrap();
+  assert_eq!(signed.address, address);
+  assert_eq!(signed.signature, hello);
+}
+
+#[test]
+fn bip322_p2tr() {
+  let address = Address::from_str(P2TR).unwrap();
+  let hello =
+    "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+  assert_eq!(verify_message(&address, "Hello World", hello), Ok(true));
+  assert_eq!(verify_message(&address, "Hello world", hello), Ok(false));
+
+  // the vector uses random aux data, so only check our own signature verifies
+  let request = MessageRequest::new(
+    "Hello World",
+    DerivationPath::master(),
+    MessageAddressType::P2tr,
+  )
+  .unwrap();
+  assert_eq!(request.format, SignatureFormat::Bip322);
+  let signed = sign_message(&key_root(), &request).unwrap();
+  assert_eq!(signed.address, address);
+  assert_eq!(
+    verify_message(&address, "Hello World", &signed.signature),
+    Ok(true)
+  );
+}
+
+#[test]
+fn bip137() {
+  let root = root(MNEMONIC, Network::Bitcoin);
+  let secp = Secp256k1::verification_only();
+  let paths = [
+    ("m/44'/0'/0'/0/0", MessageAddressType::P2pkh),
+    ("m/49'/0'/0'/0/0", MessageAddressType::P2shP2wpkh),
+    ("m/84'/0'/0'/0/0", MessageAddressType::P2wpkh),
+  ];
+  for (path, address_type) in paths.iter() {
+    let text = format!("Hello World\n{}\n", path);
+    let request = parse_message_request(&text, Network::Bitcoin).unwrap();
+    assert_eq!(request.address_type, *address_type);
+    assert_eq!(request.format, SignatureFormat::Bip137);
+
+    let signed = sign_message(&root, &request).unwrap();
+    assert_eq!(
+      verify_message(&signed.address, "Hello World", &signed.signature),
+      Ok(true)
+    );
+    assert_eq!(
+      verify_message(&signed.address, "Hello world", &signed.signature),
+      Ok(false)
+    );
+
+    // bitcoin's own verifier only knows P2PKH
+    if *address_type == MessageAddressType::P2pkh {
+      assert_eq!(
+        signed.address.to_string(),
+        "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA"
+      );
+      let sig = MessageSignature::from_base64(&signed.signature).unwrap();
+      let hash = libvulcan::bitcoin::util::misc::signed_msg_hash("Hello World");
+      assert!(sig
+        .is_signed_by_address(&secp, &signed.address, hash)
+        .unwrap());
+    }
+  }
+}
+
+#[test]
+fn coldcard_file() {
+  let request = parse_message_request("Hello World", Network::Bitcoin).unwrap();
+  assert_eq!(request.path.to_string(), "m/44'/0'/0'/0/0");
+  assert_eq!(request.address_type, MessageAddressType::P2pkh);
+
+  let request = parse_message_request("Hello World\nm/44'/1'/0'/0/3", Network::Testnet).unwrap();
+  assert_eq!(request.address_type, MessageAddressType::P2pkh);
+  let request =
+    parse_message_request("Hello World\nm/84'/1'/0'/0/3\np2wpkh", Network::Testnet).unwrap();
+  assert_eq!(request.address_type, MessageAddressType::P2wpkh);
+  assert_eq!(request.format, SignatureFormat::Bip137);
+  let request = parse_message_request("Hello World\nm/86'/1'/0'/0/3\n", Network::Testnet).unwrap();
+  assert_eq!(request.address_type, MessageAddressType::P2tr);
+  assert_eq!(request.format, SignatureFormat::Bip322);
+
+  // segwit v0 can ask for BIP322 instead
+  let request = parse_message_request(
+    "Hello World\nm/84'/1'/0'/0/3\np2wpkh\nBIP322",
+    Network::Testnet,
+  )
+  .unwrap();
+  assert_eq!(request.format, SignatureFormat::Bip322);
+  for text in [
+    "Hello World\nm/86'/1'/0'/0/3\np2tr\nbip137",
+    "Hello World\nm/44'/1'/0'/0/3\np2pkh\nbip322",
+    "Hello World\nm/84'/1'/0'/0/3\np2wpkh\nbip999",
+  ]
+  .iter()
+  {
+    assert_eq!(
+      parse_message_request(text, Network::Testnet),
+      Err(VulcanError::UnsupportedMessageFormat)
+    );
+  }
+
+  let signed = sign_message(
+    &root(MNEMONIC, Network::Bitcoin),
+    &parse_message_request("Hello World", Network::Bitcoin).unwrap(),
+  )
+  .unwrap();
+  let armor = signed.to_armor();
+  assert!(armor.starts_with("-----BEGIN BITCOIN SIGNED MESSAGE-----\nHello World\n"));
+  assert!(armor.ends_with(&format!(
+    "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA\n{}\n-----END BITCOIN SIGNATURE-----\n",
+    signed.signature
+  )));
+
+  for text in [
+    "",
+    "Héllo World",
+    "Hello\tWorld",
+    "Hello World\nm/44'/0'/0'/0/0\np2pkh\nbip137\nextra",
+  ]
+  .iter()
+  {
+    assert_eq!(
+      parse_message_request(text, Network::Bitcoin),
+      Err(VulcanError::BadMessage)
+    );
+  }
+  assert_eq!(
+    parse_message_request(&"a".repeat(241), Network::Bitcoin),
+    Err(VulcanError::BadMessage)
+  );
+  assert_eq!(
+    parse_message_request("Hello World\nm/44'/0'/0'/0/0\np2wsh", Network::Bitcoin),
+    Err(VulcanError::UnsupportedMessageFormat)
+  );
+  assert!(parse_message_request("Hello World\nm/x", Network::Bitcoin).is_err());
+}
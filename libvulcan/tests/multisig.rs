@@ -0,0 +1,292 @@
+use common::{cosigner, root, MNEMONICS};
+use libvulcan::{
+  bitcoin::{
+    secp256k1::{Message, Secp256k1},
+    util::{
+      bip32::{DerivationPath, ExtendedPubKey},
+      psbt::PartiallySignedTransaction,
+      sighash::SighashCache,
+    },
+    EcdsaSighashType, Network, OutPoint, PublicKey, Script, Transaction, TxIn, TxOut, Witness,
+  },
+  get_multisig_wallet, get_path, Account, Cosigner, MultisigWallet, ScriptType, VulcanError,
+  WalletType,
+};
+use std::str::FromStr;
+
+mod common;
+
+/// The 2-of-3 quorum as seen from the signer holding `MNEMONICS[ours]`.
+fn quorum(ours: usize, script_type: ScriptType) -> MultisigWallet {
+  let path = get_path(&Network::Testnet, &WalletType::MultiSig, &script_type).unwrap();
+  let cosigners: Vec<Cosigner> = MNEMONICS
+    .iter()
+    .map(|phrase| cosigner(phrase, &path))
+    .collect();
+  return get_multisig_wallet(
+    &root(MNEMONICS[ours], Network::Testnet),
+    &path,
+    script_type,
+    Network::Testnet,
+    2,
+    &cosigners,
+  )
+  .unwrap();
+}
+
+#[test]
+fn parse_cosigner() {
+  let c = cosigner(
+    MNEMONICS[0],
+    &DerivationPath::from_str("m/48h/1h/0h/2h").unwrap(),
+  );
+  let parsed = Cosigner::from_str(&format!("[73c5da0a/48h/1h/0h/2h]{}", c.xpub)).unwrap();
+  assert_eq!(parsed, c);
+  let parsed = Cosigner::from_str(&format!("[73c5da0a/48'/1'/0'/2']{}", c.xpub)).unwrap();
+  assert_eq!(parsed, c);
+
+  assert!(Cosigner::from_str(&format!("73c5da0a/48h/1h/0h/2h]{}", c.xpub)).is_err());
+  assert!(Cosigner::from_str(&format!("[73c5da/48h/1h/0h/2h]{}", c.xpub)).is_err());
+  assert!(Cosigner::from_str("[73c5da0a/48h/1h/0h/2h]tpubnope").is_err());
+}
+
+#[test]
+fn every_cosigner_derives_the_same_wallet() {
+  for script_type in [ScriptType::Segwit, ScriptType::NestedSegwit] {
+    let wallets: Vec<MultisigWallet> = (0..3).map(|i| quorum(i, script_type)).collect();
+    for w in &wallets[1..] {
+      assert_eq!(
+        w.address(false, 0).unwrap(),
+        wallets[0].address(false, 0).unwrap()
+      );
+      assert_eq!(
+        w.address(true, 7).unwrap(),
+        wallets[0].address(true, 7).unwrap()
+      );
+    }
+  }
+
+  let desc = quorum(0, ScriptType::Segwit).descriptor(false).unwrap();
+  assert!(desc.starts_with("wsh(sortedmulti(2,["));
+  assert!(desc.contains("[73c5da0a/48h/1h/0h/2h]tpub"));
+  assert!(desc.contains("/0/*)"));
+  let desc = quorum(0, ScriptType::NestedSegwit)
+    .descriptor(true)
+    .unwrap();
+  assert!(desc.starts_with("sh(wsh(sortedmulti(2,"));
+}
+
+#[test]
+fn our_key_is_found_by_xpub() {
+  let path = get_path(
+    &Network::Testnet,
+    &WalletType::MultiSig,
+    &ScriptType::Segwit,
+  )
+  .unwrap();
+  let mut ours = cosigner(MNEMONICS[0], &path);
+  // coordinators don't always export the path
+  ours.path = DerivationPath::master();
+  let cosigners = [
+    ours,
+    cosigner(MNEMONICS[1], &path),
+    cosigner(MNEMONICS[2], &path),
+  ];
+  let w = get_multisig_wallet(
+    &root(MNEMONICS[0], Network::Testnet),
+    &path,
+    ScriptType::Segwit,
+    Network::Testnet,
+    2,
+    &cosigners,
+  )
+  .unwrap();
+  assert_eq!(w.cosigners().len(), 3);
+  // the descriptor carries the path we derived, not the one we were given
+  assert_eq!(w.cosigners()[0].path, path);
+  let desc = w.descriptor(false).unwrap();
+  assert!(desc.contains("[73c5da0a/48h/1h/0h/2h]tpub"));
+  assert!(!desc.contains("[73c5da0a]"));
+}
+
+#[test]
+fn witness_script_is_sorted() {
+  let w = quorum(0, ScriptType::Segwit);
+  let script = w.witness_script(false, 0).unwrap();
+  let keys: Vec<Vec<u8>> = script
+    .instructions()
+    .filter_map(|i| match i.unwrap() {
+      libvulcan::bitcoin::blockdata::script::Instruction::PushBytes(bytes) => Some(bytes.to_vec()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(keys.len(), 3);
+  let mut sorted = keys.clone();
+  sorted.sort();
+  assert_eq!(keys, sorted);
+}
+
+#[test]
+fn bad_quorums() {
+  let path = get_path(
+    &Network::Testnet,
+    &WalletType::MultiSig,
+    &ScriptType::Segwit,
+  )
+  .unwrap();
+  let others = [cosigner(MNEMONICS[1], &path), cosigner(MNEMONICS[2], &path)];
+  let all = [
+    cosigner(MNEMONICS[0], &path),
+    others[0].clone(),
+    others[1].clone(),
+  ];
+  let root = root(MNEMONICS[0], Network::Testnet);
+
+  for threshold in [0, 4] {
+    assert_eq!(
+      get_multisig_wallet(
+        &root,
+        &path,
+        ScriptType::Segwit,
+        Network::Testnet,
+        threshold,
+        &all
+      )
+      .err(),
+      Some(VulcanError::BadMultisig)
+    );
+  }
+
+  let duplicate = [others[0].clone(), others[0].clone()];
+  assert_eq!(
+    get_multisig_wallet(
+      &root,
+      &path,
+      ScriptType::Segwit,
+      Network::Testnet,
+      2,
+      &duplicate
+    )
+    .err(),
+    Some(VulcanError::BadMultisig)
+  );
+
+  assert_eq!(
+    get_multisig_wallet(
+      &root,
+      &path,
+      ScriptType::Segwit,
+      Network::Bitcoin,
+      2,
+      &others
+    )
+    .err(),
+    Some(VulcanError::WrongNetwork)
+  );
+
+  // our key isn't added to a quorum that doesn't have it
+  assert_eq!(
+    get_multisig_wallet(
+      &root,
+      &path,
+      ScriptType::Segwit,
+      Network::Testnet,
+      2,
+      &others
+    )
+    .err(),
+    Some(VulcanError::NotInQuorum)
+  );
+
+  assert_eq!(
+    get_multisig_wallet(
+      &root,
+      &path,
+      ScriptType::Taproot,
+      Network::Testnet,
+      2,
+      &others
+    )
+    .err(),
+    Some(VulcanError::UnsupportedScriptType(2))
+  );
+}
+
+#[test]
+fn sign_and_verify_change() {
+  let secp = Secp256k1::new();
+  let wallets: Vec<MultisigWallet> = (0..3).map(|i| quorum(i, ScriptType::Segwit)).collect();
+  let receive = wallets[0].address(false, 3).unwrap();
+  let change = wallets[0].address(true, 1).unwrap();
+  let witness_script = wallets[0].witness_script(false, 3).unwrap();
+
+  let tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::default(),
+      script_sig: Script::new(),
+      sequence: 0xffffffff,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 9000,
+      script_pubkey: change.script_pubkey(),
+    }],
+  };
+  let utxo = TxOut {
+    value: 10000,
+    script_pubkey: receive.script_pubkey(),
+  };
+
+  let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx.clone()).unwrap();
+  psbt.inputs[0].witness_utxo = Some(utxo.clone());
+  psbt.inputs[0].witness_script = Some(witness_script.clone());
+  for (i, w) in wallets.iter().enumerate() {
+    let c = &w
+      .cosigners()
+      .iter()
+      .find(|c| c.fingerprint == w.fingerprint())
+      .unwrap();
+    let path = c.path.extend(DerivationPath::from_str("m/0/3").unwrap());
+    let key = root(MNEMONICS[i], Network::Testnet)
+      .derive_priv(&secp, &path)
+      .unwrap();
+    let pubkey = ExtendedPubKey::from_priv(&secp, &key).public_key;
+    psbt.inputs[0]
+      .bip32_derivation
+      .insert(pubkey, (w.fingerprint(), path.clone()));
+
+    let path = c.path.extend(DerivationPath::from_str("m/1/1").unwrap());
+    let key = root(MNEMONICS[i], Network::Testnet)
+      .derive_priv(&secp, &path)
+      .unwrap();
+    let pubkey = ExtendedPubKey::from_priv(&secp, &key).public_key;
+    psbt.outputs[0]
+      .bip32_derivation
+      .insert(pubkey, (w.fingerprint(), path));
+  }
+
+  for w in &wallets {
+    assert!(w.owns_output(&tx.output[0], &psbt.outputs[0]).unwrap());
+  }
+  // a different script with the same derivations isn't ours
+  let other = TxOut {
+    value: 9000,
+    script_pubkey: wallets[0].address(true, 2).unwrap().script_pubkey(),
+  };
+  assert!(!wallets[0].owns_output(&other, &psbt.outputs[0]).unwrap());
+
+  assert!(wallets[0].sign(&mut psbt).unwrap());
+  assert!(wallets[2].sign(&mut psbt).unwrap());
+  assert_eq!(psbt.inputs[0].partial_sigs.len(), 2);
+
+  let sighash = SighashCache::new(&tx)
+    .segwit_signature_hash(0, &witness_script, utxo.value, EcdsaSighashType::All)
+    .unwrap();
+  let msg = Message::from_slice(&sighash[..]).unwrap();
+  for (pubkey, sig) in &psbt.inputs[0].partial_sigs {
+    let pubkey: &PublicKey = pubkey;
+    assert!(secp.verify_ecdsa(&msg, &sig.sig, &pubkey.inner).is_ok());
+  }
+}
@@ -0,0 +1,230 @@
+use common::{claim, key_origin, root, testnet_wallet, wallet, MNEMONIC};
+use libvulcan::{
+  bitcoin::{
+    secp256k1::Secp256k1,
+    util::{
+      bip32::{DerivationPath, ExtendedPubKey},
+      psbt::{PartiallySignedTransaction, PsbtSighashType},
+    },
+    EcdsaSighashType, Network, OutPoint, Script, Transaction, TxIn, TxOut, Witness,
+  },
+  check_psbt, Policy, PolicyIssue, ScriptType, Wallet,
+};
+
+mod common;
+
+const OTHER: &str = "vocal tray giggle tool duck letter category pattern train magnet excite swamp";
+
+/// Spends 100_000 sats from our first receive address to `outputs`, with
+/// the full previous transaction attached.
+fn psbt(w: &Wallet, outputs: Vec<(Script, u64)>) -> PartiallySignedTransaction {
+  let prev = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![],
+    output: vec![TxOut {
+      value: 100_000,
+      script_pubkey: w.address(false, 0).unwrap().script_pubkey(),
+    }],
+  };
+  let tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::new(prev.txid(), 0),
+      script_sig: Script::new(),
+      sequence: 0xfffffffd,
+      witness: Witness::default(),
+    }],
+    output: outputs
+      .into_iter()
+      .map(|(script_pubkey, value)| TxOut {
+        value,
+        script_pubkey,
+      })
+      .collect(),
+  };
+  let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+
+  let (pubkey, origin) = key_origin(MNEMONIC, "m/84'/1'/0'/0/0");
+  psbt.inputs[0].witness_utxo = Some(prev.output[0].clone());
+  psbt.inputs[0].non_witness_utxo = Some(prev);
+  psbt.inputs[0].bip32_derivation.insert(pubkey, origin);
+  return psbt;
+}
+
+/// Adds our derivation at `m/84'/1'/0'/1/0` to output `i`.
+fn claim_change(psbt: &mut PartiallySignedTransaction, i: usize) {
+  claim(psbt, i, MNEMONIC, "m/84'/1'/0'/1/0");
+}
+
+#[test]
+fn clean_spend_passes() {
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+  let mut psbt = psbt(
+    &w,
+    vec![
+      (other.address(false, 0).unwrap().script_pubkey(), 60_000),
+      (w.address(true, 0).unwrap().script_pubkey(), 39_000),
+    ],
+  );
+  claim_change(&mut psbt, 1);
+  let report = check_psbt(&psbt, &w, &Policy::default());
+  assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+  assert!(!report.is_blocked(), "{:?}", report.blocks);
+}
+
+#[test]
+fn warnings() {
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+  let mut psbt = psbt(
+    &w,
+    vec![
+      (other.address(false, 0).unwrap().script_pubkey(), 10_000),
+      (Script::new_op_return(b"hello"), 0),
+      (w.address(true, 0).unwrap().script_pubkey(), 88_000),
+    ],
+  );
+  claim_change(&mut psbt, 2);
+  psbt.unsigned_tx.lock_time = 1_700_000_000;
+  psbt.unsigned_tx.input[0].sequence = 144;
+  psbt.inputs[0].non_witness_utxo = None;
+
+  let policy = Policy {
+    require_non_witness_utxo: false,
+    ..Policy::default()
+  };
+  let report = check_psbt(&psbt, &w, &policy);
+  assert!(!report.is_blocked(), "{:?}", report.blocks);
+  assert_eq!(
+    report.warnings,
+    vec![
+      PolicyIssue::MissingNonWitnessUtxo(0),
+      PolicyIssue::UnusualSequence(0, 144),
+      PolicyIssue::TimestampLockTime(1_700_000_000),
+      PolicyIssue::DataOutput(1),
+      PolicyIssue::HighFee(20),
+    ]
+  );
+
+  // disabled, zero length, or a version 1 transaction: no relative timelock
+  for (version, sequence) in [(2, 0x8000_0090), (2, 0x0040_0000), (1, 144)] {
+    psbt.unsigned_tx.version = version;
+    psbt.unsigned_tx.input[0].sequence = sequence;
+    let report = check_psbt(&psbt, &w, &policy);
+    assert!(!report
+      .warnings
+      .iter()
+      .any(|issue| matches!(issue, PolicyIssue::UnusualSequence(..))));
+  }
+}
+
+#[test]
+fn blocks() {
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+
+  // the whole input goes to fees
+  let psbt_fee = psbt(
+    &w,
+    vec![(other.address(false, 0).unwrap().script_pubkey(), 1_000)],
+  );
+  let policy = Policy {
+    max_fee_rate: 500.0,
+    ..Policy::default()
+  };
+  let report = check_psbt(&psbt_fee, &w, &policy);
+  assert!(matches!(
+    report.blocks[..],
+    [PolicyIssue::AbsurdFeeRate(rate), PolicyIssue::AbsurdFee(9900)] if rate > 500
+  ));
+
+  let mut psbt = psbt(
+    &w,
+    vec![
+      (other.address(false, 0).unwrap().script_pubkey(), 60_000),
+      (Script::from(vec![0x51]), 39_000),
+    ],
+  );
+  psbt.inputs[0].sighash_type = Some(PsbtSighashType::from(
+    EcdsaSighashType::SinglePlusAnyoneCanPay,
+  ));
+  let report = check_psbt(&psbt, &w, &Policy::default());
+  assert_eq!(
+    report.blocks,
+    vec![
+      PolicyIssue::NonDefaultSighash(0, 0x83),
+      PolicyIssue::NonStandardOutput(1),
+    ]
+  );
+
+  // the amount of a segwit v0 input can't be checked without its transaction
+  let mut psbt = psbt_fee.clone();
+  psbt.inputs[0].non_witness_utxo = None;
+  let report = check_psbt(&psbt, &w, &Policy::default());
+  assert!(report
+    .blocks
+    .contains(&PolicyIssue::MissingNonWitnessUtxo(0)));
+
+  // nothing for us to sign
+  let report = check_psbt(&psbt_fee, &other, &Policy::default());
+  assert!(report.blocks.contains(&PolicyIssue::NothingToSign));
+  assert!(report.warnings.contains(&PolicyIssue::ForeignInput(0)));
+}
+
+#[test]
+fn huge_fee_at_a_low_rate() {
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+  // 1_000 sats fee for sending 1_000 sats, about 7 sat/vB
+  let mut psbt = psbt(
+    &w,
+    vec![
+      (other.address(false, 0).unwrap().script_pubkey(), 1_000),
+      (w.address(true, 0).unwrap().script_pubkey(), 98_000),
+    ],
+  );
+  claim_change(&mut psbt, 1);
+  let report = check_psbt(&psbt, &w, &Policy::default());
+  assert_eq!(report.blocks, vec![PolicyIssue::AbsurdFee(100)]);
+
+  // the same fee for a reasonable amount, over a sat limit
+  let mut psbt_sats = psbt.clone();
+  psbt_sats.unsigned_tx.output[0].value = 50_000;
+  psbt_sats.unsigned_tx.output[1].value = 49_000;
+  let policy = Policy {
+    max_fee_sats: 500,
+    ..Policy::default()
+  };
+  let report = check_psbt(&psbt_sats, &w, &policy);
+  assert_eq!(report.blocks, vec![PolicyIssue::AbsurdFeeAmount(1_000)]);
+  assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+}
+
+#[test]
+fn wrong_network() {
+  let secp = Secp256k1::new();
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+  let outputs = vec![(other.address(false, 0).unwrap().script_pubkey(), 99_000)];
+
+  // our testnet UTXO, signed on mainnet
+  let root = root(MNEMONIC, Network::Bitcoin);
+  let mainnet = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit);
+  let report = check_psbt(&psbt(&w, outputs.clone()), &mainnet, &Policy::default());
+  assert_eq!(report.blocks, vec![PolicyIssue::WrongNetworkInput(0)]);
+
+  // a mainnet global xpub in a testnet PSBT
+  let mut psbt = psbt(&w, outputs);
+  let xpub = ExtendedPubKey::from_priv(&secp, &root);
+  psbt
+    .xpub
+    .insert(xpub, (root.fingerprint(&secp), DerivationPath::master()));
+  let report = check_psbt(&psbt, &w, &Policy::default());
+  assert_eq!(
+    report.blocks,
+    vec![PolicyIssue::WrongNetworkXpub(xpub.fingerprint())]
+  );
+}
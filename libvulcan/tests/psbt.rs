@@ -0,0 +1,174 @@
+use common::{claim, testnet_wallet, MNEMONIC};
+use libvulcan::{
+  bitcoin::{
+    base64, consensus, util::psbt::PartiallySignedTransaction, OutPoint, Script, Transaction, TxIn,
+    TxOut, Witness,
+  },
+  calc_fee, calc_input, calc_spend_change, psbt_base64, psbt_bytes, TransactionSummary,
+  VulcanError,
+};
+
+mod common;
+
+const OTHER: &str = "vocal tray giggle tool duck letter category pattern train magnet excite swamp";
+
+fn psbt(outputs: Vec<Script>) -> PartiallySignedTransaction {
+  let tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::default(),
+      script_sig: Script::new(),
+      sequence: 0xffffffff,
+      witness: Witness::default(),
+    }],
+    output: outputs
+      .into_iter()
+      .enumerate()
+      .map(|(i, script_pubkey)| TxOut {
+        value: 1000 * (i as u64 + 1),
+        script_pubkey,
+      })
+      .collect(),
+  };
+  return PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+}
+
+#[test]
+fn change_must_rederive() {
+  let w = testnet_wallet(MNEMONIC);
+  let attacker = testnet_wallet(OTHER);
+
+  let mut psbt = psbt(vec![
+    // plain spend
+    attacker.address(false, 0).unwrap().script_pubkey(),
+    // real change
+    w.address(true, 4).unwrap().script_pubkey(),
+    // attacker's script labelled with our derivation
+    attacker.address(true, 4).unwrap().script_pubkey(),
+    // our receive address, claimed with the wrong index
+    w.address(false, 1).unwrap().script_pubkey(),
+    // someone else's derivation doesn't make it ours
+    attacker.address(true, 0).unwrap().script_pubkey(),
+  ]);
+  claim(&mut psbt, 1, MNEMONIC, "m/84'/1'/0'/1/4");
+  claim(&mut psbt, 2, MNEMONIC, "m/84'/1'/0'/1/4");
+  claim(&mut psbt, 3, MNEMONIC, "m/84'/1'/0'/0/2");
+  claim(&mut psbt, 4, OTHER, "m/84'/1'/0'/1/0");
+
+  let outputs = calc_spend_change(&psbt, &w).unwrap();
+  let values = |list: &Vec<(_, u64)>| list.iter().map(|(_, v)| *v).collect::<Vec<u64>>();
+  assert_eq!(values(&outputs.spend), vec![1000, 5000]);
+  assert_eq!(values(&outputs.change), vec![2000]);
+  assert_eq!(values(&outputs.suspicious), vec![3000, 4000]);
+}
+
+#[test]
+fn change_outside_the_account_is_suspicious() {
+  let w = testnet_wallet(MNEMONIC);
+  let mut psbt = psbt(vec![w.address(true, 0).unwrap().script_pubkey()]);
+  // right key, but a path outside of the account
+  claim(&mut psbt, 0, MNEMONIC, "m/84'/1'/1'/1/0");
+
+  let outputs = calc_spend_change(&psbt, &w).unwrap();
+  assert!(outputs.change.is_empty());
+  assert_eq!(outputs.suspicious.len(), 1);
+}
+
+#[test]
+fn op_return_is_a_data_output() {
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+  let data = Script::new_op_return(b"vulcan");
+  let tx = psbt(vec![
+    other.address(false, 0).unwrap().script_pubkey(),
+    data.clone(),
+  ]);
+
+  let outputs = calc_spend_change(&tx, &w).unwrap();
+  assert_eq!(outputs.spend.len(), 1);
+  assert_eq!(outputs.data, vec![(data, 2000)]);
+
+  // other scripts without an address are kept apart for the policy to block
+  let script = Script::from(vec![0x51]);
+  let tx = psbt(vec![script.clone()]);
+  let outputs = calc_spend_change(&tx, &w).unwrap();
+  assert!(outputs.spend.is_empty());
+  assert_eq!(outputs.nonstandard, vec![(script, 1000)]);
+}
+
+#[test]
+fn non_witness_utxo_counts_only_the_spent_output() {
+  let w = testnet_wallet(MNEMONIC);
+  let other = testnet_wallet(OTHER);
+  let prev_tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![],
+    output: vec![
+      TxOut {
+        value: 50_000,
+        script_pubkey: other.address(false, 0).unwrap().script_pubkey(),
+      },
+      TxOut {
+        value: 20_000,
+        script_pubkey: w.address(false, 0).unwrap().script_pubkey(),
+      },
+    ],
+  };
+
+  let mut psbt = psbt(vec![
+    other.address(false, 1).unwrap().script_pubkey(),
+    w.address(true, 0).unwrap().script_pubkey(),
+  ]);
+  psbt.unsigned_tx.input[0].previous_output = OutPoint::new(prev_tx.txid(), 1);
+  psbt.inputs[0].non_witness_utxo = Some(prev_tx.clone());
+  claim(&mut psbt, 1, MNEMONIC, "m/84'/1'/0'/1/0");
+
+  assert_eq!(calc_input(&psbt).unwrap(), 20_000);
+  assert_eq!(calc_fee(&psbt).unwrap(), 17_000);
+
+  let summary = TransactionSummary::new(&psbt, &w).unwrap();
+  assert_eq!(summary.input, 20_000);
+  assert_eq!(summary.output, 3_000);
+  assert_eq!(summary.fee, 17_000);
+  // 1 p2wpkh input, 2 p2wpkh outputs
+  assert_eq!(summary.vsize, 141);
+  assert!((summary.fee_rate - 17_000.0 / 141.0).abs() < 1e-9);
+  assert_eq!(summary.outputs.change.len(), 1);
+
+  // a witness_utxo that disagrees with the full transaction
+  psbt.inputs[0].witness_utxo = Some(TxOut {
+    value: 1,
+    script_pubkey: w.address(false, 0).unwrap().script_pubkey(),
+  });
+  assert_eq!(calc_input(&psbt), Err(VulcanError::UtxoMismatch(0)));
+  psbt.inputs[0].witness_utxo = None;
+
+  // the transaction doesn't hash to the outpoint's txid
+  psbt.unsigned_tx.input[0].previous_output = OutPoint::new(Default::default(), 1);
+  assert_eq!(calc_input(&psbt), Err(VulcanError::UtxoMismatch(0)));
+  assert_eq!(
+    TransactionSummary::new(&psbt, &w),
+    Err(VulcanError::UtxoMismatch(0))
+  );
+
+  psbt.inputs[0].non_witness_utxo = None;
+  assert_eq!(calc_fee(&psbt), Err(VulcanError::MissingUtxo(0)));
+}
+
+#[test]
+fn binary_and_base64_files() {
+  let w = testnet_wallet(MNEMONIC);
+  let bytes = consensus::serialize(&psbt(vec![w.address(false, 1).unwrap().script_pubkey()]));
+  let encoded = psbt_base64(&bytes);
+  assert_eq!(encoded, base64::encode(&bytes));
+
+  assert_eq!(psbt_bytes(&bytes).unwrap(), bytes);
+  assert_eq!(psbt_bytes(encoded.as_bytes()).unwrap(), bytes);
+  assert_eq!(
+    psbt_bytes(format!("{}\n", encoded).as_bytes()).unwrap(),
+    bytes
+  );
+  assert_eq!(psbt_bytes(b"not a psbt"), Err(VulcanError::MalformedPsbt));
+}
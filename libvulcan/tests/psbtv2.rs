@@ -0,0 +1,289 @@
+use common::{key_origin, testnet_wallet, MNEMONIC};
+use libvulcan::{
+  bitcoin::{
+    base64, consensus, hashes::hex::FromHex, util::psbt::PartiallySignedTransaction,
+    EcdsaSighashType, OutPoint, PublicKey, Script, Transaction, TxIn, TxOut, Witness,
+  },
+  determine_locktime, psbt_version, PsbtFile, PsbtV2, RequiredLockTime, VulcanError,
+};
+
+mod common;
+
+/// BIP370 valid vector: 1 input, 2 output PSBTv2, required fields only
+const REQUIRED_ONLY: &str = "cHNidP8BAgQCAAAAAQQBAQEFAQIB+wQCAAAAAAEOIAsK2SFBnByHGXNdctxzn56p4GONH+TB7vD5lECEgV/IAQ8EAAAAAAABAwgIqBAAAAAAAAEEFgAUxDD2TEdW2jENvRoIVXLvKZkmJywAAQMIi73rCwAAAAABBBYAFE3Rk6yWSlasG54cyoRU/i9HT4UTAA==";
+
+const GLOBAL: [&str; 4] = ["02:02000000", "04:01", "05:02", "fb:02000000"];
+const INPUT: [&str; 2] = [
+  "0e:0b0ad921419c1c8719735d72dc739f9ea9e0638d1fe4c1eef0f9944084815fc8",
+  "0f:00000000",
+];
+const OUTPUT_0: [&str; 2] = [
+  "03:08a8100000000000",
+  "04:0014c430f64c4756da310dbd1a085572ef299926272c",
+];
+const OUTPUT_1: [&str; 2] = [
+  "03:8bbdeb0b00000000",
+  "04:00144dd193ac964a56ac1b9e1cca8454fe2f474f8513",
+];
+
+/// Serializes maps of `key:value` hex pairs.
+fn encode(global: &[&str], inputs: &[&[&str]], outputs: &[&[&str]]) -> Vec<u8> {
+  let mut out = b"psbt\xff".to_vec();
+  let maps = std::iter::once(global)
+    .chain(inputs.iter().copied())
+    .chain(outputs.iter().copied());
+  for map in maps {
+    for pair in map {
+      let (key, value) = pair.split_once(':').unwrap();
+      let key = Vec::<u8>::from_hex(key).unwrap();
+      let value = Vec::<u8>::from_hex(value).unwrap();
+      out.push(key.len() as u8);
+      out.extend(key);
+      out.push(value.len() as u8);
+      out.extend(value);
+    }
+    out.push(0x00);
+  }
+  return out;
+}
+
+/// The required fields vector with `pair` set in, or its key removed from,
+/// the given map.
+fn modified(map: usize, pair: &str, remove: bool) -> Vec<u8> {
+  let mut maps: Vec<Vec<&str>> = vec![
+    GLOBAL.to_vec(),
+    INPUT.to_vec(),
+    OUTPUT_0.to_vec(),
+    OUTPUT_1.to_vec(),
+  ];
+  let key = pair.split(':').next().unwrap();
+  maps[map].retain(|p| !p.starts_with(&format!("{}:", key)));
+  if !remove {
+    maps[map].push(pair);
+  }
+  return encode(&maps[0], &[&maps[1]], &[&maps[2], &maps[3]]);
+}
+
+#[test]
+fn bip370_required_fields_only() {
+  let bytes = base64::decode(REQUIRED_ONLY).unwrap();
+  assert_eq!(bytes, encode(&GLOBAL, &[&INPUT], &[&OUTPUT_0, &OUTPUT_1]));
+  assert_eq!(psbt_version(&bytes).unwrap(), 2);
+
+  let psbt = PsbtV2::deserialize(&bytes).unwrap();
+  let tx = &psbt.psbt.unsigned_tx;
+  assert_eq!(tx.version, 2);
+  assert_eq!(tx.lock_time, 0);
+  assert_eq!(tx.input.len(), 1);
+  assert_eq!(tx.input[0].sequence, 0xffffffff);
+  assert_eq!(
+    tx.output.iter().map(|out| out.value).collect::<Vec<u64>>(),
+    vec![1_091_592, 199_998_859]
+  );
+  assert_eq!(psbt.serialize(), bytes);
+
+  // the v0 view holds the same transaction
+  let v0 = consensus::serialize(&psbt.clone().into_v0());
+  assert_eq!(psbt_version(&v0).unwrap(), 0);
+  let v0: PartiallySignedTransaction = consensus::deserialize(&v0).unwrap();
+  assert_eq!(PsbtV2::from_v0(v0).serialize(), bytes);
+}
+
+#[test]
+fn bip370_invalid() {
+  let bytes = base64::decode(REQUIRED_ONLY).unwrap();
+  assert!(PsbtV2::deserialize(&bytes).is_ok());
+
+  let cases = [
+    // PSBTv2 with PSBT_GLOBAL_UNSIGNED_TX
+    modified(0, "00:02000000000000000000", false),
+    // missing PSBT_GLOBAL_INPUT_COUNT
+    modified(0, "04:", true),
+    // missing PSBT_GLOBAL_OUTPUT_COUNT
+    modified(0, "05:", true),
+    // missing PSBT_GLOBAL_TX_VERSION
+    modified(0, "02:", true),
+    // missing PSBT_IN_PREVIOUS_TXID
+    modified(1, "0e:", true),
+    // missing PSBT_IN_OUTPUT_INDEX
+    modified(1, "0f:", true),
+    // missing PSBT_OUT_AMOUNT
+    modified(2, "03:", true),
+    // missing PSBT_OUT_SCRIPT
+    modified(3, "04:", true),
+    // PSBT_IN_REQUIRED_TIME_LOCKTIME less than 500000000
+    modified(1, "11:ff64cd1d", false),
+    // PSBT_IN_REQUIRED_HEIGHT_LOCKTIME of at least 500000000
+    modified(1, "12:0065cd1d", false),
+    // more outputs than announced
+    modified(0, "05:01", false),
+  ];
+  for (i, case) in cases.iter().enumerate() {
+    assert_eq!(
+      PsbtV2::deserialize(case),
+      Err(VulcanError::MalformedPsbt),
+      "case {}",
+      i
+    );
+  }
+
+  // a v0 PSBT
+  assert_eq!(
+    PsbtV2::deserialize(&modified(0, "fb:", true)),
+    Err(VulcanError::UnsupportedVersion(0))
+  );
+}
+
+#[test]
+fn bip370_locktimes() {
+  let none = RequiredLockTime::default();
+  let height = RequiredLockTime {
+    time: None,
+    height: Some(10000),
+  };
+  let time = RequiredLockTime {
+    time: Some(1657048460),
+    height: None,
+  };
+  let both = RequiredLockTime {
+    time: Some(1657048460),
+    height: Some(10000),
+  };
+
+  assert_eq!(determine_locktime(None, &[none, none]), Ok(0));
+  assert_eq!(determine_locktime(Some(0), &[none, none]), Ok(0));
+  assert_eq!(determine_locktime(Some(10000), &[none, none]), Ok(10000));
+  assert_eq!(determine_locktime(None, &[height, none]), Ok(10000));
+  assert_eq!(determine_locktime(None, &[time, none]), Ok(1657048460));
+  assert_eq!(determine_locktime(None, &[height, both]), Ok(10000));
+  assert_eq!(determine_locktime(None, &[both, time]), Ok(1657048460));
+  assert_eq!(determine_locktime(Some(10000), &[both, none]), Ok(10000));
+  assert_eq!(
+    determine_locktime(None, &[height, time]),
+    Err(VulcanError::InconsistentPsbt)
+  );
+
+  // required locktimes are written back and decide the transaction's
+  let mut maps = INPUT.to_vec();
+  maps.push("11:8c2ac562");
+  let bytes = encode(&GLOBAL, &[&maps], &[&OUTPUT_0, &OUTPUT_1]);
+  let psbt = PsbtV2::deserialize(&bytes).unwrap();
+  assert_eq!(psbt.psbt.unsigned_tx.lock_time, 1657088652);
+  assert_eq!(psbt.serialize(), bytes);
+}
+
+#[test]
+fn bip370_all_fields() {
+  // the required fields vector with a fallback locktime, modifiable flags,
+  // a sequence and both required locktimes
+  let global = [
+    "02:02000000",
+    "03:00000000",
+    "04:01",
+    "05:02",
+    "06:03",
+    "fb:02000000",
+  ];
+  let input = [
+    INPUT[0],
+    INPUT[1],
+    "10:feffffff",
+    "11:8c2ac562",
+    "12:10270000",
+  ];
+  let bytes = encode(&global, &[&input], &[&OUTPUT_0, &OUTPUT_1]);
+
+  let psbt = PsbtV2::deserialize(&bytes).unwrap();
+  assert_eq!(psbt.fallback_locktime, Some(0));
+  assert_eq!(psbt.tx_modifiable, Some(0x03));
+  assert_eq!(
+    psbt.locktimes,
+    vec![RequiredLockTime {
+      time: Some(1657088652),
+      height: Some(10000),
+    }]
+  );
+  // an input that allows both prefers the height
+  assert_eq!(psbt.psbt.unsigned_tx.lock_time, 10000);
+  assert_eq!(psbt.psbt.unsigned_tx.input[0].sequence, 0xfffffffe);
+  assert_eq!(psbt.serialize(), bytes);
+}
+
+#[test]
+fn psbt_file_keeps_its_version() {
+  let bytes = base64::decode(REQUIRED_ONLY).unwrap();
+  let file = PsbtFile::deserialize(&bytes).unwrap();
+  assert_eq!(file.version(), 2);
+  assert_eq!(file, PsbtFile::V2(PsbtV2::deserialize(&bytes).unwrap()));
+  assert_eq!(file.serialize(), bytes);
+
+  let v0 = consensus::serialize(file.psbt());
+  let file = PsbtFile::deserialize(&v0).unwrap();
+  assert_eq!(file.version(), 0);
+  assert_eq!(file.serialize(), v0);
+
+  assert_eq!(
+    PsbtFile::deserialize(&v0[..v0.len() - 1]),
+    Err(VulcanError::MalformedPsbt)
+  );
+}
+
+#[test]
+fn sign_v2() {
+  let wallet = testnet_wallet(MNEMONIC);
+
+  let prev = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![],
+    output: vec![TxOut {
+      value: 100_000,
+      script_pubkey: wallet.address(false, 0).unwrap().script_pubkey(),
+    }],
+  };
+  let tx = Transaction {
+    version: 2,
+    lock_time: 2_000_000,
+    input: vec![TxIn {
+      previous_output: OutPoint::new(prev.txid(), 0),
+      script_sig: Script::new(),
+      sequence: 0xfffffffd,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 99_000,
+      script_pubkey: wallet.address(false, 1).unwrap().script_pubkey(),
+    }],
+  };
+  let mut v0 = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+  let (key, origin) = key_origin(MNEMONIC, "m/84'/1'/0'/0/0");
+  v0.inputs[0].witness_utxo = Some(prev.output[0].clone());
+  v0.inputs[0].non_witness_utxo = Some(prev);
+  v0.inputs[0].bip32_derivation.insert(key, origin);
+
+  let bytes = PsbtV2::from_v0(v0.clone()).serialize();
+  assert_eq!(psbt_version(&bytes).unwrap(), 2);
+  let mut v2 = PsbtV2::deserialize(&bytes).unwrap();
+  assert_eq!(v2.psbt, v0);
+  assert_eq!(v2.fallback_locktime, Some(2_000_000));
+
+  // a SIGHASH_ALL signature fixes the inputs and outputs
+  v2.tx_modifiable = Some(0x03);
+  assert!(wallet.sign(&mut v2.psbt).unwrap());
+  v2.mark_signed(EcdsaSighashType::All.into());
+  let signed = PsbtV2::deserialize(&v2.serialize()).unwrap();
+  assert!(signed.psbt.inputs[0]
+    .partial_sigs
+    .contains_key(&PublicKey::new(key)));
+  assert_eq!(signed.tx_modifiable, Some(0x00));
+  assert_eq!(signed, v2);
+
+  let mut other = signed.clone();
+  other.tx_modifiable = Some(0x03);
+  other.mark_signed(EcdsaSighashType::SinglePlusAnyoneCanPay.into());
+  assert_eq!(other.tx_modifiable, Some(0x07));
+
+  // signing the v0 PSBT gives the same signature
+  assert!(wallet.sign(&mut v0).unwrap());
+  assert_eq!(signed.into_v0(), v0);
+}
@@ -0,0 +1,152 @@
+//! SeedQR test vectors from the SeedSigner spec:
+//! https://github.com/SeedSigner/seedsigner/blob/dev/docs/seed_qr/README.md
+
+use libvulcan::{
+  bip39::{Language, Mnemonic},
+  bitcoin::util::bip32::DerivationPath,
+  decode_compact_seedqr, decode_seedqr, encode_compact_seedqr, encode_extended_seedqr,
+  encode_seedqr, parse_extended_seedqr, ScriptType, VulcanError,
+};
+use std::str::FromStr;
+
+struct Vector {
+  mnemonic: &'static str,
+  standard: &'static str,
+  compact: &'static str,
+}
+
+const VECTORS: [Vector; 7] = [
+  Vector {
+    mnemonic: "attack pizza motion avocado network gather crop fresh patrol unusual wild holiday candy pony ranch winter theme error hybrid van cereal salon goddess expire",
+    standard: "011513251154012711900771041507421289190620080870026613431420201617920614089619290300152408010643",
+    compact: "0e74b64107f94cc0ccfae6a13dcbec3662154fec67e0e00999c07892597d190a",
+  },
+  Vector {
+    mnemonic: "sound federal bonus bleak light raise false engage round stock update render quote truck quality fringe palace foot recipe labor glow tortoise potato still",
+    standard: "166206750203018810361417065805941507171219081456140818651401074412730727143709940798183613501710",
+    compact: "cfca8c658bc81962549252bc7ac3ba5b0b01d26bcae89f2b5ecebe263dcb2a36",
+  },
+  Vector {
+    mnemonic: "forum undo fragile fade shy sign arrest garment culture tube off merit",
+    standard: "073318950739065415961602009907670428187212261116",
+    compact: "5bbd9d71a8ec7990831aff359d426545",
+  },
+  Vector {
+    mnemonic: "approve fruit lens brass ring actual stool coin doll boss strong rate",
+    standard: "008607501025021714880023171503630517020917211425",
+    compact: "0acbba008d9ba005f5996b40a3475cd9",
+  },
+  Vector {
+    mnemonic: "dignity utility vacant shiver thought canoe feel multiply item youth actor coyote",
+    standard: "049619221923158517990268067811630950204300210397",
+    compact: "3e1e0bc1e31e0e4315348b76dfec0a98",
+  },
+  Vector {
+    mnemonic: "corn voice scrap arrow original diamond trial property benefit choose junk lock",
+    standard: "038719631547010112530489185713790169032209701051",
+    compact: "307eaf058659ca7a7a0d63152509e541",
+  },
+  Vector {
+    mnemonic: "vocal tray giggle tool duck letter category pattern train magnet excite swamp",
+    standard: "196218530783182905421028028912901848107106301753",
+    compact: "f55cf587f2543d01090d0ae710bd3b6d",
+  },
+];
+
+fn from_hex(hex: &str) -> Vec<u8> {
+  return (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+    .collect();
+}
+
+fn mnemonic(phrase: &str) -> Mnemonic {
+  return Mnemonic::parse_in_normalized(Language::English, phrase).unwrap();
+}
+
+#[test]
+fn standard_seedqr_vectors() {
+  for vector in VECTORS.iter() {
+    let m = mnemonic(vector.mnemonic);
+    assert_eq!(encode_seedqr(&m), vector.standard);
+    assert_eq!(decode_seedqr(vector.standard).unwrap(), m);
+  }
+}
+
+#[test]
+fn compact_seedqr_vectors() {
+  for vector in VECTORS.iter() {
+    let m = mnemonic(vector.mnemonic);
+    let compact = from_hex(vector.compact);
+    assert_eq!(encode_compact_seedqr(&m).unwrap(), compact);
+    assert_eq!(decode_compact_seedqr(&compact).unwrap(), m);
+  }
+}
+
+#[test]
+fn standard_seedqr_rejects_bad_input() {
+  // too short
+  assert_eq!(
+    decode_seedqr("0115132511540127"),
+    Err(VulcanError::BadWordCount(4))
+  );
+  // not a multiple of 4 digits
+  assert_eq!(decode_seedqr("01151"), Err(VulcanError::MalformedQr));
+  // non digits
+  assert_eq!(
+    decode_seedqr("07331895073906541596160200990767042818721226111a"),
+    Err(VulcanError::MalformedQr)
+  );
+  // index past the end of the wordlist
+  assert_eq!(
+    decode_seedqr("207318950739065415961602009907670428187212261116"),
+    Err(VulcanError::MalformedQr)
+  );
+  // bad checksum word
+  assert!(decode_seedqr("073318950739065415961602009907670428187212261117").is_err());
+}
+
+#[test]
+fn compact_seedqr_rejects_bad_input() {
+  assert_eq!(
+    decode_compact_seedqr(&[0u8; 20]),
+    Err(VulcanError::MalformedQr)
+  );
+  // CompactSeedQR is only defined for 12 and 24 words
+  let m = Mnemonic::from_entropy(&[0u8; 20]).unwrap();
+  assert_eq!(
+    encode_compact_seedqr(&m),
+    Err(VulcanError::BadWordCount(15))
+  );
+}
+
+#[test]
+fn extended_seedqr_round_trip() {
+  let m = mnemonic(VECTORS[2].mnemonic);
+  let path = DerivationPath::from_str("m/86h/0h/0h").unwrap();
+  let qr = encode_extended_seedqr(&m, &ScriptType::Taproot, &path).unwrap();
+  assert_eq!(
+    qr,
+    ["020", VECTORS[2].standard, "056054104047048104047048104"].join("")
+  );
+
+  let (words, parsed_path, script_type) = parse_extended_seedqr(&qr).unwrap();
+  assert_eq!(decode_seedqr(&encode_words(&words)).unwrap(), m);
+  assert_eq!(parsed_path, path);
+  assert_eq!(script_type, ScriptType::Taproot);
+}
+
+#[test]
+fn extended_seedqr_too_large() {
+  // 24 words and a multisig path are 3 + 96 + 36 digits
+  let m = mnemonic(VECTORS[0].mnemonic);
+  let path = DerivationPath::from_str("m/48h/0h/0h/2h").unwrap();
+  assert_eq!(
+    encode_extended_seedqr(&m, &ScriptType::Segwit, &path),
+    Err(VulcanError::QrTooLarge(135))
+  );
+}
+
+fn encode_words(words: &[u16]) -> String {
+  return words.iter().map(|w| format!("{:04}", w)).collect();
+}
@@ -0,0 +1,82 @@
+//! Seed XOR, with the example from the Coldcard documentation.
+
+use libvulcan::{
+  bip39::{Language, Mnemonic},
+  seed_xor_combine, seed_xor_split, VulcanError,
+};
+
+fn mnemonic(phrase: &str) -> Mnemonic {
+  return Mnemonic::parse_in_normalized(Language::English, phrase).unwrap();
+}
+
+#[test]
+fn coldcard_example() {
+  let parts = [
+    mnemonic("romance wink lottery autumn shop bring dawn tongue range crater truth ability miss spice fitness easy legal release recall obey exchange recycle dragon room"),
+    mnemonic("lion misery divide hurry latin fluid camp advance illegal lab pyramid unaware eager fringe sick camera series noodle toy crowd jeans select depth lounge"),
+    mnemonic("vault nominee cradle silk own frown throw leg cactus recall talent worry gadget surface shy planet purpose coffee drip few seven term squeeze educate"),
+  ];
+  let seed = mnemonic("silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor");
+  assert_eq!(seed_xor_combine(&parts).unwrap(), seed);
+
+  let reordered = [parts[2].clone(), parts[0].clone(), parts[1].clone()];
+  assert_eq!(seed_xor_combine(&reordered).unwrap(), seed);
+
+  // a missing part gives another valid seed
+  assert_ne!(seed_xor_combine(&parts[..2]).unwrap(), seed);
+}
+
+#[test]
+fn split_and_combine() {
+  let seed = mnemonic("silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor");
+  for count in 2..=4 {
+    let parts = seed_xor_split(&seed, count, &[9u8; 32]).unwrap();
+    assert_eq!(parts.len(), count);
+    for part in &parts {
+      assert_eq!(part.word_count(), 24);
+      assert_ne!(*part, seed);
+    }
+    assert_eq!(seed_xor_combine(&parts).unwrap(), seed);
+  }
+
+  // the parts depend on the RNG
+  assert_ne!(
+    seed_xor_split(&seed, 2, &[9u8; 32]).unwrap(),
+    seed_xor_split(&seed, 2, &[10u8; 32]).unwrap()
+  );
+
+  let short = mnemonic(
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+  );
+  let parts = seed_xor_split(&short, 3, &[1u8; 32]).unwrap();
+  assert_eq!(parts[2].word_count(), 12);
+  assert_eq!(seed_xor_combine(&parts).unwrap(), short);
+}
+
+#[test]
+fn bad_parts() {
+  let seed = mnemonic("silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor");
+  let short = mnemonic(
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+  );
+  assert_eq!(
+    seed_xor_split(&seed, 1, &[1u8; 32]),
+    Err(VulcanError::BadSeedXor)
+  );
+  assert_eq!(
+    seed_xor_split(&seed, 5, &[1u8; 32]),
+    Err(VulcanError::BadSeedXor)
+  );
+  assert_eq!(
+    seed_xor_split(&seed, 2, &[1u8; 16]),
+    Err(VulcanError::BadEntropyLength(16))
+  );
+  assert_eq!(
+    seed_xor_combine(std::slice::from_ref(&seed)),
+    Err(VulcanError::BadSeedXor)
+  );
+  assert_eq!(
+    seed_xor_combine(&[seed, short]),
+    Err(VulcanError::BadSeedXor)
+  );
+}
@@ -0,0 +1,195 @@
+//! Test vectors from SLIP-39, all with the passphrase "TREZOR":
+//! https://github.com/trezor/python-shamir-mnemonic/blob/master/vectors.json
+
+use libvulcan::{
+  bip39::{Language, Mnemonic},
+  bitcoin::{hashes::hex::ToHex, secp256k1::Secp256k1, Network},
+  combine_mnemonics, combine_shares, get_root, get_seed_root, slip39_words_by_prefix,
+  split_master_secret, Share, VulcanError,
+};
+use std::str::FromStr;
+
+const PASSPHRASE: &str = "TREZOR";
+
+#[test]
+fn without_sharing() {
+  let secret = combine_mnemonics(
+    &["duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard"],
+    PASSPHRASE,
+  )
+  .unwrap();
+  assert_eq!(secret.to_hex(), "bb54aac4b89dc868ba37d9cc21b2cece");
+
+  let secret = combine_mnemonics(
+    &["theory painting academic academic armed sweater year military elder discuss acne wildlife boring employer fused large satoshi bundle carbon diagnose anatomy hamster leaves tracks paces beyond phantom capital marvel lips brave detect luck"],
+    PASSPHRASE,
+  )
+  .unwrap();
+  assert_eq!(
+    secret.to_hex(),
+    "989baf9dcaad5b10ca33dfd8cc75e42477025dce88ae83e75a230086a0e00e92"
+  );
+
+  let share = Share::from_str("testify swimming academic academic column loyalty smear include exotic bedroom exotic wrist lobe cover grief golden smart junior estimate learn").unwrap();
+  assert!(share.extendable);
+  assert_eq!(
+    combine_shares(&[share], PASSPHRASE).unwrap().to_hex(),
+    "1679b4516e0ee5954351d288a838f45e"
+  );
+}
+
+#[test]
+fn invalid_shares() {
+  // bad checksum
+  assert_eq!(
+    Share::from_str("duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision kidney"),
+    Err(VulcanError::BadShare)
+  );
+  // non-zero padding
+  assert_eq!(
+    Share::from_str("duckling enlarge academic academic email result length solution fridge kidney coal piece deal husband erode duke ajar music cargo fitness"),
+    Err(VulcanError::BadShare)
+  );
+  // too short and unknown words
+  assert_eq!(
+    Share::from_str("duckling enlarge academic academic agency result"),
+    Err(VulcanError::BadShare)
+  );
+  assert_eq!(
+    Share::from_str("duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision satoshis"),
+    Err(VulcanError::BadShare)
+  );
+}
+
+#[test]
+fn basic_sharing() {
+  let shares = [
+    "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+    "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+  ];
+  assert_eq!(
+    combine_mnemonics(&shares, PASSPHRASE).unwrap().to_hex(),
+    "b43ceb7e57a0ea8766221624d01b0864"
+  );
+  assert_eq!(
+    combine_mnemonics(&shares[..1], PASSPHRASE),
+    Err(VulcanError::InsufficientShares)
+  );
+
+  let shares = [
+    "humidity disease academic always aluminum jewelry energy woman receiver strategy amuse duckling lying evidence network walnut tactics forget hairy rebound impulse brother survive clothes stadium mailman rival ocean reward venture always armed unwrap",
+    "humidity disease academic agency actress jacket gross physics cylinder solution fake mortgage benefit public busy prepare sharp friar change work slow purchase ruler again tricycle involve viral wireless mixture anatomy desert cargo upgrade",
+  ];
+  assert_eq!(
+    combine_mnemonics(&shares, PASSPHRASE).unwrap().to_hex(),
+    "c938b319067687e990e05e0da0ecce1278f75ff58d9853f19dcaeed5de104aae"
+  );
+}
+
+#[test]
+fn groups() {
+  let shares = [
+    "eraser senior beard romp adorn nuclear spill corner cradle style ancient family general leader ambition exchange unusual garlic promise voice",
+    "eraser senior ceramic snake clay various huge numb argue hesitate auction category timber browser greatest hanger petition script leaf pickup",
+    "eraser senior ceramic shaft dynamic become junior wrist silver peasant force math alto coal amazing segment yelp velvet image paces",
+    "eraser senior ceramic round column hawk trust auction smug shame alive greatest sheriff living perfect corner chest sled fumes adequate",
+    "eraser senior decision smug corner ruin rescue cubic angel tackle skin skunk program roster trash rumor slush angel flea amazing",
+  ];
+  assert_eq!(
+    combine_mnemonics(&shares, PASSPHRASE).unwrap().to_hex(),
+    "7c3397a292a5941682d7a4ae2d898d11"
+  );
+}
+
+#[test]
+fn split_and_recover() {
+  let secret: Vec<u8> = (0..32).collect();
+  let random = [7u8; 32];
+  let groups = split_master_secret(
+    &secret,
+    PASSPHRASE,
+    2,
+    &[(1, 1), (2, 3), (3, 5)],
+    0,
+    true,
+    &random,
+  )
+  .unwrap();
+  assert_eq!(groups.len(), 3);
+  assert_eq!(groups[2].len(), 5);
+
+  // every share survives a round trip through its words
+  for share in groups.iter().flatten() {
+    assert_eq!(share.words().len(), 33);
+    assert_eq!(Share::from_str(&share.to_string()).unwrap(), *share);
+  }
+
+  let mut shares = vec![groups[0][0].clone(), groups[2][4].clone()];
+  assert_eq!(
+    combine_shares(&shares, PASSPHRASE),
+    Err(VulcanError::InsufficientShares)
+  );
+  shares.push(groups[2][1].clone());
+  shares.push(groups[2][1].clone());
+  assert_eq!(
+    combine_shares(&shares, PASSPHRASE),
+    Err(VulcanError::InsufficientShares)
+  );
+  shares.push(groups[2][2].clone());
+  assert_eq!(combine_shares(&shares, PASSPHRASE).unwrap(), secret);
+
+  // the passphrase decrypts, so a wrong one gives another secret
+  assert_ne!(combine_shares(&shares, "").unwrap(), secret);
+
+  // shares of another backup don't mix
+  let other = split_master_secret(&secret, PASSPHRASE, 1, &[(2, 2)], 0, true, &[8u8; 32]).unwrap();
+  assert_eq!(
+    combine_shares(&[groups[1][0].clone(), other[0][0].clone()], PASSPHRASE),
+    Err(VulcanError::ShareMismatch)
+  );
+
+  assert_eq!(
+    split_master_secret(&secret, "", 1, &[(1, 3)], 0, true, &random),
+    Err(VulcanError::BadShamirGroups)
+  );
+  assert_eq!(
+    split_master_secret(&secret, "", 3, &[(2, 3), (2, 3)], 0, true, &random),
+    Err(VulcanError::BadShamirGroups)
+  );
+  assert_eq!(
+    split_master_secret(&secret[..15], "", 1, &[(2, 3)], 0, true, &random),
+    Err(VulcanError::BadEntropyLength(15))
+  );
+}
+
+#[test]
+fn bip39_seed() {
+  let mnemonic = Mnemonic::parse_in_normalized(
+    Language::English,
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+  )
+  .unwrap();
+  let seed = mnemonic.to_seed_normalized("");
+  let groups = split_master_secret(&seed, "", 1, &[(2, 3)], 0, false, &[1u8; 32]).unwrap();
+  assert_eq!(groups[0][0].words().len(), 59);
+
+  let shares = [groups[0][2].clone(), groups[0][0].clone()];
+  let recovered = combine_shares(&shares, "").unwrap();
+  let secp = Secp256k1::new();
+  assert_eq!(
+    get_seed_root(&recovered, Network::Bitcoin)
+      .unwrap()
+      .fingerprint(&secp),
+    get_root(&mnemonic, Network::Bitcoin)
+      .unwrap()
+      .fingerprint(&secp)
+  );
+}
+
+#[test]
+fn words_by_prefix() {
+  assert_eq!(slip39_words_by_prefix("acad"), ["academic"]);
+  assert_eq!(slip39_words_by_prefix("ac").len(), 7);
+  assert_eq!(slip39_words_by_prefix("zero"), ["zero"]);
+  assert!(slip39_words_by_prefix("abandon").is_empty());
+}
@@ -0,0 +1,268 @@
+//! Address and key vectors from BIP49, BIP84 and BIP86, all for the mnemonic
+//! "abandon abandon ... about".
+
+use common::{root, wallet, MNEMONIC};
+use libvulcan::{
+  bitcoin::{
+    hashes::Hash,
+    secp256k1::{Message, Secp256k1, XOnlyPublicKey},
+    util::{
+      bip32::DerivationPath,
+      psbt::PartiallySignedTransaction,
+      sighash::{Prevouts, SighashCache},
+      taproot::TapBranchHash,
+    },
+    EcdsaSighashType, Network, OutPoint, SchnorrSighashType, Script, Transaction, TxIn, TxOut,
+    Witness,
+  },
+  descriptor::checksum,
+  ScriptType,
+};
+use std::str::FromStr;
+
+mod common;
+
+#[test]
+fn bip49_addresses() {
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::NestedSegwit);
+  assert_eq!(
+    w.address(false, 0).unwrap().to_string(),
+    "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf"
+  );
+
+  let w = wallet(MNEMONIC, Network::Testnet, ScriptType::NestedSegwit);
+  assert_eq!(
+    w.address(false, 0).unwrap().to_string(),
+    "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2"
+  );
+}
+
+#[test]
+fn bip84_addresses() {
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit);
+  assert_eq!(
+    w.address(false, 0).unwrap().to_string(),
+    "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+  );
+  assert_eq!(
+    w.address(false, 1).unwrap().to_string(),
+    "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g"
+  );
+  assert_eq!(
+    w.address(true, 0).unwrap().to_string(),
+    "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el"
+  );
+}
+
+#[test]
+fn bip86_addresses() {
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Taproot);
+  assert_eq!(
+    w.address(false, 0).unwrap().to_string(),
+    "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+  );
+  assert_eq!(
+    w.address(false, 1).unwrap().to_string(),
+    "bc1p4qhjn9zdvkux4e44uhx8tc55attvtyu358kutcqkudyccelu0was9fqzwh"
+  );
+  assert_eq!(
+    w.address(true, 0).unwrap().to_string(),
+    "bc1p3qkhfews2uk44qtvauqyr2ttdsw7svhkl9nkm9s9c3x4ax5h60wqwruhk7"
+  );
+}
+
+#[test]
+fn descriptors() {
+  // BIP380 example
+  assert_eq!(checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+
+  let xpub = "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V";
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::NestedSegwit);
+  assert!(w
+    .descriptor(false)
+    .unwrap()
+    .starts_with("sh(wpkh([73c5da0a/49h/0h/0h]xpub"));
+
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Segwit);
+  let desc = w.descriptor(false).unwrap();
+  assert_eq!(
+    desc.split('#').next().unwrap(),
+    format!("wpkh([73c5da0a/84h/0h/0h]{}/0/*)", xpub)
+  );
+  let (body, sum) = desc.split_at(desc.len() - 9);
+  assert_eq!(sum, format!("#{}", checksum(body).unwrap()));
+
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Taproot);
+  assert!(w
+    .descriptor(true)
+    .unwrap()
+    .starts_with("tr([73c5da0a/86h/0h/0h]xpub"));
+}
+
+#[test]
+fn sign_nested_segwit_input() {
+  let secp = Secp256k1::new();
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::NestedSegwit);
+  let address = w.address(false, 0).unwrap();
+
+  let root = root(MNEMONIC, Network::Bitcoin);
+  let path = DerivationPath::from_str("m/49h/0h/0h/0/0").unwrap();
+  let pubkey = root
+    .derive_priv(&secp, &path)
+    .unwrap()
+    .to_priv()
+    .public_key(&secp);
+
+  let tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::default(),
+      script_sig: Script::new(),
+      sequence: 0xffffffff,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 9000,
+      script_pubkey: address.script_pubkey(),
+    }],
+  };
+  let utxo = TxOut {
+    value: 10000,
+    script_pubkey: address.script_pubkey(),
+  };
+  let redeem_script = Script::new_v0_p2wpkh(&pubkey.wpubkey_hash().unwrap());
+
+  let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx.clone()).unwrap();
+  psbt.inputs[0].witness_utxo = Some(utxo.clone());
+  psbt.inputs[0].redeem_script = Some(redeem_script);
+  psbt.inputs[0]
+    .bip32_derivation
+    .insert(pubkey.inner, (w.fingerprint(), path));
+
+  assert!(w.sign(&mut psbt).unwrap());
+
+  let sig = psbt.inputs[0].partial_sigs.get(&pubkey).unwrap();
+  let script_code = Script::new_p2pkh(&pubkey.pubkey_hash());
+  let sighash = SighashCache::new(&tx)
+    .segwit_signature_hash(0, &script_code, utxo.value, EcdsaSighashType::All)
+    .unwrap();
+  let msg = Message::from_slice(&sighash[..]).unwrap();
+  assert!(secp.verify_ecdsa(&msg, &sig.sig, &pubkey.inner).is_ok());
+}
+
+#[test]
+fn sign_taproot_input() {
+  let secp = Secp256k1::new();
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Taproot);
+  let address = w.address(false, 0).unwrap();
+  assert_eq!(
+    address.to_string(),
+    "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+  );
+
+  // BIP86 internal and tweaked output key of m/86'/0'/0'/0/0
+  let internal_key =
+    XOnlyPublicKey::from_str("cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115")
+      .unwrap();
+  let output_key =
+    XOnlyPublicKey::from_str("a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c")
+      .unwrap();
+  assert_eq!(
+    XOnlyPublicKey::from_slice(&address.script_pubkey()[2..]).unwrap(),
+    output_key
+  );
+
+  let tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::default(),
+      script_sig: Script::new(),
+      sequence: 0xffffffff,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 9000,
+      script_pubkey: address.script_pubkey(),
+    }],
+  };
+  let utxo = TxOut {
+    value: 10000,
+    script_pubkey: address.script_pubkey(),
+  };
+  let path = DerivationPath::from_str("m/86h/0h/0h/0/0").unwrap();
+
+  let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx.clone()).unwrap();
+  psbt.inputs[0].witness_utxo = Some(utxo.clone());
+  psbt.inputs[0].tap_internal_key = Some(internal_key);
+  psbt.inputs[0]
+    .tap_key_origins
+    .insert(internal_key, (vec![], (w.fingerprint(), path)));
+
+  assert!(w.sign(&mut psbt).unwrap());
+
+  let sig = psbt.inputs[0].tap_key_sig.unwrap();
+  assert_eq!(sig.hash_ty, SchnorrSighashType::Default);
+  let sighash = SighashCache::new(&tx)
+    .taproot_key_spend_signature_hash(0, &Prevouts::All(&[utxo]), SchnorrSighashType::Default)
+    .unwrap();
+  let msg = Message::from_slice(&sighash[..]).unwrap();
+  assert!(secp.verify_schnorr(&sig.sig, &msg, &output_key).is_ok());
+  assert!(secp.verify_schnorr(&sig.sig, &msg, &internal_key).is_err());
+}
+
+#[test]
+fn taproot_input_with_script_tree_is_skipped() {
+  let secp = Secp256k1::new();
+  let w = wallet(MNEMONIC, Network::Bitcoin, ScriptType::Taproot);
+  let internal_key =
+    XOnlyPublicKey::from_str("cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115")
+      .unwrap();
+  let path = DerivationPath::from_str("m/86h/0h/0h/0/0").unwrap();
+  let merkle_root = TapBranchHash::from_inner([1; 32]);
+
+  let tx = Transaction {
+    version: 2,
+    lock_time: 0,
+    input: vec![TxIn {
+      previous_output: OutPoint::default(),
+      script_sig: Script::new(),
+      sequence: 0xffffffff,
+      witness: Witness::default(),
+    }],
+    output: vec![TxOut {
+      value: 9000,
+      script_pubkey: w.address(false, 0).unwrap().script_pubkey(),
+    }],
+  };
+  let psbt_spending = |script_pubkey: Script, merkle_root: Option<TapBranchHash>| {
+    let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx.clone()).unwrap();
+    psbt.inputs[0].witness_utxo = Some(TxOut {
+      value: 10000,
+      script_pubkey,
+    });
+    psbt.inputs[0].tap_internal_key = Some(internal_key);
+    psbt.inputs[0].tap_merkle_root = merkle_root;
+    psbt.inputs[0]
+      .tap_key_origins
+      .insert(internal_key, (vec![], (w.fingerprint(), path.clone())));
+    return psbt;
+  };
+
+  // an output that commits to a script tree, with the merkle root to match
+  let tree_output = Script::new_v1_p2tr(&secp, internal_key, Some(merkle_root));
+  let mut psbt = psbt_spending(tree_output, Some(merkle_root));
+  assert!(!w.sign(&mut psbt).unwrap());
+  assert!(psbt.inputs[0].tap_key_sig.is_none());
+
+  // the merkle root left out of the PSBT
+  let tree_output = Script::new_v1_p2tr(&secp, internal_key, Some(merkle_root));
+  let mut psbt = psbt_spending(tree_output, None);
+  assert!(!w.sign(&mut psbt).unwrap());
+
+  // a different output key than the internal key tweaks to
+  let other = w.address(false, 1).unwrap().script_pubkey();
+  let mut psbt = psbt_spending(other, None);
+  assert!(!w.sign(&mut psbt).unwrap());
+}
@@ -69,6 +69,19 @@ pub enum Key {
   X,
   Y,
   Z,
+  Zero,
+  One,
+  Two,
+  Three,
+  Four,
+  Five,
+  Six,
+  Seven,
+  Eight,
+  Nine,
+  /// switches the passphrase screen between upper case, lower case and
+  /// symbols
+  Shift,
   Back,
   Forward,
 }
@@ -102,10 +115,64 @@ impl Key {
       Key::X => "X",
       Key::Y => "Y",
       Key::Z => "Z",
+      Key::Zero => "0",
+      Key::One => "1",
+      Key::Two => "2",
+      Key::Three => "3",
+      Key::Four => "4",
+      Key::Five => "5",
+      Key::Six => "6",
+      Key::Seven => "7",
+      Key::Eight => "8",
+      Key::Nine => "9",
+      // types nothing, it changes what the next keys type
+      Key::Shift => "",
       Key::Back => "*",
       Key::Forward => "#",
     }
   }
+
+  /// What the key types in the passphrase screen's symbol layout. Every
+  /// printable ASCII symbol and the space are on the letter keys and the
+  /// digit keys 2 to 8, the other digits stay.
+  pub fn to_symbol(&self) -> &str {
+    match *self {
+      Key::A => " ",
+      Key::B => ".",
+      Key::C => ",",
+      Key::Two => "?",
+      Key::D => "!",
+      Key::E => "'",
+      Key::F => "\"",
+      Key::Three => "-",
+      Key::G => "@",
+      Key::H => "#",
+      Key::I => "$",
+      Key::Four => "%",
+      Key::J => "&",
+      Key::K => "*",
+      Key::L => "+",
+      Key::Five => "=",
+      Key::M => "/",
+      Key::N => "\\",
+      Key::O => "|",
+      Key::Six => "_",
+      Key::P => "(",
+      Key::Q => ")",
+      Key::R => "{",
+      Key::S => "}",
+      Key::Seven => ":",
+      Key::T => "[",
+      Key::U => "]",
+      Key::V => "<",
+      Key::Eight => ">",
+      Key::W => ";",
+      Key::X => "^",
+      Key::Y => "`",
+      Key::Z => "~",
+      _ => self.to_string(),
+    }
+  }
 }
 impl defmt::Format for Key {
   fn format(&self, f: defmt::Formatter) {
@@ -136,6 +203,17 @@ impl defmt::Format for Key {
       Key::X => defmt::write!(f, "X"),
       Key::Y => defmt::write!(f, "Y"),
       Key::Z => defmt::write!(f, "Z"),
+      Key::Zero => defmt::write!(f, "0"),
+      Key::One => defmt::write!(f, "1"),
+      Key::Two => defmt::write!(f, "2"),
+      Key::Three => defmt::write!(f, "3"),
+      Key::Four => defmt::write!(f, "4"),
+      Key::Five => defmt::write!(f, "5"),
+      Key::Six => defmt::write!(f, "6"),
+      Key::Seven => defmt::write!(f, "7"),
+      Key::Eight => defmt::write!(f, "8"),
+      Key::Nine => defmt::write!(f, "9"),
+      Key::Shift => defmt::write!(f, "Shift"),
       Key::Back => defmt::write!(f, "*"),
       Key::Forward => defmt::write!(f, "#"),
     }
@@ -230,26 +308,32 @@ impl defmt::Format for NavigationKey {
   }
 }
 
+/// Keys typed by pressing a button once, twice and so on. The digit comes
+/// after the letters.
 struct ButtonKeyMap {
-  two: [Key; 3],
-  three: [Key; 3],
-  four: [Key; 3],
-  five: [Key; 3],
-  six: [Key; 3],
-  seven: [Key; 4],
-  eight: [Key; 3],
-  nine: [Key; 4],
+  zero: [Key; 1],
+  one: [Key; 2],
+  two: [Key; 4],
+  three: [Key; 4],
+  four: [Key; 4],
+  five: [Key; 4],
+  six: [Key; 4],
+  seven: [Key; 5],
+  eight: [Key; 4],
+  nine: [Key; 5],
 }
 
 static BUTTON_KEY_MAP: ButtonKeyMap = ButtonKeyMap {
-  two: [Key::A, Key::B, Key::C],
-  three: [Key::D, Key::E, Key::F],
-  four: [Key::G, Key::H, Key::I],
-  five: [Key::J, Key::K, Key::L],
-  six: [Key::M, Key::N, Key::O],
-  seven: [Key::P, Key::Q, Key::R, Key::S],
-  eight: [Key::T, Key::U, Key::V],
-  nine: [Key::W, Key::X, Key::Y, Key::Z],
+  zero: [Key::Zero],
+  one: [Key::One, Key::Shift],
+  two: [Key::A, Key::B, Key::C, Key::Two],
+  three: [Key::D, Key::E, Key::F, Key::Three],
+  four: [Key::G, Key::H, Key::I, Key::Four],
+  five: [Key::J, Key::K, Key::L, Key::Five],
+  six: [Key::M, Key::N, Key::O, Key::Six],
+  seven: [Key::P, Key::Q, Key::R, Key::S, Key::Seven],
+  eight: [Key::T, Key::U, Key::V, Key::Eight],
+  nine: [Key::W, Key::X, Key::Y, Key::Z, Key::Nine],
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -290,18 +374,19 @@ impl Button {
       panic!("button_to_key: times_pressed out of range");
     }
     if *self == Button::Seven || *self == Button::Nine {
-      if times_pressed > 4 {
+      if times_pressed > 5 {
         panic!("button_to_key: times_pressed out of range");
       }
-    } else if times_pressed > 3 {
+    } else if times_pressed > 4 {
       panic!("button_to_key: times_pressed out of range");
     }
 
     let i = times_pressed - 1;
 
     match *self {
-      Button::Zero => None,
-      Button::One => None,
+      // 0 only has its digit, 1 pressed twice is shift
+      Button::Zero => BUTTON_KEY_MAP.zero.get(i).copied(),
+      Button::One => BUTTON_KEY_MAP.one.get(i).copied(),
       Button::Two => Some(BUTTON_KEY_MAP.two[i]),
       Button::Three => Some(BUTTON_KEY_MAP.three[i]),
       Button::Four => Some(BUTTON_KEY_MAP.four[i]),
@@ -353,9 +438,10 @@ impl defmt::Format for ButtonEvent {
   }
 }
 
-pub type EventBuffer = [ButtonEvent; 8];
+pub type EventBuffer = [ButtonEvent; 10];
 pub trait EventBufferUtil {
   fn unshift(&mut self, event: ButtonEvent) -> ();
+  fn check_for_quint(&self) -> bool;
   fn check_for_quad(&self) -> bool;
   fn check_for_triple(&self) -> bool;
   fn check_for_double(&self) -> bool;
@@ -372,7 +458,7 @@ impl EventBufferUtil for EventBuffer {
     self[0] = event;
   }
 
-  fn check_for_quad(&self) -> bool {
+  fn check_for_quint(&self) -> bool {
     if self[1].is_some()
       && self[2].is_none()
       && self[3].is_some()
@@ -380,10 +466,13 @@ impl EventBufferUtil for EventBuffer {
       && self[5].is_some()
       && self[6].is_none()
       && self[7].is_some()
+      && self[8].is_none()
+      && self[9].is_some()
     {
       if self[1].button == self[3].button
         && self[3].button == self[5].button
         && self[5].button == self[7].button
+        && self[7].button == self[9].button
       {
         let button = self[1].button.unwrap();
         if button == Button::Seven || button == Button::Nine {
@@ -395,6 +484,29 @@ impl EventBufferUtil for EventBuffer {
     return false;
   }
 
+  fn check_for_quad(&self) -> bool {
+    if self[1].is_some()
+      && self[2].is_none()
+      && self[3].is_some()
+      && self[4].is_none()
+      && self[5].is_some()
+      && self[6].is_none()
+      && self[7].is_some()
+    {
+      if self[1].button == self[3].button
+        && self[3].button == self[5].button
+        && self[5].button == self[7].button
+      {
+        let button = self[1].button.unwrap();
+        if button != Button::Zero && button != Button::One {
+          return true;
+        }
+      }
+    }
+
+    return false;
+  }
+
   fn check_for_triple(&self) -> bool {
     if self[1].is_some()
       && self[2].is_none()
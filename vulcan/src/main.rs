@@ -30,6 +30,8 @@ defmt::timestamp!("{=usize}", {
 
 #[global_allocator]
 static ALLOCATOR: CortexMHeap = CortexMHeap::empty();
+// libvulcan allocates while parsing PSBTs and deriving keys
+const HEAP_SIZE: usize = 64 * 1024;
 
 // #[alloc_error_handler]
 // fn oom(_: Layout) -> ! {
@@ -47,20 +49,25 @@ mod view;
 mod app {
   use crate::framebuffer::Framebuffer;
   use crate::keypad::{self, EventBufferUtil, KeypadRead};
-  use crate::types::{BacklightLED, Cmd, Display, KeyType, KeypadMode, Model, Msg, Screen};
+  use crate::types::{
+    BacklightLED, Cmd, Display, KeyType, KeypadMode, Model, Msg, Screen, ADDRESS_FILE,
+    MESSAGE_FILE, MULTISIG_FILE, REGISTERED_MULTISIG_FILE, SETTINGS_FILE, SIGNED_PSBT_FILE,
+  };
   use crate::update::update;
   use crate::view::view;
-  use alloc::vec::Vec;
+  use alloc::{format, string::String, vec, vec::Vec};
   use asm_delay::{bitrate, AsmDelay};
   use display_interface_spi::SPIInterface;
   use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
   use embedded_hal::spi::{Mode, Phase, Polarity};
   use embedded_hal::{digital::v2::OutputPin, prelude::*};
-  use embedded_sdmmc::{Controller, File, TimeSource, Timestamp, VolumeIdx};
+  use embedded_sdmmc::{Controller, File, ShortFileName, TimeSource, Timestamp, VolumeIdx};
   use fatfs::{FileSystem, FsOptions};
   use keypad2::Keypad;
+  use libvulcan::VulcanError;
   use st7789::{Orientation, TearingEffect, ST7789};
   use stm32h7xx_hal::device::SDMMC1;
+  use stm32h7xx_hal::rng::{Rng, RngCore};
   use stm32h7xx_hal::sdmmc::{Sdmmc, SdmmcBlockDevice};
   use stm32h7xx_hal::{prelude::*, rcc};
   use systick_monotonic::*;
@@ -97,6 +104,7 @@ mod app {
     backlight: BacklightLED,
     event_buffer: Option<keypad::EventBuffer>,
     framebuffer: Framebuffer,
+    rng: Rng,
     // sd: Sdmmc<SDMMC1>,
     sd_fatfs: Option<Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>>,
   }
@@ -104,7 +112,7 @@ mod app {
   #[init]
   fn init(ctx: init::Context) -> (Shared, Local, init::Monotonics) {
     unsafe {
-      super::ALLOCATOR.init(cortex_m_rt::heap_start() as usize, 512);
+      super::ALLOCATOR.init(cortex_m_rt::heap_start() as usize, super::HEAP_SIZE);
     };
 
     defmt::info!("INIT");
@@ -191,6 +199,8 @@ mod app {
       Keypad::new(rows, cols)
     };
 
+    let rng = ctx.device.RNG.constrain(ccdr.peripheral.RNG, &ccdr.clocks);
+
     let sd_fatfs: Option<Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>> = {
       // SDMMC1 pins
       let clk = gpioc.pc12.into_alternate_af12();
@@ -242,6 +252,7 @@ mod app {
         delay,
         event_buffer: None,
         framebuffer: Framebuffer::new(),
+        rng,
         sd_fatfs,
       },
       init::Monotonics(mono),
@@ -260,14 +271,14 @@ mod app {
     }
   }
 
-  #[task(priority = 3, shared = [state, should_render], local = [sd_fatfs])]
+  #[task(priority = 3, shared = [state, should_render], local = [sd_fatfs, rng])]
   fn update_task(ctx: update_task::Context, msg: Msg) {
     let update_task::SharedResources {
       should_render,
       state,
     } = ctx.shared;
     let local = ctx.local;
-    let update_task::LocalResources { sd_fatfs } = local;
+    let update_task::LocalResources { sd_fatfs, rng } = local;
 
     (should_render, state).lock(|should_render, state| {
       let cmd = update(state, msg);
@@ -299,6 +310,82 @@ mod app {
             sd.close_dir(&volume, root_dir);
           }
         }
+        Cmd::LoadMultisigConfig => {
+          let msg = match load_text_file(sd_fatfs, MULTISIG_FILE, MAX_CONFIG_SIZE) {
+            Ok(text) => Msg::ConfigLoaded(text),
+            Err(err) => Msg::Error(err),
+          };
+          update_task::spawn(msg).ok();
+        }
+        Cmd::LoadMessage => {
+          let msg = match load_text_file(sd_fatfs, MESSAGE_FILE, MAX_MESSAGE_SIZE) {
+            Ok(text) => Msg::MessageLoaded(text),
+            Err(err) => Msg::Error(err),
+          };
+          update_task::spawn(msg).ok();
+        }
+        Cmd::LoadAddress => {
+          let msg = match load_text_file(sd_fatfs, ADDRESS_FILE, MAX_ADDRESS_SIZE) {
+            Ok(text) => Msg::AddressLoaded(text),
+            Err(err) => Msg::Error(err),
+          };
+          update_task::spawn(msg).ok();
+        }
+        Cmd::LoadPsbt => {
+          let bytes = match sd_fatfs {
+            Some(sd) => find_file(sd, b"PSB", SIGNED_PSBT_FILE, MAX_PSBT_SIZE)
+              .and_then(|name| read_bytes(sd, &name, MAX_PSBT_SIZE))
+              .ok_or(VulcanError::FileNotFound("PSBT")),
+            None => Err(VulcanError::NoSdCard),
+          };
+          let msg = match bytes {
+            Ok(bytes) => Msg::PsbtLoaded(bytes),
+            Err(err) => Msg::Error(err),
+          };
+          update_task::spawn(msg).ok();
+        }
+        Cmd::WriteFile(filename, contents) => {
+          let msg = match sd_fatfs {
+            Some(sd) => match write_file(sd, filename, contents.as_bytes()) {
+              Some(()) => Msg::FileWritten(filename),
+              None => Msg::Error(VulcanError::WriteFailed(filename)),
+            },
+            None => Msg::Error(VulcanError::NoSdCard),
+          };
+          update_task::spawn(msg).ok();
+        }
+        Cmd::ChipEntropy => {
+          let mut bytes = [0u8; 32];
+          match rng.fill(&mut bytes) {
+            Ok(()) => {
+              update_task::spawn(Msg::ChipEntropy(bytes)).ok();
+            }
+            Err(_) => {
+              defmt::info!("the RNG failed, not creating a mnemonic without it");
+              update_task::spawn(Msg::Error(VulcanError::BadEntropy)).ok();
+            }
+          }
+        }
+        Cmd::LoadSettings => {
+          // without a card or a file the defaults stay
+          let text = match sd_fatfs {
+            Some(sd) => read_file(sd, SETTINGS_FILE, MAX_SETTINGS_SIZE),
+            None => None,
+          };
+          update_task::spawn(Msg::SettingsLoaded(text.unwrap_or_default())).ok();
+        }
+        Cmd::LoadRegisteredMultisig => {
+          // without a card or a file no wallet is registered
+          let text = match sd_fatfs {
+            Some(sd) => read_file(sd, REGISTERED_MULTISIG_FILE, MAX_CONFIG_SIZE),
+            None => None,
+          };
+          update_task::spawn(Msg::RegisteredMultisigLoaded(text.unwrap_or_default())).ok();
+        }
+        Cmd::ScanQR => {
+          // the OV2640 isn't driven yet, scanned text arrives as `Msg::QRScanned`
+          update_task::spawn(Msg::Error(VulcanError::NoCamera)).ok();
+        }
         Cmd::None => {}
       };
 
@@ -306,6 +393,135 @@ mod app {
     });
   }
 
+  // setup files are a few hundred bytes per cosigner
+  const MAX_CONFIG_SIZE: u32 = 8 * 1024;
+  // a 240 character message, a path and an address type
+  const MAX_MESSAGE_SIZE: u32 = 512;
+  // one address or BIP21 URI
+  const MAX_ADDRESS_SIZE: u32 = 256;
+  // a few `key=value` lines
+  const MAX_SETTINGS_SIZE: u32 = 256;
+  // the heap holds the file, the parsed PSBT and the signed base64 at once
+  const MAX_PSBT_SIZE: u32 = 12 * 1024;
+
+  /// Name of the first file with `extension` of at most `max_size` bytes in
+  /// the root directory of the SD card, other than `skip`.
+  fn find_file(
+    sd: &mut Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>,
+    extension: &[u8],
+    skip: &str,
+    max_size: u32,
+  ) -> Option<String> {
+    let volume = sd.get_volume(VolumeIdx(0)).ok()?;
+    let root_dir = sd.open_root_dir(&volume).ok()?;
+
+    let mut name: Option<ShortFileName> = None;
+    sd.iterate_dir(&volume, &root_dir, |entry| {
+      if name.is_none()
+        && !entry.attributes.is_directory()
+        && entry.name.extension() == extension
+        && entry.size <= max_size
+        && format!("{}", entry.name) != skip
+      {
+        name = Some(entry.name.clone());
+      }
+    })
+    .ok();
+
+    sd.close_dir(&volume, root_dir);
+    return Some(format!("{}", name?));
+  }
+
+  /// Reads the text file a screen asked for, or the error to show if there's
+  /// no card or no such file.
+  fn load_text_file(
+    sd_fatfs: &mut Option<Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>>,
+    filename: &'static str,
+    max_size: u32,
+  ) -> Result<String, VulcanError> {
+    let sd = sd_fatfs.as_mut().ok_or(VulcanError::NoSdCard)?;
+    return read_file(sd, filename, max_size).ok_or(VulcanError::FileNotFound(filename));
+  }
+
+  /// Reads `filename` from the root directory of the SD card if it's text of
+  /// at most `max_size` bytes.
+  fn read_file(
+    sd: &mut Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>,
+    filename: &str,
+    max_size: u32,
+  ) -> Option<String> {
+    return String::from_utf8(read_bytes(sd, filename, max_size)?).ok();
+  }
+
+  /// Reads `filename` from the root directory of the SD card if it's at most
+  /// `max_size` bytes.
+  fn read_bytes(
+    sd: &mut Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>,
+    filename: &str,
+    max_size: u32,
+  ) -> Option<Vec<u8>> {
+    let mut volume = sd.get_volume(VolumeIdx(0)).ok()?;
+    let root_dir = sd.open_root_dir(&volume).ok()?;
+
+    let contents = match sd.open_file_in_dir(
+      &mut volume,
+      &root_dir,
+      filename,
+      embedded_sdmmc::Mode::ReadOnly,
+    ) {
+      Ok(mut file) if file.length() <= max_size => {
+        let mut contents: Vec<u8> = vec![0u8; file.length() as usize];
+        let read = sd.read(&volume, &mut file, &mut contents);
+        sd.close_file(&volume, file).ok();
+        match read {
+          Ok(len) => {
+            contents.truncate(len);
+            Some(contents)
+          }
+          Err(_) => None,
+        }
+      }
+      Ok(file) => {
+        sd.close_file(&volume, file).ok();
+        None
+      }
+      Err(_) => None,
+    };
+
+    sd.close_dir(&volume, root_dir);
+    return contents;
+  }
+
+  /// Creates or replaces `filename` in the root directory of the SD card.
+  fn write_file(
+    sd: &mut Controller<SdmmcBlockDevice<Sdmmc<SDMMC1>>, SdClock>,
+    filename: &str,
+    contents: &[u8],
+  ) -> Option<()> {
+    let mut volume = sd.get_volume(VolumeIdx(0)).ok()?;
+    let root_dir = sd.open_root_dir(&volume).ok()?;
+
+    let written = match sd.open_file_in_dir(
+      &mut volume,
+      &root_dir,
+      filename,
+      embedded_sdmmc::Mode::ReadWriteCreateOrTruncate,
+    ) {
+      Ok(mut file) => {
+        let written = sd.write(&mut volume, &mut file, contents);
+        sd.close_file(&volume, file).ok();
+        match written {
+          Ok(len) if len == contents.len() => Some(()),
+          _ => None,
+        }
+      }
+      Err(_) => None,
+    };
+
+    sd.close_dir(&volume, root_dir);
+    return written;
+  }
+
   #[task(priority = 2, local = [event_buffer, delay], shared = [keypad, state])]
   fn keypad_task(ctx: keypad_task::Context) -> () {
     let keypad_task::LocalResources {
@@ -321,7 +537,7 @@ mod app {
 
     if event_buffer.is_none() {
       // set default value
-      *event_buffer = Some([keypad::ButtonEvent { button: None, now }; 8]);
+      *event_buffer = Some([keypad::ButtonEvent { button: None, now }; 10]);
     }
 
     let mut key: Option<keypad::Key> = None;
@@ -408,9 +624,12 @@ mod app {
 
                 if let Some(last_button) = event_buffer[1].button {
                   if (last_button == keypad::Button::Seven || last_button == keypad::Button::Nine)
-                    && event_buffer.check_for_quad()
+                    && event_buffer.check_for_quint()
                   {
-                    // only buttons that can be pressed 4 times are 7 an 9
+                    // only buttons that can be pressed 5 times are 7 an 9
+                    // last 5 are the same
+                    key = last_button.to_key(5);
+                  } else if event_buffer.check_for_quad() {
                     // last 4 are the same
                     key = last_button.to_key(4);
                   } else if event_buffer.check_for_triple() {
@@ -1,6 +1,13 @@
 use crate::keypad::{Key, NavigationKey, NumberKey};
 use display_interface_spi::SPIInterface;
 use heapless::String;
+use libvulcan::{
+  bip39::Mnemonic,
+  bitcoin::{util::bip32, Address, Network},
+  AddressMatch, Bip85Application, Codex32Share, EntropySource, MessageRequest, MultisigConfig,
+  PolicyReport, PsbtFile, ScriptType, Share, SignedMessage, TransactionSummary, VulcanError,
+  DEFAULT_GAP_LIMIT,
+};
 use st7789::ST7789;
 use stm32h7xx_hal::{
   device::SPI1,
@@ -17,6 +24,98 @@ pub type Display = ST7789<
 >;
 pub type BacklightLED = PA1<Output<PushPull>>;
 
+/// What the device's keys are derived from
+#[derive(Debug, PartialEq, Clone)]
+pub enum DeviceSeed {
+  /// a BIP39 mnemonic, keys also depend on the passphrase
+  Mnemonic(Mnemonic),
+  /// a master secret recovered from SLIP-39 shares or a codex32 backup, used
+  /// as the BIP32 seed as is
+  Secret(alloc::vec::Vec<u8>),
+}
+
+/// How a new mnemonic is made
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CreateMethod {
+  /// dice rolls or coin flips for a mnemonic of this many words
+  Entropy(EntropySource, usize),
+  /// words picked by hand, the device lists the valid final words
+  FinalWord(usize),
+  /// Seed XOR parts of this many words typed in, XORed into the mnemonic
+  SeedXor(usize),
+  /// the device's mnemonic split into this many Seed XOR parts
+  SplitSeedXor(usize),
+}
+
+/// Ways to create a mnemonic offered on the create menu
+pub const CREATE_MENU: [(&str, CreateMethod); 11] = [
+  ("12 Words, 50 Dice", CreateMethod::Entropy(EntropySource::Dice, 12)),
+  ("24 Words, 99 Dice", CreateMethod::Entropy(EntropySource::Dice, 24)),
+  ("12 Words, 128 Coins", CreateMethod::Entropy(EntropySource::Coin, 12)),
+  ("24 Words, 256 Coins", CreateMethod::Entropy(EntropySource::Coin, 24)),
+  ("Pick 11 Words", CreateMethod::FinalWord(12)),
+  ("Pick 23 Words", CreateMethod::FinalWord(24)),
+  ("Combine 12 Word XOR", CreateMethod::SeedXor(12)),
+  ("Combine 24 Word XOR", CreateMethod::SeedXor(24)),
+  ("Split Into 2 XOR Parts", CreateMethod::SplitSeedXor(2)),
+  ("Split Into 3 XOR Parts", CreateMethod::SplitSeedXor(3)),
+  ("Split Into 4 XOR Parts", CreateMethod::SplitSeedXor(4)),
+];
+
+/// Networks offered on the network menu
+pub const NETWORK_MENU: [(&str, Network); 4] = [
+  ("Mainnet", Network::Bitcoin),
+  ("Testnet", Network::Testnet),
+  ("Signet", Network::Signet),
+  ("Regtest", Network::Regtest),
+];
+
+/// Device settings on the SD card, one `key=value` per line
+pub const SETTINGS_FILE: &str = "VULCAN.CFG";
+/// Registered multisig wallet on the SD card, reloaded at boot
+pub const REGISTERED_MULTISIG_FILE: &str = "VULCANMS.TXT";
+
+/// BIP85 applications offered on the child seed menu
+pub const CHILD_SEED_MENU: [(&str, Bip85Application); 6] = [
+  ("12 Words", Bip85Application::Bip39(12)),
+  ("18 Words", Bip85Application::Bip39(18)),
+  ("24 Words", Bip85Application::Bip39(24)),
+  ("WIF Key", Bip85Application::Wif),
+  ("XPRV", Bip85Application::Xprv),
+  ("HEX 32 Bytes", Bip85Application::Hex(32)),
+];
+
+/// Shamir backups offered on the shamir menu: the label, how many groups are
+/// needed, and each group's member threshold and share count
+pub const SHAMIR_MENU: [(&str, u8, &[(u8, u8)]); 3] = [
+  ("2 of 3 Shares", 1, &[(2, 3)]),
+  ("3 of 5 Shares", 1, &[(3, 5)]),
+  ("2 of 3 Groups of 2 of 3", 2, &[(2, 3), (2, 3), (2, 3)]),
+];
+/// Bytes of the master secrets Trezor restores SLIP-39 shares of
+pub const TREZOR_SECRET_LENGTHS: [usize; 2] = [16, 32];
+
+/// codex32 backups offered on the codex32 menu: the label, threshold and
+/// share count. A threshold of 0 is the seed as a single string.
+pub const CODEX32_MENU: [(&str, u8, u8); 3] = [
+  ("Export Seed", 0, 1),
+  ("2 of 3 Shares", 2, 3),
+  ("3 of 5 Shares", 3, 5),
+];
+
+/// Share words shown on one page
+pub const SHARE_PAGE_WORDS: usize = 20;
+
+/// Gap limits the verify menu cycles through
+pub const GAP_LIMITS: [u32; 3] = [DEFAULT_GAP_LIMIT, 100, 500];
+
+/// Script types the export menu cycles through
+pub const EXPORT_SCRIPT_TYPES: [(&str, ScriptType); 3] = [
+  ("Native Segwit", ScriptType::Segwit),
+  ("Nested Segwit", ScriptType::NestedSegwit),
+  ("Taproot", ScriptType::Taproot),
+];
+
 #[derive(Debug)]
 pub enum KeypadMode {
   Number,
@@ -24,6 +123,15 @@ pub enum KeypadMode {
   Navigation,
 }
 
+/// What the letter keys type on the passphrase screen, `Key::Shift` moves to
+/// the next one
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TextLayout {
+  Upper,
+  Lower,
+  Symbols,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum KeyType {
   Number(NumberKey),
@@ -37,7 +145,85 @@ pub struct Model {
   pub msg: String<50usize>,
   pub keypad_mode: KeypadMode,
   pub selected_item: usize,
-  pub home_menu: [&'static str; 4],
+  pub home_menu: [&'static str; 11],
+  pub error: Option<VulcanError>,
+  /// the device's mnemonic or recovered secret, every key is derived from it
+  pub seed: Option<DeviceSeed>,
+  /// multisig wallet registered from a setup file
+  pub multisig: Option<MultisigConfig>,
+  /// PSBT waiting for the user to confirm signing it, written back in the
+  /// version it was read as
+  pub psbt: Option<PsbtFile>,
+  /// what the PSBT spends, shown before signing it
+  pub summary: Option<TransactionSummary>,
+  /// warnings to approve and blocks that refuse signing the PSBT
+  pub policy: Option<PolicyReport>,
+  /// the output of the PSBT on screen
+  pub output_index: usize,
+  /// QR payload of the wallet export on screen
+  pub export: Option<alloc::string::String>,
+  /// script type of the exported single-sig account
+  pub export_script_type: ScriptType,
+  /// BIP85 application picked on the child seed menu
+  pub child_app: Option<Bip85Application>,
+  /// child index digits typed on the keypad
+  pub child_index: String<10usize>,
+  /// the derived child seed or key on screen
+  pub child_seed: Option<alloc::string::String>,
+  /// BIP39 passphrase every key is derived with
+  pub passphrase: String<50usize>,
+  /// passphrase being typed on the keypad
+  pub passphrase_entry: String<50usize>,
+  /// what the letter keys type into the passphrase
+  pub text_layout: TextLayout,
+  /// root fingerprint of the typed passphrase, shown before it's applied, of
+  /// the combined Seed XOR parts, or of the mnemonic about to be stored
+  pub fingerprint: Option<bip32::Fingerprint>,
+  /// how the mnemonic being created is made
+  pub create: Option<CreateMethod>,
+  /// dice rolls or coin flips typed so far
+  pub entropy: alloc::string::String,
+  /// mix on-chip RNG entropy into the rolls or flips
+  pub chip_entropy: bool,
+  /// words picked by hand so far
+  pub words: alloc::vec::Vec<&'static str>,
+  /// letters of the word being typed
+  pub word_entry: String<8usize>,
+  /// words that complete the picked words with a valid checksum
+  pub final_words: alloc::vec::Vec<&'static str>,
+  /// Seed XOR parts typed so far, or the parts of the device's mnemonic on
+  /// screen
+  pub xor_parts: alloc::vec::Vec<Mnemonic>,
+  /// the newly created mnemonic on screen
+  pub mnemonic: Option<alloc::string::String>,
+  /// message waiting for the user to confirm signing it
+  pub message: Option<MessageRequest>,
+  /// the last signed message
+  pub signed_message: Option<SignedMessage>,
+  /// addresses searched on each chain when verifying an address
+  pub gap_limit: u32,
+  /// the address being verified
+  pub address: Option<Address>,
+  /// where the address was found, `None` if it isn't ours
+  pub address_match: Option<AddressMatch>,
+  /// shares of the backup on screen, or the shares entered when recovering
+  pub shares: alloc::vec::Vec<Share>,
+  /// the SLIP-39, codex32 or Seed XOR share on screen
+  pub share_index: usize,
+  /// the page of its words on screen
+  pub share_page: usize,
+  /// root fingerprint recovered from the entered shares, and whether it's
+  /// this wallet's
+  pub recovered: Option<(bip32::Fingerprint, bool)>,
+  /// the secret recovered from the entered shares, until it's stored as the
+  /// device's seed or discarded
+  pub recovered_seed: Option<alloc::vec::Vec<u8>>,
+  /// codex32 strings on screen, or the shares entered when recovering
+  pub codex32: alloc::vec::Vec<Codex32Share>,
+  /// characters of the codex32 string being typed
+  pub codex32_entry: alloc::string::String,
+  /// network of every key, address and xpub, saved in the settings file
+  pub network: Network,
 }
 impl Default for Model {
   fn default() -> Self {
@@ -49,19 +235,100 @@ impl Default for Model {
       home_menu: [
         "Create New Wallet",
         "Sign Transaction",
+        "Sign Message",
         "Verify Address",
         "Export Wallet",
+        "Import Multisig",
+        "Child Seeds",
+        "Passphrase",
+        "Shamir Backup",
+        "codex32 Backup",
+        "Network",
       ],
+      error: None,
+      seed: None,
+      multisig: None,
+      psbt: None,
+      summary: None,
+      policy: None,
+      output_index: 0,
+      export: None,
+      export_script_type: ScriptType::Segwit,
+      child_app: None,
+      child_index: String::new(),
+      child_seed: None,
+      passphrase: String::new(),
+      passphrase_entry: String::new(),
+      text_layout: TextLayout::Upper,
+      fingerprint: None,
+      create: None,
+      entropy: alloc::string::String::new(),
+      chip_entropy: true,
+      words: alloc::vec::Vec::new(),
+      word_entry: String::new(),
+      final_words: alloc::vec::Vec::new(),
+      xor_parts: alloc::vec::Vec::new(),
+      mnemonic: None,
+      message: None,
+      signed_message: None,
+      gap_limit: DEFAULT_GAP_LIMIT,
+      address: None,
+      address_match: None,
+      shares: alloc::vec::Vec::new(),
+      share_index: 0,
+      share_page: 0,
+      recovered: None,
+      recovered_seed: None,
+      codex32: alloc::vec::Vec::new(),
+      codex32_entry: alloc::string::String::new(),
+      network: Network::Bitcoin,
+    };
+  }
+}
+
+impl Model {
+  /// Shamir backups the device's seed can be split into. Trezor only
+  /// restores 128 and 256 bit secrets, so a mnemonic's 512 bit seed has none.
+  pub fn shamir_splits(&self) -> &'static [(&'static str, u8, &'static [(u8, u8)])] {
+    return match &self.seed {
+      Some(DeviceSeed::Secret(secret)) if TREZOR_SECRET_LENGTHS.contains(&secret.len()) => {
+        &SHAMIR_MENU
+      }
+      _ => &[],
     };
   }
 }
 
+/// Multisig setup file on the SD card
+pub const MULTISIG_FILE: &str = "MULTISIG.TXT";
+/// Message signing request on the SD card
+pub const MESSAGE_FILE: &str = "MESSAGE.TXT";
+/// Address to verify on the SD card
+pub const ADDRESS_FILE: &str = "ADDRESS.TXT";
+/// Signed PSBT written to the SD card, never loaded for signing
+pub const SIGNED_PSBT_FILE: &str = "SIGNED.PSB";
+/// Signed message written to the SD card
+pub const SIGNED_MESSAGE_FILE: &str = "SIGNED.ASC";
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum ExportScreen {
   Menu,
   SeedQR,
   Specter,
   Sparrow,
+  Electrum,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CreateScreen {
+  Menu,
+  Entropy,
+  Words,
+  FinalWord,
+  SeedXor,
+  SplitSeedXor,
+  Show,
+  Confirm,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -69,22 +336,111 @@ pub enum SignScreen {
   Menu,
   FromQR,
   FromFile,
+  Summary,
+  Output,
+  Confirm,
+  Signed,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum MessageScreen {
+  Menu,
+  FromQR,
+  FromFile,
+  Confirm,
+  Signed,
+  QR,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum VerifyScreen {
+  Menu,
+  FromFile,
+  Result,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ImportScreen {
+  Menu,
+  FromQR,
+  FromFile,
+  Done,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ChildSeedScreen {
+  Menu,
+  Index,
+  Show,
+  QR,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum PassphraseScreen {
+  Entry,
+  Confirm,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ShamirScreen {
+  Menu,
+  Show,
+  Recover,
+  Recovered,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Codex32Screen {
+  Menu,
+  Show,
+  FromQR,
+  Entry,
+  Recovered,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Screen {
   Splash,
   Home,
-  Create,
+  Create(CreateScreen),
   Sign(SignScreen),
-  Verify,
+  SignMessage(MessageScreen),
+  Verify(VerifyScreen),
   ExportWallet(ExportScreen),
+  ImportMultisig(ImportScreen),
+  ChildSeed(ChildSeedScreen),
+  Passphrase(PassphraseScreen),
+  Shamir(ShamirScreen),
+  Codex32(Codex32Screen),
+  Network,
+  Error,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Msg {
   Navigate(Screen),
   KeyUp(KeyType),
+  /// contents of a multisig setup file read from the SD card
+  ConfigLoaded(alloc::string::String),
+  /// contents of a binary or base64 PSBT file of either version read from
+  /// the SD card
+  PsbtLoaded(alloc::vec::Vec<u8>),
+  /// contents of a Coldcard message signing file read from the SD card
+  MessageLoaded(alloc::string::String),
+  /// an address or BIP21 URI to verify, read from the SD card
+  AddressLoaded(alloc::string::String),
+  /// random bytes from the on-chip RNG
+  ChipEntropy([u8; 32]),
+  /// contents of the settings file, empty without an SD card or file
+  SettingsLoaded(alloc::string::String),
+  /// contents of the registered multisig wallet file, empty without an SD
+  /// card or file
+  RegisteredMultisigLoaded(alloc::string::String),
+  /// text of a scanned QR code
+  QRScanned(alloc::string::String),
+  /// the file of a `Cmd::WriteFile` is on the SD card
+  FileWritten(&'static str),
+  Error(VulcanError),
 }
 
 impl defmt::Format for Msg {
@@ -92,6 +448,18 @@ impl defmt::Format for Msg {
     match self {
       Msg::Navigate(screen) => defmt::write!(f, "Msg::Navigate({})", defmt::Debug2Format(&screen)),
       Msg::KeyUp(key) => defmt::write!(f, "Msg::KeyUp({})", defmt::Debug2Format(&key)),
+      Msg::ConfigLoaded(text) => defmt::write!(f, "Msg::ConfigLoaded({})", text.len()),
+      Msg::PsbtLoaded(bytes) => defmt::write!(f, "Msg::PsbtLoaded({})", bytes.len()),
+      Msg::MessageLoaded(text) => defmt::write!(f, "Msg::MessageLoaded({})", text.len()),
+      Msg::AddressLoaded(text) => defmt::write!(f, "Msg::AddressLoaded({})", text.len()),
+      Msg::ChipEntropy(_) => defmt::write!(f, "Msg::ChipEntropy"),
+      Msg::SettingsLoaded(text) => defmt::write!(f, "Msg::SettingsLoaded({})", text.len()),
+      Msg::RegisteredMultisigLoaded(text) => {
+        defmt::write!(f, "Msg::RegisteredMultisigLoaded({})", text.len())
+      }
+      Msg::QRScanned(text) => defmt::write!(f, "Msg::QRScanned({})", text.len()),
+      Msg::FileWritten(name) => defmt::write!(f, "Msg::FileWritten({})", name),
+      Msg::Error(err) => defmt::write!(f, "Msg::Error({})", defmt::Debug2Format(&err)),
     }
   }
 }
@@ -101,6 +469,24 @@ pub enum Cmd {
   None,
   UpdateAfter(u64, Msg),
   InitSD,
+  LoadMultisigConfig,
+  /// read a PSBT file from the SD card
+  LoadPsbt,
+  /// read a message signing file from the SD card
+  LoadMessage,
+  /// read an address file from the SD card
+  LoadAddress,
+  /// create or replace a file in the root directory of the SD card, answered
+  /// with `Msg::FileWritten` or an error
+  WriteFile(&'static str, alloc::string::String),
+  /// read 32 bytes from the on-chip RNG
+  ChipEntropy,
+  /// read the settings file from the SD card
+  LoadSettings,
+  /// read the registered multisig wallet from the SD card
+  LoadRegisteredMultisig,
+  /// scan a QR code with the camera
+  ScanQR,
 }
 
 impl defmt::Format for Cmd {
@@ -108,6 +494,17 @@ impl defmt::Format for Cmd {
     match self {
       Cmd::None => defmt::write!(f, "Cmd::None"),
       Cmd::InitSD => defmt::write!(f, "Cmd::InitSD"),
+      Cmd::LoadMultisigConfig => defmt::write!(f, "Cmd::LoadMultisigConfig"),
+      Cmd::LoadPsbt => defmt::write!(f, "Cmd::LoadPsbt"),
+      Cmd::LoadMessage => defmt::write!(f, "Cmd::LoadMessage"),
+      Cmd::LoadAddress => defmt::write!(f, "Cmd::LoadAddress"),
+      Cmd::ChipEntropy => defmt::write!(f, "Cmd::ChipEntropy"),
+      Cmd::LoadSettings => defmt::write!(f, "Cmd::LoadSettings"),
+      Cmd::LoadRegisteredMultisig => defmt::write!(f, "Cmd::LoadRegisteredMultisig"),
+      Cmd::ScanQR => defmt::write!(f, "Cmd::ScanQR"),
+      Cmd::WriteFile(name, contents) => {
+        defmt::write!(f, "Cmd::WriteFile({}, {})", name, contents.len())
+      }
       Cmd::UpdateAfter(time, msg) => defmt::write!(f, "Cmd::UpdateAfter({}, {})", time, msg),
     }
   }
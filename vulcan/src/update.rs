@@ -1,8 +1,50 @@
 use crate::{
-  keypad::{Key, NavigationKey},
-  types::{Cmd, ExportScreen, KeyType, Model, Msg, Screen, SignScreen},
+  keypad::{Key, NavigationKey, NumberKey},
+  types::{
+    ChildSeedScreen, Cmd, Codex32Screen, CreateMethod, CreateScreen, DeviceSeed, ExportScreen,
+    ImportScreen, KeyType, KeypadMode, MessageScreen, Model, Msg, PassphraseScreen, Screen,
+    ShamirScreen, SignScreen, TextLayout, VerifyScreen, CHILD_SEED_MENU, CODEX32_MENU, CREATE_MENU,
+    EXPORT_SCRIPT_TYPES, GAP_LIMITS, NETWORK_MENU, REGISTERED_MULTISIG_FILE, SETTINGS_FILE,
+    SHARE_PAGE_WORDS, SIGNED_MESSAGE_FILE, SIGNED_PSBT_FILE, TREZOR_SECRET_LENGTHS,
+  },
+};
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+use core::str::FromStr;
+use libvulcan::{
+  address_script_type,
+  bip39::{Language, Mnemonic},
+  bitcoin::{
+    secp256k1::Secp256k1,
+    util::{bip32, psbt::PartiallySignedTransaction},
+    Address, EcdsaSighashType, Network,
+  },
+  check_psbt,
+  codex32::CHARSET as CODEX32_CHARSET,
+  codex32_combine, codex32_identifier, codex32_split, combine_shares, convert_xpub_slip132,
+  electrum_export, final_words, find_address, generate_mnemonic, generic_export,
+  get_passphrase_root, get_path, get_root, get_seed_root, get_wallet, parse_address,
+  parse_message_request, parse_multisig_config,
+  psbt::spent_output,
+  psbt_base64, psbt_bytes, seed_xor_combine, seed_xor_split, sign_message, slip39_words_by_prefix,
+  specter_export, split_master_secret, Codex32Share, MultisigWallet, Policy, PsbtFile, Share,
+  SignatureFormat, TransactionSummary, VulcanError, Wallet, WalletType, MAX_SEED_XOR_PARTS,
+  MIN_SEED_XOR_PARTS,
 };
 
+/// Iteration exponent of new Shamir backups, Trezor's default
+const SHAMIR_ITERATION_EXPONENT: u8 = 1;
+/// Words in the shortest share
+const MIN_SHARE_WORDS: usize = 20;
+/// Words in a share of a 256 bit secret, the longest Trezor restores
+const MAX_SHARE_WORDS: usize = 33;
+/// `ms1` and the data part of the longest codex32 string
+const MAX_CODEX32_LEN: usize = 127;
+
 fn go_up(state: &mut Model) {
   if state.selected_item > 0 {
     state.selected_item = state.selected_item - 1;
@@ -18,6 +60,634 @@ fn go_home(state: &mut Model) {
   state.selected_item = 0;
 }
 
+/// The device's mnemonic, `NoWallet` until one is created and `NoMnemonic`
+/// for a wallet recovered from shares.
+fn load_mnemonic(state: &Model) -> Result<&Mnemonic, VulcanError> {
+  return match &state.seed {
+    Some(DeviceSeed::Mnemonic(mnemonic)) => Ok(mnemonic),
+    Some(DeviceSeed::Secret(_)) => Err(VulcanError::NoMnemonic),
+    None => Err(VulcanError::NoWallet),
+  };
+}
+
+/// The root key of the device's seed and `passphrase`.
+fn load_root(state: &Model, passphrase: &str) -> Result<bip32::ExtendedPrivKey, VulcanError> {
+  return match &state.seed {
+    Some(DeviceSeed::Secret(secret)) => get_seed_root(secret, state.network),
+    _ => get_passphrase_root(load_mnemonic(state)?, passphrase, state.network),
+  };
+}
+
+/// The BIP32 seed of the device's mnemonic and passphrase, or its recovered
+/// secret.
+fn load_seed(state: &Model) -> Result<Vec<u8>, VulcanError> {
+  return match &state.seed {
+    Some(DeviceSeed::Secret(secret)) => Ok(secret.clone()),
+    _ => {
+      let seed = load_mnemonic(state)?.to_seed_normalized(&state.passphrase);
+      Ok(seed.to_vec())
+    }
+  };
+}
+
+/// Root fingerprint of a seed recovered from a backup, and whether it's the
+/// device's.
+fn recovered_root(state: &Model, seed: &[u8]) -> Result<(bip32::Fingerprint, bool), VulcanError> {
+  let secp = Secp256k1::new();
+  let recovered = get_seed_root(seed, state.network)?.fingerprint(&secp);
+  let own = match load_root(state, &state.passphrase) {
+    Ok(root) => root.fingerprint(&secp) == recovered,
+    Err(_) => false,
+  };
+  return Ok((recovered, own));
+}
+
+/// Makes a recovered seed that isn't this wallet's the device's seed. The
+/// passphrase and multisig wallet belong to the previous seed, so they're
+/// dropped. A wallet reloaded at boot is kept for the first seed.
+fn store_recovered(state: &mut Model) {
+  if let (Some(secret), Some((_, false))) = (state.recovered_seed.take(), state.recovered) {
+    if state.seed.is_some() {
+      state.multisig = None;
+    }
+    state.seed = Some(DeviceSeed::Secret(secret));
+    state.passphrase.clear();
+  }
+  state.recovered = None;
+  go_home(state);
+}
+
+/// File a wallet export is written to on the SD card.
+fn export_file(screen: ExportScreen) -> Option<&'static str> {
+  return match screen {
+    ExportScreen::Specter => Some("SPECTER.JSN"),
+    ExportScreen::Sparrow => Some("SPARROW.JSN"),
+    ExportScreen::Electrum => Some("ELECTRUM.JSN"),
+    ExportScreen::Menu | ExportScreen::SeedQR => None,
+  };
+}
+
+/// QR payload and file contents of a wallet export. The generic JSON is too
+/// large for a QR code the screen can show, so Sparrow gets the account
+/// descriptor and Electrum the zpub to scan instead.
+fn build_export(
+  screen: ExportScreen,
+  state: &Model,
+) -> Result<Option<(String, String)>, VulcanError> {
+  let network = state.network;
+  let script_type = state.export_script_type;
+  let root = load_root(state, &state.passphrase)?;
+  let path = get_path(&network, &WalletType::SingleSig, &script_type)?;
+  let wallet = get_wallet(&root, &path, script_type, network)?;
+
+  match screen {
+    ExportScreen::Specter => {
+      let json = specter_export(&wallet, "Vulcan")?;
+      return Ok(Some((json.clone(), json)));
+    }
+    ExportScreen::Sparrow => {
+      let json = generic_export(&root, network)?;
+      return Ok(Some((wallet.descriptor(false)?, json)));
+    }
+    ExportScreen::Electrum => {
+      let xpub = convert_xpub_slip132(
+        &wallet.xpub()?,
+        &network,
+        &WalletType::SingleSig,
+        &script_type,
+      );
+      return Ok(Some((xpub, electrum_export(&wallet)?)));
+    }
+    ExportScreen::Menu | ExportScreen::SeedQR => return Ok(None),
+  }
+}
+
+/// Writes the export's file to the SD card, its QR code is shown once the
+/// file is written.
+fn show_export(state: &mut Model, screen: ExportScreen) -> Cmd {
+  match (build_export(screen, state), export_file(screen)) {
+    (Ok(Some((qr, contents))), Some(filename)) => {
+      state.export = Some(qr);
+      return Cmd::WriteFile(filename, contents);
+    }
+    (Ok(_), _) => return Cmd::None,
+    (Err(err), _) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+      return Cmd::None;
+    }
+  }
+}
+
+/// Turns the typed rolls or flips into a new mnemonic and shows it.
+fn create_mnemonic(state: &mut Model, chip_entropy: Option<&[u8]>) {
+  let result = match state.create {
+    Some(CreateMethod::Entropy(source, _)) => {
+      generate_mnemonic(source, &state.entropy, chip_entropy)
+    }
+    _ => Err(VulcanError::BadEntropy),
+  };
+  state.entropy.clear();
+  match result {
+    Ok(mnemonic) => {
+      state.mnemonic = Some(mnemonic.to_string());
+      state.screen = Screen::Create(CreateScreen::Show);
+    }
+    Err(err) => {
+      state.create = None;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Shows the root fingerprint of the created mnemonic before it becomes the
+/// device's seed.
+fn confirm_mnemonic(state: &mut Model) {
+  let secp = Secp256k1::new();
+  let result = match &state.mnemonic {
+    Some(words) => Mnemonic::parse_in_normalized(Language::English, words)
+      .map_err(VulcanError::from)
+      .and_then(|mnemonic| get_root(&mnemonic, state.network)),
+    None => Err(VulcanError::NoWallet),
+  };
+  match result {
+    Ok(root) => {
+      state.fingerprint = Some(root.fingerprint(&secp));
+      state.screen = Screen::Create(CreateScreen::Confirm);
+    }
+    Err(err) => {
+      state.create = None;
+      state.mnemonic = None;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Makes the created mnemonic the device's seed. The passphrase and multisig
+/// wallet belong to the previous seed, so they're dropped. A wallet reloaded
+/// at boot is kept for the first seed.
+fn store_mnemonic(state: &mut Model) {
+  let result = match &state.mnemonic {
+    Some(words) => Mnemonic::parse_in_normalized(Language::English, words),
+    None => return,
+  };
+  state.create = None;
+  state.mnemonic = None;
+  state.fingerprint = None;
+  match result {
+    Ok(mnemonic) => {
+      if state.seed.is_some() {
+        state.multisig = None;
+      }
+      state.seed = Some(DeviceSeed::Mnemonic(mnemonic));
+      state.passphrase.clear();
+      go_home(state);
+    }
+    Err(err) => {
+      state.error = Some(err.into());
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Completes the typed letters to a wordlist word. Once all but the last word
+/// are picked, lists the words that make the checksum valid.
+fn add_word(state: &mut Model) {
+  let candidates = Language::English.words_by_prefix(&state.word_entry);
+  let word = match candidates
+    .iter()
+    .find(|word| **word == state.word_entry.as_str())
+  {
+    Some(word) => *word,
+    None if candidates.len() == 1 => candidates[0],
+    None => return,
+  };
+  state.words.push(word);
+
+  if let Some(CreateMethod::SeedXor(count)) = state.create {
+    if state.words.len() == count {
+      let phrase = state.words.join(" ");
+      match Mnemonic::parse_in_normalized(Language::English, &phrase) {
+        Ok(part) => {
+          state.xor_parts.push(part);
+          state.words.clear();
+          if state.xor_parts.len() >= MIN_SEED_XOR_PARTS {
+            combine_xor_parts(state);
+          }
+        }
+        // a wrong last word is left typed so it can be fixed
+        Err(_) => {
+          state.words.pop();
+          return;
+        }
+      }
+    }
+  }
+  state.word_entry.clear();
+
+  if let Some(CreateMethod::FinalWord(count)) = state.create {
+    if state.words.len() + 1 == count {
+      state.keypad_mode = KeypadMode::Navigation;
+      match final_words(&state.words) {
+        Ok(words) => {
+          state.final_words = words;
+          state.selected_item = 0;
+          state.screen = Screen::Create(CreateScreen::FinalWord);
+        }
+        Err(err) => {
+          state.create = None;
+          state.words.clear();
+          state.error = Some(err);
+          state.screen = Screen::Error;
+        }
+      }
+    }
+  }
+}
+
+/// XORs the typed parts together and shows the combined root fingerprint, so
+/// the user can check it before adding more parts or using the mnemonic.
+fn combine_xor_parts(state: &mut Model) {
+  let secp = Secp256k1::new();
+  state.keypad_mode = KeypadMode::Navigation;
+  state.selected_item = 0;
+  let result = seed_xor_combine(&state.xor_parts).and_then(|mnemonic| {
+    let root = get_root(&mnemonic, Network::Bitcoin)?;
+    return Ok((mnemonic, root.fingerprint(&secp)));
+  });
+  match result {
+    Ok((mnemonic, fingerprint)) => {
+      state.mnemonic = Some(mnemonic.to_string());
+      state.fingerprint = Some(fingerprint);
+      state.screen = Screen::Create(CreateScreen::SeedXor);
+    }
+    Err(err) => {
+      state.create = None;
+      state.xor_parts.clear();
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Splits the device's mnemonic into the picked number of Seed XOR parts and
+/// shows the first one.
+fn split_seed_xor(state: &mut Model, random: &[u8]) {
+  let result = match state.create {
+    Some(CreateMethod::SplitSeedXor(parts)) => {
+      load_mnemonic(state).and_then(|mnemonic| seed_xor_split(mnemonic, parts, random))
+    }
+    _ => Err(VulcanError::BadSeedXor),
+  };
+  match result {
+    Ok(parts) => {
+      state.xor_parts = parts;
+      state.share_index = 0;
+      state.screen = Screen::Create(CreateScreen::SplitSeedXor);
+    }
+    Err(err) => {
+      state.create = None;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Shows the root fingerprint of the typed passphrase so typos are caught
+/// before it's used.
+fn confirm_passphrase(state: &mut Model) {
+  let secp = Secp256k1::new();
+  state.keypad_mode = KeypadMode::Navigation;
+  let result = load_mnemonic(state)
+    .and_then(|mnemonic| get_passphrase_root(mnemonic, &state.passphrase_entry, state.network));
+  match result {
+    Ok(root) => {
+      state.fingerprint = Some(root.fingerprint(&secp));
+      state.screen = Screen::Passphrase(PassphraseScreen::Confirm);
+    }
+    Err(err) => {
+      state.passphrase_entry.clear();
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Derives the child seed for the typed index and shows it.
+fn show_child_seed(state: &mut Model) {
+  let result = match (state.child_app, state.child_index.parse::<u32>()) {
+    (Some(app), Ok(index)) => {
+      load_root(state, &state.passphrase).and_then(|root| app.derive(&root, index))
+    }
+    _ => Err(VulcanError::BadDerivationPath),
+  };
+  state.keypad_mode = KeypadMode::Navigation;
+  match result {
+    Ok(seed) => {
+      state.child_seed = Some(seed);
+      state.screen = Screen::ChildSeed(ChildSeedScreen::Show);
+    }
+    Err(err) => {
+      state.child_app = None;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Parses a message request, scanned or read from the SD card, and asks to
+/// confirm it.
+fn load_message(state: &mut Model, text: &str) {
+  match parse_message_request(text, state.network) {
+    Ok(request) => {
+      state.message = Some(request);
+      state.screen = Screen::SignMessage(MessageScreen::Confirm);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Signs the confirmed message and writes the armored signature to the SD
+/// card, next to the request file. The signature is shown once it's written.
+fn sign_confirmed_message(state: &mut Model) -> Cmd {
+  let result = match &state.message {
+    Some(request) => {
+      load_root(state, &state.passphrase).and_then(|root| sign_message(&root, request))
+    }
+    None => Err(VulcanError::BadMessage),
+  };
+  state.message = None;
+  match result {
+    Ok(signed) => {
+      let armor = signed.to_armor();
+      state.signed_message = Some(signed);
+      return Cmd::WriteFile(SIGNED_MESSAGE_FILE, armor);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+      return Cmd::None;
+    }
+  }
+}
+
+/// Looks for the address in the single-sig account of its script type, then
+/// in the registered multisig wallet, and shows where it was found.
+fn verify_address(state: &mut Model, text: &str) {
+  let network = state.network;
+  let result = parse_address(text, network).and_then(|address| {
+    let root = load_root(state, &state.passphrase)?;
+    let script_type = address_script_type(&address);
+    let mut found = None;
+    if let Some(script_type) = script_type {
+      let path = get_path(&network, &WalletType::SingleSig, &script_type)?;
+      let wallet = get_wallet(&root, &path, script_type, network)?;
+      found = find_address(&wallet, &address, state.gap_limit)?;
+    }
+    match &state.multisig {
+      Some(config) if found.is_none() && script_type == Some(config.script_type) => {
+        if let Ok(wallet) = config.register(&root, network) {
+          found = find_address(&wallet, &address, state.gap_limit)?;
+        }
+      }
+      _ => {}
+    }
+    return Ok((address, found));
+  });
+  match result {
+    Ok((address, found)) => {
+      state.address = Some(address);
+      state.address_match = found;
+      state.screen = Screen::Verify(VerifyScreen::Result);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Splits the device's recovered secret into the shares of the picked backup
+/// and shows the first one. The menu only offers backups of secrets Trezor
+/// restores, see `Model::shamir_splits`.
+fn split_backup(state: &mut Model, random: &[u8]) {
+  let result = match state.shamir_splits().get(state.selected_item) {
+    Some((_, group_threshold, groups)) => load_seed(state).and_then(|seed| {
+      if !TREZOR_SECRET_LENGTHS.contains(&seed.len()) {
+        return Err(VulcanError::UnsupportedSecretLength(seed.len()));
+      }
+      return split_master_secret(
+        &seed,
+        "",
+        *group_threshold,
+        groups,
+        SHAMIR_ITERATION_EXPONENT,
+        true,
+        random,
+      );
+    }),
+    None => Err(VulcanError::BadShamirGroups),
+  };
+  state.selected_item = 0;
+  match result {
+    Ok(groups) => {
+      state.shares = groups.into_iter().flatten().collect();
+      state.share_index = 0;
+      state.share_page = 0;
+      state.screen = Screen::Shamir(ShamirScreen::Show);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Pages of words of the share on screen.
+fn share_pages(state: &Model) -> usize {
+  return match state.shares.get(state.share_index) {
+    Some(share) => (share.words().len() + SHARE_PAGE_WORDS - 1) / SHARE_PAGE_WORDS,
+    None => 0,
+  };
+}
+
+/// Completes the typed letters to a SLIP-39 word. Once the words make a valid
+/// share it's added to the entered shares, and the master secret is recovered
+/// as soon as there are enough of them. Words that are still no share at the
+/// length of the first share are an error.
+fn add_share_word(state: &mut Model) {
+  let candidates = slip39_words_by_prefix(&state.word_entry);
+  let word = match candidates
+    .iter()
+    .find(|word| **word == state.word_entry.as_str())
+  {
+    Some(word) => *word,
+    None if candidates.len() == 1 => candidates[0],
+    None => return,
+  };
+  state.words.push(word);
+  state.word_entry.clear();
+
+  if state.words.len() < MIN_SHARE_WORDS {
+    return;
+  }
+  let share_words = match state.shares.first() {
+    Some(share) => share.words().len(),
+    None => MAX_SHARE_WORDS,
+  };
+  let share = match Share::from_str(&state.words.join(" ")) {
+    Ok(share) => share,
+    // the share is longer
+    Err(_) if state.words.len() < share_words => return,
+    Err(err) => {
+      state.words.clear();
+      state.shares.clear();
+      state.keypad_mode = KeypadMode::Navigation;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+      return;
+    }
+  };
+  state.words.clear();
+  if state.shares.contains(&share) {
+    return;
+  }
+  state.shares.push(share);
+
+  let result = combine_shares(&state.shares, "").and_then(|secret| {
+    let recovered = recovered_root(state, &secret)?;
+    return Ok((secret, recovered));
+  });
+  match result {
+    Ok((secret, recovered)) => {
+      state.recovered = Some(recovered);
+      state.recovered_seed = Some(secret);
+      state.shares.clear();
+      state.keypad_mode = KeypadMode::Navigation;
+      state.screen = Screen::Shamir(ShamirScreen::Recovered);
+    }
+    Err(VulcanError::InsufficientShares) => {}
+    Err(err) => {
+      state.shares.clear();
+      state.keypad_mode = KeypadMode::Navigation;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Encodes the seed of the device's mnemonic and passphrase as the picked
+/// codex32 backup and shows the first string. Shares need `random`, a seed
+/// that isn't split doesn't.
+fn codex32_backup(state: &mut Model, random: Option<&[u8]>) {
+  let result = match CODEX32_MENU.get(state.selected_item) {
+    Some((_, threshold, count)) => load_seed(state).and_then(|seed| {
+      let secp = Secp256k1::new();
+      let root = get_seed_root(&seed, state.network)?;
+      let identifier = codex32_identifier(&root.fingerprint(&secp));
+      return match random {
+        Some(random) => codex32_split(&seed, &identifier, *threshold, *count, random),
+        None => Codex32Share::from_seed(&seed, &identifier).map(|share| vec![share]),
+      };
+    }),
+    None => Err(VulcanError::BadShamirGroups),
+  };
+  state.selected_item = 0;
+  match result {
+    Ok(strings) => {
+      state.codex32 = strings;
+      state.share_index = 0;
+      state.screen = Screen::Codex32(Codex32Screen::Show);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Adds a typed or scanned codex32 string to the entered shares, and
+/// recovers the seed as soon as there are enough of them.
+fn add_codex32_share(state: &mut Model, text: &str) {
+  let result = Codex32Share::from_str(text).and_then(|share| {
+    if !state.codex32.contains(&share) {
+      state.codex32.push(share);
+    }
+    let seed = codex32_combine(&state.codex32)?;
+    let recovered = recovered_root(state, &seed)?;
+    return Ok((seed, recovered));
+  });
+  state.codex32_entry.clear();
+  match result {
+    Ok((seed, recovered)) => {
+      state.recovered = Some(recovered);
+      state.recovered_seed = Some(seed);
+      state.codex32.clear();
+      state.keypad_mode = KeypadMode::Navigation;
+      state.screen = Screen::Codex32(Codex32Screen::Recovered);
+    }
+    Err(VulcanError::InsufficientShares) => {}
+    Err(err) => {
+      state.codex32.clear();
+      state.keypad_mode = KeypadMode::Navigation;
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Registers a multisig setup file this wallet is part of and saves it to the
+/// SD card, which moves on to the done screen.
+fn import_multisig(state: &mut Model, text: &str) -> Cmd {
+  let result = parse_multisig_config(text).and_then(|config| {
+    let mainnet = state.network == Network::Bitcoin;
+    if config
+      .cosigners
+      .iter()
+      .any(|cosigner| (cosigner.xpub.network == Network::Bitcoin) != mainnet)
+    {
+      return Err(VulcanError::WrongNetwork);
+    }
+    // only a quorum this wallet is part of is stored
+    config.register(&load_root(state, &state.passphrase)?, state.network)?;
+    return Ok(config);
+  });
+  match result {
+    Ok(config) => {
+      let contents = config.to_string();
+      state.multisig = Some(config);
+      return Cmd::WriteFile(REGISTERED_MULTISIG_FILE, contents);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+      return Cmd::None;
+    }
+  }
+}
+
+/// The settings file's contents.
+fn settings(state: &Model) -> String {
+  return format!("network={}\n", state.network);
+}
+
+/// Applies the settings file. Unknown keys and values are skipped, so files
+/// of other firmware versions still load.
+fn load_settings(state: &mut Model, text: &str) {
+  for line in text.lines() {
+    if let Some(value) = line.trim().strip_prefix("network=") {
+      if let Ok(network) = Network::from_str(value) {
+        state.network = network;
+      }
+    }
+  }
+}
+
 fn splash(state: &mut Model, msg: Msg) -> Cmd {
   match msg {
     Msg::Navigate(screen) => {
@@ -25,11 +695,21 @@ fn splash(state: &mut Model, msg: Msg) -> Cmd {
 
       match screen {
         Screen::Splash => {
-          return Cmd::UpdateAfter(2000, Msg::Navigate(Screen::Home));
+          return Cmd::LoadSettings;
         }
         _ => {}
       }
     }
+    Msg::SettingsLoaded(text) => {
+      load_settings(state, &text);
+      return Cmd::LoadRegisteredMultisig;
+    }
+    Msg::RegisteredMultisigLoaded(text) => {
+      // it's matched against the seed when used, a file that doesn't parse
+      // leaves no wallet registered
+      state.multisig = parse_multisig_config(&text).ok();
+      return Cmd::UpdateAfter(2000, Msg::Navigate(Screen::Home));
+    }
     Msg::KeyUp(key_type) => match key_type {
       KeyType::Text(key) => match key {
         Key::Back => {
@@ -51,12 +731,137 @@ fn splash(state: &mut Model, msg: Msg) -> Cmd {
       },
       _ => {}
     },
+    _ => {}
   }
 
   return Cmd::None;
 }
 
+/// The wallet a PSBT is reviewed and signed with
+enum Signer {
+  SingleSig(Wallet),
+  Multisig(MultisigWallet),
+}
+
+/// The registered multisig wallet if the PSBT's first input spends a script,
+/// otherwise the single-sig account of the type of output it spends.
+fn load_signer(state: &Model, psbt: &PartiallySignedTransaction) -> Result<Signer, VulcanError> {
+  let network = state.network;
+  let root = load_root(state, &state.passphrase)?;
+  let input = psbt.inputs.first().ok_or(VulcanError::InconsistentPsbt)?;
+  if input.witness_script.is_some() {
+    let config = state.multisig.as_ref().ok_or(VulcanError::NotInQuorum)?;
+    return Ok(Signer::Multisig(config.register(&root, network)?));
+  }
+
+  let utxo = spent_output(&psbt.unsigned_tx, 0, input)?;
+  let script_type = Address::from_script(&utxo.script_pubkey, network)
+    .as_ref()
+    .and_then(address_script_type)
+    .ok_or(VulcanError::NonStandardScript)?;
+  let path = get_path(&network, &WalletType::SingleSig, &script_type)?;
+  return Ok(Signer::SingleSig(get_wallet(
+    &root,
+    &path,
+    script_type,
+    network,
+  )?));
+}
+
+/// Outputs paged through before signing, change isn't shown one by one.
+fn review_outputs(state: &Model) -> usize {
+  return match &state.summary {
+    Some(summary) => {
+      let outputs = &summary.outputs;
+      outputs.spend.len()
+        + outputs.suspicious.len()
+        + outputs.data.len()
+        + outputs.nonstandard.len()
+    }
+    None => 0,
+  };
+}
+
+/// Decodes a PSBT file, checks it against the signing policy and shows what
+/// it spends.
+fn review_psbt(state: &mut Model, contents: &[u8]) {
+  let result = psbt_bytes(contents)
+    .and_then(|bytes| PsbtFile::deserialize(&bytes))
+    .and_then(|file| {
+      let psbt = file.psbt();
+      let policy = Policy::default();
+      let (summary, report) = match load_signer(state, psbt)? {
+        Signer::SingleSig(wallet) => (
+          TransactionSummary::new(psbt, &wallet)?,
+          check_psbt(psbt, &wallet, &policy),
+        ),
+        Signer::Multisig(wallet) => (
+          TransactionSummary::new(psbt, &wallet)?,
+          check_psbt(psbt, &wallet, &policy),
+        ),
+      };
+      return Ok((file, summary, report));
+    });
+  match result {
+    Ok((file, summary, report)) => {
+      state.psbt = Some(file);
+      state.summary = Some(summary);
+      state.policy = Some(report);
+      state.output_index = 0;
+      state.screen = Screen::Sign(SignScreen::Summary);
+    }
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+    }
+  }
+}
+
+/// Signs the reviewed PSBT and writes it to the SD card as base64 in the
+/// version it was read as, unless the policy blocks it. The signed screen is
+/// shown once the file is written.
+fn sign_reviewed_psbt(state: &mut Model) -> Cmd {
+  if let Some(report) = &state.policy {
+    if report.is_blocked() {
+      return Cmd::None;
+    }
+  }
+
+  let result = match state.psbt.take() {
+    Some(mut file) => load_signer(state, file.psbt()).and_then(|signer| {
+      let signed = match signer {
+        Signer::SingleSig(wallet) => wallet.sign(file.psbt_mut())?,
+        Signer::Multisig(wallet) => wallet.sign(file.psbt_mut())?,
+      };
+      if !signed {
+        return Err(VulcanError::NothingToSign);
+      }
+      file.mark_signed(EcdsaSighashType::All.into());
+      return Ok(psbt_base64(&file.serialize()));
+    }),
+    None => Err(VulcanError::MalformedPsbt),
+  };
+  state.summary = None;
+  state.policy = None;
+  match result {
+    Ok(encoded) => return Cmd::WriteFile(SIGNED_PSBT_FILE, encoded),
+    Err(err) => {
+      state.error = Some(err);
+      state.screen = Screen::Error;
+      return Cmd::None;
+    }
+  }
+}
+
 pub fn update(state: &mut Model, msg: Msg) -> Cmd {
+  if let Msg::Error(err) = msg {
+    // errors can come from any screen
+    state.error = Some(err);
+    state.screen = Screen::Error;
+    state.selected_item = 0;
+    return Cmd::None;
+  }
+
   match &state.screen {
     Screen::Splash => {
       let cmd = splash(state, msg);
@@ -72,17 +877,41 @@ pub fn update(state: &mut Model, msg: Msg) -> Cmd {
           NavigationKey::Forward => {
             match state.selected_item {
               0 => {
-                state.screen = Screen::Create;
+                state.screen = Screen::Create(CreateScreen::Menu);
               }
               1 => {
                 state.screen = Screen::Sign(SignScreen::Menu);
               }
               2 => {
-                state.screen = Screen::Verify;
+                state.screen = Screen::SignMessage(MessageScreen::Menu);
               }
               3 => {
+                state.screen = Screen::Verify(VerifyScreen::Menu);
+              }
+              4 => {
                 state.screen = Screen::ExportWallet(ExportScreen::Menu);
               }
+              5 => {
+                state.screen = Screen::ImportMultisig(ImportScreen::Menu);
+              }
+              6 => {
+                state.screen = Screen::ChildSeed(ChildSeedScreen::Menu);
+              }
+              7 => {
+                state.passphrase_entry = state.passphrase.clone();
+                state.text_layout = TextLayout::Upper;
+                state.keypad_mode = KeypadMode::Text;
+                state.screen = Screen::Passphrase(PassphraseScreen::Entry);
+              }
+              8 => {
+                state.screen = Screen::Shamir(ShamirScreen::Menu);
+              }
+              9 => {
+                state.screen = Screen::Codex32(Codex32Screen::Menu);
+              }
+              10 => {
+                state.screen = Screen::Network;
+              }
               _ => {}
             }
             state.selected_item = 0;
@@ -93,60 +922,131 @@ pub fn update(state: &mut Model, msg: Msg) -> Cmd {
       },
       _ => {}
     },
-    Screen::Create => match msg {
-      Msg::KeyUp(key_type) => match key_type {
-        KeyType::Navigation(key) => match key {
-          NavigationKey::Back => go_home(state),
+    Screen::Create(screen) => match screen {
+      CreateScreen::Menu => match msg {
+        Msg::ChipEntropy(bytes) => split_seed_xor(state, &bytes),
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            // the last item toggles chip entropy
+            NavigationKey::Down => go_down(state, CREATE_MENU.len() + 1),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => match CREATE_MENU.get(state.selected_item) {
+              Some((_, method)) => {
+                state.create = Some(*method);
+                match method {
+                  CreateMethod::Entropy(_, _) => {
+                    state.entropy.clear();
+                    state.keypad_mode = KeypadMode::Number;
+                    state.screen = Screen::Create(CreateScreen::Entropy);
+                  }
+                  CreateMethod::FinalWord(_) | CreateMethod::SeedXor(_) => {
+                    state.words.clear();
+                    state.word_entry.clear();
+                    state.xor_parts.clear();
+                    state.keypad_mode = KeypadMode::Text;
+                    state.screen = Screen::Create(CreateScreen::Words);
+                  }
+                  // the parts are random, whatever the chip entropy setting
+                  CreateMethod::SplitSeedXor(_) => return Cmd::ChipEntropy,
+                }
+              }
+              None => state.chip_entropy = !state.chip_entropy,
+            },
+            _ => {}
+          },
           _ => {}
         },
         _ => {}
       },
-      _ => {}
-    },
-    Screen::Sign(screen) => match screen {
-      SignScreen::Menu => match msg {
+      CreateScreen::Entropy => match msg {
+        Msg::ChipEntropy(bytes) => create_mnemonic(state, Some(&bytes)),
         Msg::KeyUp(key_type) => match key_type {
-          KeyType::Navigation(key) => match key {
-            NavigationKey::Up => go_up(state),
-            NavigationKey::Down => go_down(state, 2),
-            NavigationKey::Back => go_home(state),
-            NavigationKey::Forward => {
-              match state.selected_item {
-                0 => {
-                  state.screen = Screen::Sign(SignScreen::FromQR);
+          KeyType::Number(key) => {
+            if let Some(CreateMethod::Entropy(source, words)) = state.create {
+              let required = source.inputs(words).unwrap_or(0);
+              match key {
+                NumberKey::Back => {
+                  if state.entropy.pop().is_none() {
+                    state.create = None;
+                    state.keypad_mode = KeypadMode::Navigation;
+                    state.screen = Screen::Create(CreateScreen::Menu);
+                  }
                 }
-                1 => {
-                  state.screen = Screen::Sign(SignScreen::FromFile);
-                  return Cmd::InitSD;
+                NumberKey::Forward => {
+                  if state.entropy.len() == required {
+                    state.keypad_mode = KeypadMode::Navigation;
+                    if state.chip_entropy {
+                      return Cmd::ChipEntropy;
+                    }
+                    create_mnemonic(state, None);
+                  }
+                }
+                _ => {
+                  if let Some(c) = key.to_string().chars().next() {
+                    if source.is_valid(c) && state.entropy.len() < required {
+                      state.entropy.push(c);
+                    }
+                  }
                 }
-                _ => {}
               }
             }
-            _ => {}
-          },
+          }
           _ => {}
         },
         _ => {}
       },
-      SignScreen::FromQR => match msg {
+      CreateScreen::Words => match msg {
         Msg::KeyUp(key_type) => match key_type {
-          KeyType::Navigation(key) => match key {
-            NavigationKey::Back => {
-              state.screen = Screen::Sign(SignScreen::Menu);
-              state.selected_item = 0;
+          KeyType::Text(key) => match key {
+            Key::Back => {
+              if state.word_entry.pop().is_none() && state.words.pop().is_none() {
+                state.keypad_mode = KeypadMode::Navigation;
+                if state.xor_parts.len() >= MIN_SEED_XOR_PARTS {
+                  // back to the parts combined so far
+                  state.screen = Screen::Create(CreateScreen::SeedXor);
+                } else {
+                  state.create = None;
+                  state.xor_parts.clear();
+                  state.screen = Screen::Create(CreateScreen::Menu);
+                }
+              }
+            }
+            Key::Forward => add_word(state),
+            _ => {
+              // only letters that still lead to a word
+              let mut entry = state.word_entry.clone();
+              if entry
+                .push_str(&key.to_string().to_ascii_lowercase())
+                .is_ok()
+                && !Language::English.words_by_prefix(&entry).is_empty()
+              {
+                state.word_entry = entry;
+              }
             }
-            _ => {}
           },
           _ => {}
         },
         _ => {}
       },
-      SignScreen::FromFile => match msg {
+      CreateScreen::FinalWord => match msg {
         Msg::KeyUp(key_type) => match key_type {
           KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            NavigationKey::Down => go_down(state, state.final_words.len()),
             NavigationKey::Back => {
-              state.screen = Screen::Sign(SignScreen::Menu);
-              state.selected_item = 0;
+              state.final_words.clear();
+              state.keypad_mode = KeypadMode::Text;
+              state.screen = Screen::Create(CreateScreen::Words);
+            }
+            NavigationKey::Forward => {
+              if let Some(word) = state.final_words.get(state.selected_item) {
+                state.mnemonic = Some(format!("{} {}", state.words.join(" "), word));
+                state.words.clear();
+                state.final_words.clear();
+                state.selected_item = 0;
+                state.screen = Screen::Create(CreateScreen::Show);
+              }
             }
             _ => {}
           },
@@ -154,31 +1054,35 @@ pub fn update(state: &mut Model, msg: Msg) -> Cmd {
         },
         _ => {}
       },
-      _ => {}
-    },
-    Screen::Verify => match msg {
-      Msg::KeyUp(key_type) => match key_type {
-        KeyType::Navigation(key) => match key {
-          NavigationKey::Back => go_home(state),
-          _ => {}
-        },
-        _ => {}
-      },
-      _ => {}
-    },
-    Screen::ExportWallet(screen) => match screen {
-      ExportScreen::Menu => match msg {
+      CreateScreen::SeedXor => match msg {
         Msg::KeyUp(key_type) => match key_type {
           KeyType::Navigation(key) => match key {
             NavigationKey::Up => go_up(state),
-            NavigationKey::Down => go_down(state, 3),
-            NavigationKey::Back => go_home(state),
+            NavigationKey::Down => {
+              // no more parts can be added to the last one
+              let items = if state.xor_parts.len() < MAX_SEED_XOR_PARTS {
+                2
+              } else {
+                1
+              };
+              go_down(state, items);
+            }
+            NavigationKey::Back => {
+              state.create = None;
+              state.xor_parts.clear();
+              state.mnemonic = None;
+              state.fingerprint = None;
+              state.selected_item = 0;
+              state.screen = Screen::Create(CreateScreen::Menu);
+            }
             NavigationKey::Forward => {
-              match state.selected_item {
-                0 => {
-                  state.screen = Screen::ExportWallet(ExportScreen::SeedQR);
-                }
-                _ => {}
+              if state.selected_item == 0 && state.xor_parts.len() < MAX_SEED_XOR_PARTS {
+                state.keypad_mode = KeypadMode::Text;
+                state.screen = Screen::Create(CreateScreen::Words);
+              } else {
+                state.xor_parts.clear();
+                state.fingerprint = None;
+                state.screen = Screen::Create(CreateScreen::Show);
               }
               state.selected_item = 0;
             }
@@ -188,11 +1092,321 @@ pub fn update(state: &mut Model, msg: Msg) -> Cmd {
         },
         _ => {}
       },
-      ExportScreen::SeedQR => match msg {
+      CreateScreen::SplitSeedXor => match msg {
         Msg::KeyUp(key_type) => match key_type {
           KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              if state.share_index + 1 < state.xor_parts.len() {
+                state.share_index += 1;
+              } else {
+                state.create = None;
+                state.xor_parts.clear();
+                go_home(state);
+              }
+            }
             NavigationKey::Back => {
-              state.screen = Screen::ExportWallet(ExportScreen::Menu);
+              if state.share_index > 0 {
+                state.share_index -= 1;
+              } else {
+                state.create = None;
+                state.xor_parts.clear();
+                state.screen = Screen::Create(CreateScreen::Menu);
+              }
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      CreateScreen::Show => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => confirm_mnemonic(state),
+            NavigationKey::Back => {
+              state.create = None;
+              state.mnemonic = None;
+              go_home(state);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      CreateScreen::Confirm => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => store_mnemonic(state),
+            NavigationKey::Back => {
+              state.fingerprint = None;
+              state.screen = Screen::Create(CreateScreen::Show);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::Sign(screen) => match screen {
+      SignScreen::Menu => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            NavigationKey::Down => go_down(state, 2),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => {
+              match state.selected_item {
+                0 => {
+                  state.screen = Screen::Sign(SignScreen::FromQR);
+                }
+                1 => {
+                  state.screen = Screen::Sign(SignScreen::FromFile);
+                  return Cmd::LoadPsbt;
+                }
+                _ => {}
+              }
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      SignScreen::FromQR => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::Sign(SignScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      SignScreen::FromFile => match msg {
+        Msg::PsbtLoaded(bytes) => review_psbt(state, &bytes),
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::Sign(SignScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      SignScreen::Summary => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              state.output_index = 0;
+              if review_outputs(state) > 0 {
+                state.screen = Screen::Sign(SignScreen::Output);
+              } else {
+                state.screen = Screen::Sign(SignScreen::Confirm);
+              }
+            }
+            NavigationKey::Back => {
+              state.psbt = None;
+              state.summary = None;
+              state.policy = None;
+              state.screen = Screen::Sign(SignScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      SignScreen::Output => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              if state.output_index + 1 < review_outputs(state) {
+                state.output_index += 1;
+              } else {
+                state.screen = Screen::Sign(SignScreen::Confirm);
+              }
+            }
+            NavigationKey::Back => {
+              if state.output_index > 0 {
+                state.output_index -= 1;
+              } else {
+                state.screen = Screen::Sign(SignScreen::Summary);
+              }
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      SignScreen::Confirm => match msg {
+        Msg::FileWritten(SIGNED_PSBT_FILE) => {
+          state.screen = Screen::Sign(SignScreen::Signed);
+        }
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => return sign_reviewed_psbt(state),
+            NavigationKey::Back => {
+              state.psbt = None;
+              state.summary = None;
+              state.policy = None;
+              state.screen = Screen::Sign(SignScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      SignScreen::Signed => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back | NavigationKey::Forward => go_home(state),
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::SignMessage(screen) => match screen {
+      MessageScreen::Menu => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            NavigationKey::Down => go_down(state, 2),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => {
+              let (screen, cmd) = match state.selected_item {
+                0 => (MessageScreen::FromQR, Cmd::ScanQR),
+                _ => (MessageScreen::FromFile, Cmd::LoadMessage),
+              };
+              state.screen = Screen::SignMessage(screen);
+              state.selected_item = 0;
+              return cmd;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      MessageScreen::FromQR | MessageScreen::FromFile => match msg {
+        Msg::QRScanned(text) | Msg::MessageLoaded(text) => load_message(state, &text),
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::SignMessage(MessageScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      MessageScreen::Confirm => match msg {
+        Msg::FileWritten(SIGNED_MESSAGE_FILE) => {
+          state.screen = Screen::SignMessage(MessageScreen::Signed);
+        }
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => return sign_confirmed_message(state),
+            NavigationKey::Left | NavigationKey::Right => {
+              if let Some(request) = &mut state.message {
+                let format = match request.format {
+                  SignatureFormat::Bip137 => SignatureFormat::Bip322,
+                  SignatureFormat::Bip322 => SignatureFormat::Bip137,
+                };
+                if request.address_type.supports(format) {
+                  request.format = format;
+                }
+              }
+            }
+            NavigationKey::Back => {
+              state.message = None;
+              state.screen = Screen::SignMessage(MessageScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      MessageScreen::Signed => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              state.screen = Screen::SignMessage(MessageScreen::QR);
+            }
+            NavigationKey::Back => {
+              state.signed_message = None;
+              go_home(state);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      MessageScreen::QR => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::SignMessage(MessageScreen::Signed);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::Verify(screen) => match screen {
+      VerifyScreen::Menu => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            NavigationKey::Down => go_down(state, 2),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => match state.selected_item {
+              0 => {
+                state.screen = Screen::Verify(VerifyScreen::FromFile);
+                state.selected_item = 0;
+                return Cmd::LoadAddress;
+              }
+              // the last item cycles the gap limit
+              _ => {
+                let next = GAP_LIMITS
+                  .iter()
+                  .position(|gap| *gap == state.gap_limit)
+                  .map_or(0, |i| (i + 1) % GAP_LIMITS.len());
+                state.gap_limit = GAP_LIMITS[next];
+              }
+            },
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      VerifyScreen::FromFile => match msg {
+        Msg::AddressLoaded(text) => verify_address(state, &text),
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::Verify(VerifyScreen::Menu);
               state.selected_item = 0;
             }
             _ => {}
@@ -201,6 +1415,526 @@ pub fn update(state: &mut Model, msg: Msg) -> Cmd {
         },
         _ => {}
       },
+      VerifyScreen::Result => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back | NavigationKey::Forward => {
+              state.address = None;
+              state.address_match = None;
+              state.screen = Screen::Verify(VerifyScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::ExportWallet(screen) => match screen {
+      ExportScreen::Menu => match msg {
+        Msg::FileWritten(filename) => {
+          let written = [
+            ExportScreen::Specter,
+            ExportScreen::Sparrow,
+            ExportScreen::Electrum,
+          ]
+          .iter()
+          .copied()
+          .find(|screen| export_file(*screen) == Some(filename));
+          if let Some(screen) = written {
+            state.screen = Screen::ExportWallet(screen);
+            state.selected_item = 0;
+          }
+        }
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            // the last item cycles the script type
+            NavigationKey::Down => go_down(state, 5),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => match state.selected_item {
+              0 => {
+                match load_mnemonic(state) {
+                  Ok(_) => state.screen = Screen::ExportWallet(ExportScreen::SeedQR),
+                  Err(err) => {
+                    state.error = Some(err);
+                    state.screen = Screen::Error;
+                  }
+                }
+                state.selected_item = 0;
+              }
+              // the menu stays until the file is written
+              1 => return show_export(state, ExportScreen::Specter),
+              2 => return show_export(state, ExportScreen::Sparrow),
+              3 => return show_export(state, ExportScreen::Electrum),
+              _ => {
+                let next = EXPORT_SCRIPT_TYPES
+                  .iter()
+                  .position(|(_, script_type)| *script_type == state.export_script_type)
+                  .map_or(0, |i| (i + 1) % EXPORT_SCRIPT_TYPES.len());
+                state.export_script_type = EXPORT_SCRIPT_TYPES[next].1;
+              }
+            },
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ExportScreen::SeedQR
+      | ExportScreen::Specter
+      | ExportScreen::Sparrow
+      | ExportScreen::Electrum => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.export = None;
+              state.screen = Screen::ExportWallet(ExportScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::ImportMultisig(screen) => match screen {
+      ImportScreen::Menu => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            NavigationKey::Down => go_down(state, 2),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => {
+              let (screen, cmd) = match state.selected_item {
+                0 => (ImportScreen::FromQR, Cmd::ScanQR),
+                _ => (ImportScreen::FromFile, Cmd::LoadMultisigConfig),
+              };
+              state.screen = Screen::ImportMultisig(screen);
+              state.selected_item = 0;
+              return cmd;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ImportScreen::FromQR | ImportScreen::FromFile => match msg {
+        Msg::QRScanned(text) | Msg::ConfigLoaded(text) => return import_multisig(state, &text),
+        Msg::FileWritten(REGISTERED_MULTISIG_FILE) => {
+          state.screen = Screen::ImportMultisig(ImportScreen::Done);
+        }
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::ImportMultisig(ImportScreen::Menu);
+              state.selected_item = 0;
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ImportScreen::Done => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back | NavigationKey::Forward => go_home(state),
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::ChildSeed(screen) => match screen {
+      ChildSeedScreen::Menu => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            NavigationKey::Down => go_down(state, CHILD_SEED_MENU.len()),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => {
+              if let Some((_, app)) = CHILD_SEED_MENU.get(state.selected_item) {
+                state.child_app = Some(*app);
+                state.child_index.clear();
+                state.keypad_mode = KeypadMode::Number;
+                state.screen = Screen::ChildSeed(ChildSeedScreen::Index);
+              }
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ChildSeedScreen::Index => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Number(key) => match key {
+            NumberKey::Back => {
+              if state.child_index.pop().is_none() {
+                state.child_app = None;
+                state.keypad_mode = KeypadMode::Navigation;
+                state.screen = Screen::ChildSeed(ChildSeedScreen::Menu);
+              }
+            }
+            NumberKey::Forward => {
+              if state.child_index.is_empty() {
+                state.child_index.push('0').ok();
+              }
+              show_child_seed(state);
+            }
+            _ => {
+              // hardened indexes have at most 10 digits
+              state.child_index.push_str(key.to_string()).ok();
+            }
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ChildSeedScreen::Show => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              state.screen = Screen::ChildSeed(ChildSeedScreen::QR);
+            }
+            NavigationKey::Back => {
+              state.child_app = None;
+              state.child_seed = None;
+              state.screen = Screen::ChildSeed(ChildSeedScreen::Menu);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ChildSeedScreen::QR => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.screen = Screen::ChildSeed(ChildSeedScreen::Show);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::Passphrase(screen) => match screen {
+      PassphraseScreen::Entry => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Text(key) => match key {
+            Key::Back => {
+              if state.passphrase_entry.pop().is_none() {
+                state.keypad_mode = KeypadMode::Navigation;
+                go_home(state);
+              }
+            }
+            Key::Forward => confirm_passphrase(state),
+            Key::Shift => {
+              state.text_layout = match state.text_layout {
+                TextLayout::Upper => TextLayout::Lower,
+                TextLayout::Lower => TextLayout::Symbols,
+                TextLayout::Symbols => TextLayout::Upper,
+              };
+            }
+            _ => {
+              let text = match state.text_layout {
+                TextLayout::Upper => key.to_string().to_string(),
+                TextLayout::Lower => key.to_string().to_ascii_lowercase(),
+                TextLayout::Symbols => key.to_symbol().to_string(),
+              };
+              // a full passphrase takes no more, the screen says so
+              state.passphrase_entry.push_str(&text).ok();
+            }
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      PassphraseScreen::Confirm => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              state.passphrase = state.passphrase_entry.clone();
+              state.passphrase_entry.clear();
+              state.fingerprint = None;
+              go_home(state);
+            }
+            NavigationKey::Back => {
+              state.fingerprint = None;
+              state.keypad_mode = KeypadMode::Text;
+              state.screen = Screen::Passphrase(PassphraseScreen::Entry);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::Shamir(screen) => match screen {
+      ShamirScreen::Menu => match msg {
+        Msg::ChipEntropy(bytes) => split_backup(state, &bytes),
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            // the last item recovers from shares
+            NavigationKey::Down => go_down(state, state.shamir_splits().len() + 1),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => {
+              if state.selected_item < state.shamir_splits().len() {
+                return Cmd::ChipEntropy;
+              }
+              state.shares.clear();
+              state.words.clear();
+              state.word_entry.clear();
+              state.keypad_mode = KeypadMode::Text;
+              state.screen = Screen::Shamir(ShamirScreen::Recover);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ShamirScreen::Show => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              if state.share_page + 1 < share_pages(state) {
+                state.share_page += 1;
+              } else if state.share_index + 1 < state.shares.len() {
+                state.share_index += 1;
+                state.share_page = 0;
+              } else {
+                state.shares.clear();
+                go_home(state);
+              }
+            }
+            NavigationKey::Back => {
+              if state.share_page > 0 {
+                state.share_page -= 1;
+              } else if state.share_index > 0 {
+                state.share_index -= 1;
+                state.share_page = share_pages(state) - 1;
+              } else {
+                state.shares.clear();
+                state.screen = Screen::Shamir(ShamirScreen::Menu);
+              }
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ShamirScreen::Recover => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Text(key) => match key {
+            Key::Back => {
+              if state.word_entry.pop().is_none() && state.words.pop().is_none() {
+                state.shares.clear();
+                state.keypad_mode = KeypadMode::Navigation;
+                state.screen = Screen::Shamir(ShamirScreen::Menu);
+                state.selected_item = 0;
+              }
+            }
+            Key::Forward => add_share_word(state),
+            _ => {
+              // only letters that still lead to a word
+              let mut entry = state.word_entry.clone();
+              if entry
+                .push_str(&key.to_string().to_ascii_lowercase())
+                .is_ok()
+                && !slip39_words_by_prefix(&entry).is_empty()
+              {
+                state.word_entry = entry;
+              }
+            }
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      ShamirScreen::Recovered => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.recovered = None;
+              state.recovered_seed = None;
+              go_home(state);
+            }
+            NavigationKey::Forward => store_recovered(state),
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::Codex32(screen) => match screen {
+      Codex32Screen::Menu => match msg {
+        Msg::ChipEntropy(bytes) => codex32_backup(state, Some(&bytes)),
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Up => go_up(state),
+            // the last two items scan or type in shares
+            NavigationKey::Down => go_down(state, CODEX32_MENU.len() + 2),
+            NavigationKey::Back => go_home(state),
+            NavigationKey::Forward => match CODEX32_MENU.get(state.selected_item) {
+              Some((_, 0, _)) => codex32_backup(state, None),
+              Some(_) => return Cmd::ChipEntropy,
+              None => {
+                state.codex32.clear();
+                state.codex32_entry.clear();
+                let scan = state.selected_item == CODEX32_MENU.len();
+                state.selected_item = 0;
+                if scan {
+                  state.screen = Screen::Codex32(Codex32Screen::FromQR);
+                  return Cmd::ScanQR;
+                }
+                state.keypad_mode = KeypadMode::Text;
+                state.screen = Screen::Codex32(Codex32Screen::Entry);
+              }
+            },
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      Codex32Screen::Show => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Forward => {
+              if state.share_index + 1 < state.codex32.len() {
+                state.share_index += 1;
+              } else {
+                state.codex32.clear();
+                go_home(state);
+              }
+            }
+            NavigationKey::Back => {
+              if state.share_index > 0 {
+                state.share_index -= 1;
+              } else {
+                state.codex32.clear();
+                state.screen = Screen::Codex32(Codex32Screen::Menu);
+              }
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      Codex32Screen::FromQR => match msg {
+        Msg::QRScanned(text) => {
+          add_codex32_share(state, &text);
+          // scan the next share until there are enough
+          if state.screen == Screen::Codex32(Codex32Screen::FromQR) {
+            return Cmd::ScanQR;
+          }
+        }
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.codex32.clear();
+              state.screen = Screen::Codex32(Codex32Screen::Menu);
+            }
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      Codex32Screen::Entry => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Text(key) => match key {
+            Key::Back => {
+              if state.codex32_entry.pop().is_none() {
+                state.codex32.clear();
+                state.keypad_mode = KeypadMode::Navigation;
+                state.screen = Screen::Codex32(Codex32Screen::Menu);
+              }
+            }
+            Key::Forward => {
+              // the entry only holds the data part, `ms1` is implied
+              let text = format!("ms1{}", state.codex32_entry);
+              if Codex32Share::from_str(&text).is_ok() {
+                add_codex32_share(state, &text);
+              }
+            }
+            _ => {
+              // only characters of the bech32 charset
+              let c = key.to_string().to_ascii_lowercase();
+              if c.len() == 1
+                && CODEX32_CHARSET.contains(&c.as_bytes()[0])
+                && state.codex32_entry.len() + 3 < MAX_CODEX32_LEN
+              {
+                state.codex32_entry.push_str(&c);
+              }
+            }
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+      Codex32Screen::Recovered => match msg {
+        Msg::KeyUp(key_type) => match key_type {
+          KeyType::Navigation(key) => match key {
+            NavigationKey::Back => {
+              state.recovered = None;
+              state.recovered_seed = None;
+              go_home(state);
+            }
+            NavigationKey::Forward => store_recovered(state),
+            _ => {}
+          },
+          _ => {}
+        },
+        _ => {}
+      },
+    },
+    Screen::Network => match msg {
+      Msg::FileWritten(SETTINGS_FILE) => go_home(state),
+      Msg::KeyUp(key_type) => match key_type {
+        KeyType::Navigation(key) => match key {
+          NavigationKey::Up => go_up(state),
+          NavigationKey::Down => go_down(state, NETWORK_MENU.len()),
+          NavigationKey::Back => go_home(state),
+          NavigationKey::Forward => {
+            // home once the setting is saved, the error screen says if it
+            // isn't
+            if let Some((_, network)) = NETWORK_MENU.get(state.selected_item) {
+              state.network = *network;
+              return Cmd::WriteFile(SETTINGS_FILE, settings(state));
+            }
+          }
+          _ => {}
+        },
+        _ => {}
+      },
+      _ => {}
+    },
+    Screen::Error => match msg {
+      Msg::KeyUp(key_type) => match key_type {
+        KeyType::Navigation(key) => match key {
+          NavigationKey::Back | NavigationKey::Forward => {
+            state.error = None;
+            go_home(state);
+          }
+          _ => {}
+        },
+        _ => {}
+      },
       _ => {}
     },
   }
@@ -0,0 +1,88 @@
+use super::util::{
+  draw_menu, draw_nav, draw_text_qr, draw_words, draw_wrapped, get_fonts, ViewError, ViewResult,
+  MARGIN_X, MARGIN_Y,
+};
+use crate::types::{ChildSeedScreen, Model, CHILD_SEED_MENU};
+use alloc::{format, vec::Vec};
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+use libvulcan::{
+  bip39::{Language, Mnemonic},
+  encode_seedqr, Bip85Application,
+};
+
+pub fn child_seed(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  screen: &ChildSeedScreen,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let fonts = get_fonts();
+
+  match screen {
+    ChildSeedScreen::Menu => {
+      let top_left =
+        draw_nav(display, "Child Seeds")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      let items: Vec<&str> = CHILD_SEED_MENU.iter().map(|(label, _)| *label).collect();
+      draw_menu(display, &state, &top_left, &items)?;
+    }
+    ChildSeedScreen::Index => {
+      draw_nav(display, "Child Index")?;
+
+      Text::with_alignment(
+        &format!("Index: {}_", state.child_index),
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      Text::with_alignment(
+        "# to derive, * to delete",
+        display.bounding_box().center() + Point::new(0, 60),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    ChildSeedScreen::Show => {
+      draw_nav(display, &format!("Child #{}", state.child_index))?;
+
+      if let (Some(app), Some(seed)) = (&state.child_app, &state.child_seed) {
+        match app {
+          Bip85Application::Bip39(_) => draw_words(display, seed)?,
+          _ => {
+            draw_wrapped(display, seed, 50)?;
+          }
+        }
+      }
+
+      Text::with_alignment(
+        "# for QR code",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    ChildSeedScreen::QR => {
+      if let (Some(app), Some(seed)) = (&state.child_app, &state.child_seed) {
+        match app {
+          // wallets scan child mnemonics as SeedQR
+          Bip85Application::Bip39(_) => {
+            if let Ok(mnemonic) = Mnemonic::parse_in_normalized(Language::English, seed) {
+              draw_text_qr(display, &encode_seedqr(&mnemonic))?;
+            }
+          }
+          _ => draw_text_qr(display, seed)?,
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,126 @@
+use super::util::{
+  draw_menu, draw_nav, draw_recovered, get_fonts, ViewError, ViewResult, MARGIN_X, MARGIN_Y,
+};
+use crate::types::{Codex32Screen, Model, CODEX32_MENU};
+use alloc::{format, string::String, vec::Vec};
+use core::str::FromStr;
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+use libvulcan::Codex32Share;
+
+/// Characters in a group, codex32 strings are copied by hand four at a time
+const GROUP_LEN: usize = 4;
+/// Groups on a line of the large font
+const LINE_GROUPS: usize = 4;
+
+/// Draws a codex32 string in upper case groups below the nav bar.
+fn draw_groups(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  text: &str,
+) -> ViewResult {
+  let fonts = get_fonts();
+  let text = text.to_uppercase();
+  let groups: Vec<&str> = text
+    .as_bytes()
+    .chunks(GROUP_LEN)
+    .map(|group| core::str::from_utf8(group).unwrap_or(""))
+    .collect();
+  for (i, line) in groups.chunks(LINE_GROUPS).enumerate() {
+    Text::with_alignment(
+      &line.join(" "),
+      Point::new(display.bounding_box().center().x, 55 + i as i32 * 22),
+      fonts.black,
+      Alignment::Center,
+    )
+    .draw(display)?;
+  }
+
+  Ok(())
+}
+
+pub fn codex32_backup(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  screen: &Codex32Screen,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let fonts = get_fonts();
+
+  match screen {
+    Codex32Screen::Menu => {
+      let top_left =
+        draw_nav(display, "codex32 Backup")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      let mut items: Vec<&str> = CODEX32_MENU.iter().map(|(label, _, _)| *label).collect();
+      items.push("Scan Shares");
+      items.push("Enter Shares");
+      draw_menu(display, &state, &top_left, &items)?;
+    }
+    Codex32Screen::Show => {
+      if let Some(share) = state.codex32.get(state.share_index) {
+        if share.threshold == 0 {
+          draw_nav(display, "Seed")?;
+        } else {
+          draw_nav(
+            display,
+            &format!(
+              "Share {} of {}, {} needed",
+              state.share_index + 1,
+              state.codex32.len(),
+              share.threshold
+            ),
+          )?;
+        }
+        draw_groups(display, &share.to_string())?;
+
+        Text::with_alignment(
+          "# next, * back",
+          display.bounding_box().center() + Point::new(0, 110),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+    }
+    Codex32Screen::FromQR => {
+      draw_nav(display, &format!("Scan Share {}", state.codex32.len() + 1))?;
+
+      Text::with_alignment(
+        "Scanning codex32 QR code",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    Codex32Screen::Entry => {
+      draw_nav(display, &format!("Share {}", state.codex32.len() + 1))?;
+
+      let text = format!("ms1{}", state.codex32_entry);
+      draw_groups(display, &format!("{}_", text))?;
+
+      let status = if Codex32Share::from_str(&text).is_ok() {
+        String::from("Checksum OK, # to add")
+      } else {
+        format!("{} characters, * deletes", text.len())
+      };
+      Text::with_alignment(
+        &status,
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    Codex32Screen::Recovered => {
+      draw_nav(display, "Seed Recovered")?;
+      draw_recovered(display, state)?;
+    }
+  }
+
+  Ok(())
+}
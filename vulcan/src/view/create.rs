@@ -1,26 +1,223 @@
-use super::util::{get_fonts, ViewError, ViewResult};
-use crate::types::Model;
+use super::util::{
+  draw_menu, draw_nav, draw_words, get_fonts, ViewError, ViewResult, MARGIN_X, MARGIN_Y,
+};
+use crate::types::{CreateMethod, CreateScreen, Model, CREATE_MENU};
+use alloc::{format, vec::Vec};
 use embedded_graphics::{
   pixelcolor::Rgb565,
   prelude::*,
   text::{Alignment, Text},
 };
+use libvulcan::{bip39::Language, EntropySource, MAX_SEED_XOR_PARTS};
+
+/// Rolls or flips shown on the entry screen
+const TAIL_LEN: usize = 20;
+/// Matching words suggested while typing
+const SUGGESTIONS: usize = 4;
 
 pub fn create_wallet(
   display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
   state: &Model,
+  screen: &CreateScreen,
 ) -> ViewResult {
   display.clear(Rgb565::WHITE)?;
 
   let fonts = get_fonts();
 
-  Text::with_alignment(
-    "create wallet",
-    display.bounding_box().center() + Point::new(0, 5),
-    fonts.black,
-    Alignment::Center,
-  )
-  .draw(display)?;
+  match screen {
+    CreateScreen::Menu => {
+      let top_left =
+        draw_nav(display, "Create New Wallet")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      let mut items: Vec<&str> = CREATE_MENU.iter().map(|(label, _)| *label).collect();
+      items.push(if state.chip_entropy {
+        "Chip Entropy: On"
+      } else {
+        "Chip Entropy: Off"
+      });
+      draw_menu(display, &state, &top_left, &items)?;
+    }
+    CreateScreen::Entropy => {
+      if let Some(CreateMethod::Entropy(source, words)) = state.create {
+        let (title, hint) = match source {
+          EntropySource::Dice => ("Dice Rolls", "enter rolls 1-6"),
+          EntropySource::Coin => ("Coin Flips", "heads 1, tails 0"),
+        };
+        draw_nav(display, title)?;
+
+        let count = state.entropy.len();
+        let required = source.inputs(words).unwrap_or(0);
+        Text::with_alignment(
+          &format!("{} / {}", count, required),
+          display.bounding_box().center() + Point::new(0, -45),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+        Text::with_alignment(
+          &format!("{} bits of entropy", source.bits(count)),
+          display.bounding_box().center() + Point::new(0, -15),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+        Text::with_alignment(
+          &state.entropy[count.saturating_sub(TAIL_LEN)..],
+          display.bounding_box().center() + Point::new(0, 15),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+        Text::with_alignment(
+          if count == required {
+            "# to create"
+          } else {
+            hint
+          },
+          display.bounding_box().center() + Point::new(0, 60),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+    }
+    CreateScreen::Words => {
+      let title = match state.create {
+        Some(CreateMethod::FinalWord(count)) => {
+          Some(format!("Word {} of {}", state.words.len() + 1, count - 1))
+        }
+        Some(CreateMethod::SeedXor(count)) => Some(format!(
+          "Part {} Word {} of {}",
+          state.xor_parts.len() + 1,
+          state.words.len() + 1,
+          count
+        )),
+        _ => None,
+      };
+      if let Some(title) = title {
+        draw_nav(display, &title)?;
+
+        Text::with_alignment(
+          &format!("{}_", state.word_entry),
+          display.bounding_box().center() + Point::new(0, -45),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+        if !state.word_entry.is_empty() {
+          let suggestions = Language::English.words_by_prefix(&state.word_entry);
+          for (i, word) in suggestions.iter().take(SUGGESTIONS).enumerate() {
+            Text::with_alignment(
+              word,
+              display.bounding_box().center() + Point::new(0, -15 + i as i32 * 20),
+              fonts.black,
+              Alignment::Center,
+            )
+            .draw(display)?;
+          }
+        }
+        Text::with_alignment(
+          "# to add, * to delete",
+          display.bounding_box().center() + Point::new(0, 80),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+    }
+    CreateScreen::FinalWord => {
+      let top_left =
+        draw_nav(display, "Pick Final Word")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      draw_menu(display, &state, &top_left, &state.final_words)?;
+    }
+    CreateScreen::SeedXor => {
+      let top_left = draw_nav(
+        display,
+        &format!("{} Parts Combined", state.xor_parts.len()),
+      )? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      if let Some(fingerprint) = &state.fingerprint {
+        Text::with_alignment(
+          &format!("Fingerprint {}", fingerprint),
+          Point::new(display.bounding_box().center().x, top_left.y + 10),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+      let items: &[&str] = if state.xor_parts.len() < MAX_SEED_XOR_PARTS {
+        &["Add Another Part", "Show Combined Words"]
+      } else {
+        &["Show Combined Words"]
+      };
+      draw_menu(display, &state, &(top_left + Point::new(0, 30)), items)?;
+    }
+    CreateScreen::SplitSeedXor => {
+      if let Some(part) = state.xor_parts.get(state.share_index) {
+        draw_nav(
+          display,
+          &format!(
+            "Part {} of {}",
+            state.share_index + 1,
+            state.xor_parts.len()
+          ),
+        )?;
+        draw_words(display, &part.to_string())?;
+
+        Text::with_alignment(
+          "# next, * back",
+          display.bounding_box().center() + Point::new(0, 110),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+    }
+    CreateScreen::Show => {
+      draw_nav(display, "Write Down Words")?;
+
+      if let Some(mnemonic) = &state.mnemonic {
+        draw_words(display, mnemonic)?;
+      }
+      Text::with_alignment(
+        "# to use, * to discard",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    CreateScreen::Confirm => {
+      draw_nav(display, "Use As Wallet")?;
+
+      if let Some(fingerprint) = &state.fingerprint {
+        Text::with_alignment(
+          &format!("Fingerprint {}", fingerprint),
+          display.bounding_box().center() + Point::new(0, -25),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+      if state.seed.is_some() {
+        Text::with_alignment(
+          "Replaces the loaded wallet",
+          display.bounding_box().center() + Point::new(0, 5),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+      Text::with_alignment(
+        "# to store, * to go back",
+        display.bounding_box().center() + Point::new(0, 60),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+  }
 
   Ok(())
 }
@@ -0,0 +1,41 @@
+use super::util::{draw_nav, get_fonts, ViewError, ViewResult};
+use crate::types::Model;
+use alloc::{format, string::String};
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+
+pub fn error(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  draw_nav(display, "Error")?;
+
+  let fonts = get_fonts();
+
+  let msg = match &state.error {
+    Some(err) => format!("{}", err),
+    None => String::from("unknown error"),
+  };
+  Text::with_alignment(
+    &msg,
+    display.bounding_box().center() + Point::new(0, 5),
+    fonts.black,
+    Alignment::Center,
+  )
+  .draw(display)?;
+
+  Text::with_alignment(
+    "# to continue",
+    display.bounding_box().center() + Point::new(0, 60),
+    fonts.black,
+    Alignment::Center,
+  )
+  .draw(display)?;
+
+  Ok(())
+}
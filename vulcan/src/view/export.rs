@@ -1,12 +1,13 @@
-use super::util::{draw_button, draw_nav, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
+use super::util::{draw_button, draw_nav, draw_text_qr, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
 use crate::{
-  types::{ExportScreen, Model},
+  types::{DeviceSeed, ExportScreen, Model, EXPORT_SCRIPT_TYPES},
   view::util::ViewColor,
 };
-use alloc::vec;
+use alloc::{format, vec};
 use core::mem::size_of;
 use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
 use embedded_qr::{QrCode, QrCodeEcc, QrDrawable, Version};
+use libvulcan::encode_seedqr;
 
 fn draw_qr(
   target: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
@@ -84,18 +85,33 @@ pub fn export_wallet(
 
       top_left = draw_button(display, &state, &top_left, 0, "SeedQR")?;
       top_left = draw_button(display, &state, &top_left, 1, "Specter")?;
-      draw_button(display, &state, &top_left, 2, "Sparrow")?;
+      top_left = draw_button(display, &state, &top_left, 2, "Sparrow")?;
+      top_left = draw_button(display, &state, &top_left, 3, "Electrum")?;
+
+      let script_type = EXPORT_SCRIPT_TYPES
+        .iter()
+        .find(|(_, script_type)| *script_type == state.export_script_type)
+        .map_or("", |(label, _)| *label);
+      draw_button(
+        display,
+        &state,
+        &top_left,
+        4,
+        &format!("Script: {}", script_type),
+      )?;
     }
     ExportScreen::SeedQR => {
-      let seedqr = "136400980811079503490561095703230934105802751813017212440282184807481683015201310078178605500063";
-
-      defmt::info!("{}", seedqr);
+      let mnemonic = match &state.seed {
+        Some(DeviceSeed::Mnemonic(mnemonic)) => mnemonic,
+        _ => return Ok(()),
+      };
+      let seedqr = encode_seedqr(mnemonic);
 
       let version = Version::new(3);
       let mut outbuffer = vec![0u8; version.buffer_len()];
       let mut tempbuffer = vec![0u8; version.buffer_len()];
       let qr: QrCode = QrCode::encode_text(
-        seedqr,
+        &seedqr,
         &mut tempbuffer,
         &mut outbuffer,
         QrCodeEcc::Low,
@@ -116,7 +132,11 @@ pub fn export_wallet(
 
       core::mem::drop(outbuffer);
     }
-    _ => {}
+    ExportScreen::Specter | ExportScreen::Sparrow | ExportScreen::Electrum => {
+      if let Some(export) = &state.export {
+        draw_text_qr(display, export)?;
+      }
+    }
   }
 
   Ok(())
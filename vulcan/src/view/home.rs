@@ -1,4 +1,4 @@
-use super::util::{draw_button, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
+use super::util::{draw_menu, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
 use crate::types::Model;
 use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
 
@@ -8,10 +8,8 @@ pub fn home(
 ) -> ViewResult {
   display.clear(Rgb565::WHITE)?;
 
-  let mut top_left = Point::new(MARGIN_X as i32, MARGIN_Y as i32);
-  for (i, msg) in state.home_menu.iter().enumerate() {
-    top_left = draw_button(display, &state, &top_left, i, msg)?;
-  }
+  let top_left = Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+  draw_menu(display, &state, &top_left, &state.home_menu)?;
 
   Ok(())
 }
@@ -0,0 +1,93 @@
+use super::util::{draw_button, draw_nav, get_fonts, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
+use crate::types::{ImportScreen, Model};
+use alloc::format;
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+use libvulcan::ScriptType;
+
+pub fn import_multisig(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  screen: &ImportScreen,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let fonts = get_fonts();
+
+  match screen {
+    ImportScreen::Menu => {
+      let mut top_left =
+        draw_nav(display, "Import Multisig")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      top_left = draw_button(display, &state, &top_left, 0, "Scan QR Code")?;
+      draw_button(display, &state, &top_left, 1, "Load From SD Card")?;
+    }
+    ImportScreen::FromQR => {
+      draw_nav(display, "Scan QR Code")?;
+
+      Text::with_alignment(
+        "Scanning setup QR code",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    ImportScreen::FromFile => {
+      draw_nav(display, "Load From SD Card")?;
+
+      Text::with_alignment(
+        "Reading MULTISIG.TXT",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    ImportScreen::Done => {
+      draw_nav(display, "Multisig Imported")?;
+
+      if let Some(config) = &state.multisig {
+        let format = match config.script_type {
+          ScriptType::NestedSegwit => "P2SH-P2WSH",
+          ScriptType::Segwit => "P2WSH",
+          ScriptType::Taproot => "P2TR",
+        };
+        let policy = format!(
+          "{} of {} {}",
+          config.threshold,
+          config.cosigners.len(),
+          format
+        );
+
+        Text::with_alignment(
+          &config.name,
+          display.bounding_box().center() + Point::new(0, -25),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+        Text::with_alignment(
+          &policy,
+          display.bounding_box().center() + Point::new(0, 5),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+
+      Text::with_alignment(
+        "# to continue",
+        display.bounding_box().center() + Point::new(0, 60),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+  }
+
+  Ok(())
+}
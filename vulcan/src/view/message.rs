@@ -0,0 +1,99 @@
+use super::util::{
+  draw_button, draw_nav, draw_text_qr, draw_wrapped, get_fonts, ViewError, ViewResult, MARGIN_X,
+  MARGIN_Y,
+};
+use crate::types::{MessageScreen, Model};
+use alloc::{format, string::ToString};
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+use libvulcan::MessageAddressType;
+
+pub fn sign_message(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  screen: &MessageScreen,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let fonts = get_fonts();
+
+  match screen {
+    MessageScreen::Menu => {
+      let mut top_left =
+        draw_nav(display, "Sign Message")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      top_left = draw_button(display, &state, &top_left, 0, "Scan QR Code")?;
+      draw_button(display, &state, &top_left, 1, "Load From SD Card")?;
+    }
+    MessageScreen::FromQR => {
+      draw_nav(display, "Scan QR Code")?;
+
+      Text::with_alignment(
+        "Scanning message QR code",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    MessageScreen::FromFile => {
+      draw_nav(display, "Load From SD Card")?;
+
+      Text::with_alignment(
+        "Reading MESSAGE.TXT",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    MessageScreen::Confirm => {
+      draw_nav(display, "Sign Message?")?;
+
+      if let Some(request) = &state.message {
+        let mut y = draw_wrapped(display, &request.message, 50)?;
+        y = draw_wrapped(display, &request.path.to_string(), y + 13)?;
+        y = draw_wrapped(display, request.address_type.name(), y)?;
+        let format = match request.address_type {
+          MessageAddressType::P2wpkh => format!("{}, < > to change", request.format.name()),
+          _ => request.format.name().to_string(),
+        };
+        draw_wrapped(display, &format, y)?;
+      }
+
+      Text::with_alignment(
+        "# to sign, * to cancel",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    MessageScreen::Signed => {
+      draw_nav(display, "Message Signed")?;
+
+      if let Some(signed) = &state.signed_message {
+        let y = draw_wrapped(display, &signed.address.to_string(), 50)?;
+        draw_wrapped(display, &signed.signature, y + 13)?;
+      }
+
+      Text::with_alignment(
+        "# for QR code",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    MessageScreen::QR => {
+      if let Some(signed) = &state.signed_message {
+        draw_text_qr(display, &signed.signature)?;
+      }
+    }
+  }
+
+  Ok(())
+}
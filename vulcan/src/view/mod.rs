@@ -1,18 +1,34 @@
 use self::{
+  child::child_seed,
+  codex32::codex32_backup,
   create::create_wallet,
+  error::error,
   export::export_wallet,
   home::home,
+  import::import_multisig,
+  message::sign_message,
+  network::network,
+  passphrase::passphrase,
+  shamir::shamir_backup,
   sign::sign_transaction,
   splash::splash,
-  util::{ViewColor, ViewError, ViewResult},
+  util::{draw_network, ViewColor, ViewError, ViewResult},
   verify::verify_address,
 };
 use crate::types::{Model, Screen};
 use embedded_graphics::draw_target::DrawTarget;
 
+mod child;
+mod codex32;
 mod create;
+mod error;
 mod export;
 mod home;
+mod import;
+mod message;
+mod network;
+mod passphrase;
+mod shamir;
 mod sign;
 mod splash;
 pub mod util;
@@ -22,12 +38,28 @@ pub fn view(
   display: &mut impl DrawTarget<Color = ViewColor, Error = ViewError>,
   state: &Model,
 ) -> ViewResult {
-  return match &state.screen {
+  match &state.screen {
     Screen::Splash => splash(display, state),
     Screen::Home => home(display, state),
-    Screen::Create => create_wallet(display, state),
-    Screen::Verify => verify_address(display, state),
+    Screen::Create(screen) => create_wallet(display, state, screen),
+    Screen::Verify(screen) => verify_address(display, state, screen),
     Screen::ExportWallet(screen) => export_wallet(display, state, screen),
     Screen::Sign(screen) => sign_transaction(display, state, screen),
-  };
+    Screen::SignMessage(screen) => sign_message(display, state, screen),
+    Screen::ImportMultisig(screen) => import_multisig(display, state, screen),
+    Screen::ChildSeed(screen) => child_seed(display, state, screen),
+    Screen::Passphrase(screen) => passphrase(display, state, screen),
+    Screen::Shamir(screen) => shamir_backup(display, state, screen),
+    Screen::Codex32(screen) => codex32_backup(display, state, screen),
+    Screen::Network => network(display, state),
+    Screen::Error => error(display, state),
+  }?;
+
+  // every other screen has a nav bar
+  match &state.screen {
+    Screen::Splash | Screen::Home => {}
+    _ => draw_network(display, state.network)?,
+  }
+
+  Ok(())
 }
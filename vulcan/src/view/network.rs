@@ -0,0 +1,28 @@
+use super::util::{draw_menu, draw_nav, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
+use crate::types::{Model, NETWORK_MENU};
+use alloc::{format, string::String, vec::Vec};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+pub fn network(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let top_left = draw_nav(display, "Network")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+  let labels: Vec<String> = NETWORK_MENU
+    .iter()
+    .map(|(label, network)| {
+      if *network == state.network {
+        format!("{} (in use)", label)
+      } else {
+        String::from(*label)
+      }
+    })
+    .collect();
+  let items: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+  draw_menu(display, &state, &top_left, &items)?;
+
+  Ok(())
+}
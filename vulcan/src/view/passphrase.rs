@@ -0,0 +1,126 @@
+use super::util::{draw_nav, draw_wrapped, get_fonts, ViewError, ViewResult};
+use crate::{
+  keypad::Button,
+  types::{Model, PassphraseScreen, TextLayout},
+};
+use alloc::{format, string::String};
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+
+/// Buttons of the symbol layout with how many presses they take
+const SYMBOL_BUTTONS: [(Button, usize); 8] = [
+  (Button::Two, 4),
+  (Button::Three, 4),
+  (Button::Four, 4),
+  (Button::Five, 4),
+  (Button::Six, 4),
+  (Button::Seven, 5),
+  (Button::Eight, 4),
+  (Button::Nine, 5),
+];
+
+/// The symbols of each button in press order, the space as `spc`.
+fn symbol_legend(buttons: &[(Button, usize)]) -> String {
+  let mut legend = String::new();
+  for (button, presses) in buttons {
+    legend.push_str(button.to_string());
+    legend.push(':');
+    for times_pressed in 1..=*presses {
+      if let Some(key) = button.to_key(times_pressed) {
+        match key.to_symbol() {
+          " " => legend.push_str("spc"),
+          symbol => legend.push_str(symbol),
+        }
+      }
+    }
+    legend.push(' ');
+  }
+  return legend;
+}
+
+pub fn passphrase(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  screen: &PassphraseScreen,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let fonts = get_fonts();
+
+  match screen {
+    PassphraseScreen::Entry => {
+      draw_nav(display, "Passphrase")?;
+
+      let layout = match state.text_layout {
+        TextLayout::Upper => "ABC",
+        TextLayout::Lower => "abc",
+        TextLayout::Symbols => "!?#",
+      };
+      let entry = &state.passphrase_entry;
+      let length = if entry.len() == entry.capacity() {
+        format!("{} characters, the limit", entry.len())
+      } else {
+        format!("{} of {} characters", entry.len(), entry.capacity())
+      };
+      let mut y = draw_wrapped(display, &format!("{}, 1 twice to switch", layout), 50)?;
+      y = draw_wrapped(display, &length, y)?;
+      if state.text_layout == TextLayout::Symbols {
+        for row in SYMBOL_BUTTONS.chunks(4) {
+          y = draw_wrapped(display, &symbol_legend(row), y)?;
+        }
+      }
+
+      Text::with_alignment(
+        &format!("{}_", state.passphrase_entry),
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      Text::with_alignment(
+        "# to continue",
+        display.bounding_box().center() + Point::new(0, 60),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    PassphraseScreen::Confirm => {
+      draw_nav(display, "Confirm Passphrase")?;
+
+      let passphrase = if state.passphrase_entry.is_empty() {
+        "(no passphrase)"
+      } else {
+        state.passphrase_entry.as_str()
+      };
+      Text::with_alignment(
+        passphrase,
+        display.bounding_box().center() + Point::new(0, -25),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      if let Some(fingerprint) = &state.fingerprint {
+        Text::with_alignment(
+          &format!("Fingerprint {}", fingerprint),
+          display.bounding_box().center() + Point::new(0, 5),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+      Text::with_alignment(
+        "# to apply, * to edit",
+        display.bounding_box().center() + Point::new(0, 60),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,135 @@
+use super::util::{
+  draw_menu, draw_nav, draw_numbered_words, draw_recovered, draw_wrapped, get_fonts, ViewError,
+  ViewResult, MARGIN_X, MARGIN_Y,
+};
+use crate::types::{Model, ShamirScreen, SHARE_PAGE_WORDS};
+use alloc::{format, vec::Vec};
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
+use libvulcan::slip39_words_by_prefix;
+
+/// Matching words suggested while typing
+const SUGGESTIONS: usize = 4;
+
+pub fn shamir_backup(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  screen: &ShamirScreen,
+) -> ViewResult {
+  display.clear(Rgb565::WHITE)?;
+
+  let fonts = get_fonts();
+
+  match screen {
+    ShamirScreen::Menu => {
+      let top_left =
+        draw_nav(display, "Shamir Backup")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      let splits = state.shamir_splits();
+      let mut items: Vec<&str> = splits.iter().map(|(label, _, _)| *label).collect();
+      items.push("Recover From Shares");
+      draw_menu(display, &state, &top_left, &items)?;
+
+      // only `Recover From Shares` is listed, say why
+      if splits.is_empty() {
+        let y = display.bounding_box().center().y + 40;
+        draw_wrapped(
+          display,
+          "Only a 128 or 256 bit secret from shares or codex32 can be split, not a mnemonic",
+          y,
+        )?;
+      }
+    }
+    ShamirScreen::Show => {
+      if let Some(share) = state.shares.get(state.share_index) {
+        draw_nav(
+          display,
+          &format!(
+            "Group {} Share {} of {}",
+            share.group_index + 1,
+            share.member_index + 1,
+            state
+              .shares
+              .iter()
+              .filter(|other| other.group_index == share.group_index)
+              .count()
+          ),
+        )?;
+
+        let words = share.words();
+        let first = state.share_page * SHARE_PAGE_WORDS;
+        let page = &words[first..words.len().min(first + SHARE_PAGE_WORDS)];
+        draw_numbered_words(display, page, first + 1)?;
+
+        let needed = if share.group_count > 1 {
+          format!(
+            "{} of group, {} groups needed",
+            share.member_threshold, share.group_threshold
+          )
+        } else {
+          format!("{} shares needed", share.member_threshold)
+        };
+        Text::with_alignment(
+          &needed,
+          display.bounding_box().center() + Point::new(0, 80),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+        Text::with_alignment(
+          "# next, * back",
+          display.bounding_box().center() + Point::new(0, 105),
+          fonts.black,
+          Alignment::Center,
+        )
+        .draw(display)?;
+      }
+    }
+    ShamirScreen::Recover => {
+      draw_nav(
+        display,
+        &format!(
+          "Share {} Word {}",
+          state.shares.len() + 1,
+          state.words.len() + 1
+        ),
+      )?;
+
+      Text::with_alignment(
+        &format!("{}_", state.word_entry),
+        display.bounding_box().center() + Point::new(0, -45),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      if !state.word_entry.is_empty() {
+        let suggestions = slip39_words_by_prefix(&state.word_entry);
+        for (i, word) in suggestions.iter().take(SUGGESTIONS).enumerate() {
+          Text::with_alignment(
+            word,
+            display.bounding_box().center() + Point::new(0, -15 + i as i32 * 20),
+            fonts.black,
+            Alignment::Center,
+          )
+          .draw(display)?;
+        }
+      }
+      Text::with_alignment(
+        "# to add, * to delete",
+        display.bounding_box().center() + Point::new(0, 80),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    ShamirScreen::Recovered => {
+      draw_nav(display, "Shares Recovered")?;
+      draw_recovered(display, state)?;
+    }
+  }
+
+  Ok(())
+}
@@ -1,6 +1,13 @@
-use super::util::{draw_button, draw_nav, ViewError, ViewResult, MARGIN_X, MARGIN_Y};
+use super::util::{
+  draw_button, draw_nav, draw_wrapped, get_fonts, ViewError, ViewResult, MARGIN_X, MARGIN_Y,
+};
 use crate::types::{Model, SignScreen};
-use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use alloc::{format, string::ToString, vec::Vec};
+use embedded_graphics::{
+  pixelcolor::Rgb565,
+  prelude::*,
+  text::{Alignment, Text},
+};
 
 pub fn sign_transaction(
   display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
@@ -9,6 +16,8 @@ pub fn sign_transaction(
 ) -> ViewResult {
   display.clear(Rgb565::WHITE)?;
 
+  let fonts = get_fonts();
+
   match screen {
     SignScreen::Menu => {
       let mut top_left =
@@ -18,12 +27,146 @@ pub fn sign_transaction(
       draw_button(display, &state, &top_left, 1, "Select PSBT File")?;
     }
     SignScreen::FromFile => {
-      let mut top_left =
-        draw_nav(display, "Select PSBT File")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+      draw_nav(display, "Select PSBT File")?;
+
+      Text::with_alignment(
+        "Reading .psb PSBT file",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    SignScreen::Summary => {
+      draw_nav(display, "Transaction")?;
+
+      if let (Some(file), Some(summary)) = (&state.psbt, &state.summary) {
+        let change: u64 = summary.outputs.change.iter().map(|(_, value)| value).sum();
+        let mut y = draw_wrapped(display, &format!("PSBT version {}", file.version()), 50)?;
+        y = draw_wrapped(display, &format!("Spends {} sats", summary.input), y)?;
+        y = draw_wrapped(
+          display,
+          &format!("Sends {} sats", summary.output - change),
+          y,
+        )?;
+        y = draw_wrapped(display, &format!("Change {} sats", change), y)?;
+        y = draw_wrapped(
+          display,
+          &format!("Fee {} sats, {:.1} sat/vB", summary.fee, summary.fee_rate),
+          y,
+        )?;
+        if !summary.outputs.suspicious.is_empty() {
+          draw_wrapped(
+            display,
+            &format!(
+              "{} outputs claim to be change but aren't",
+              summary.outputs.suspicious.len()
+            ),
+            y + 13,
+          )?;
+        }
+      }
+
+      Text::with_alignment(
+        "# to review, * to cancel",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    SignScreen::Output => {
+      if let Some(summary) = &state.summary {
+        let outputs = &summary.outputs;
+        let mut review: Vec<(&str, alloc::string::String, u64)> = Vec::new();
+        for (address, value) in &outputs.spend {
+          review.push(("Send to", address.to_string(), *value));
+        }
+        for (address, value) in &outputs.suspicious {
+          review.push(("NOT your change", address.to_string(), *value));
+        }
+        for (script, value) in &outputs.data {
+          review.push(("Data", script.asm(), *value));
+        }
+        for (script, value) in &outputs.nonstandard {
+          review.push(("Non-standard script", script.asm(), *value));
+        }
+
+        draw_nav(
+          display,
+          &format!("Output {} of {}", state.output_index + 1, review.len()),
+        )?;
+        if let Some((label, text, value)) = review.get(state.output_index) {
+          let mut y = draw_wrapped(display, label, 50)?;
+          y = draw_wrapped(display, text, y)?;
+          draw_wrapped(display, &format!("{} sats", value), y + 13)?;
+        }
+      }
+
+      Text::with_alignment(
+        "# next, * back",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    SignScreen::Confirm => {
+      let blocked = match &state.policy {
+        Some(report) => report.is_blocked(),
+        None => false,
+      };
+      if blocked {
+        draw_nav(display, "Can't Sign")?;
+      } else {
+        draw_nav(display, "Sign Transaction?")?;
+      }
+
+      let mut y = 50;
+      if let Some(summary) = &state.summary {
+        y = draw_wrapped(display, &format!("Fee {} sats", summary.fee), y)?;
+      }
+      if let Some(report) = &state.policy {
+        let blocks = report.blocks.iter().map(|issue| ("blocked", issue));
+        let warnings = report.warnings.iter().map(|issue| ("warning", issue));
+        for (kind, issue) in blocks.chain(warnings) {
+          // keep clear of the hint at the bottom
+          if y > 200 {
+            break;
+          }
+          y = draw_wrapped(display, &format!("{}: {}", kind, issue), y + 5)?;
+        }
+      }
+
+      Text::with_alignment(
+        if blocked {
+          "* to cancel"
+        } else {
+          "# to sign, * to cancel"
+        },
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    SignScreen::Signed => {
+      draw_nav(display, "Transaction Signed")?;
 
-      let width: u32 = display.bounding_box().bottom_right().unwrap().x as u32 - MARGIN_X * 2;
-      // top_left = draw_button(display, &state, &top_left, width, 0, "Scan QR Code")?;
-      // top_left = draw_button(display, &state, &top_left, width, 1, "Select PSBT File")?;
+      Text::with_alignment(
+        "Written to SIGNED.PSB",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      Text::with_alignment(
+        "# to continue",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
     }
     _ => {}
   }
@@ -1,11 +1,17 @@
 use crate::types::{Display, Model};
+use alloc::{format, vec, vec::Vec};
 use embedded_graphics::{
-  mono_font::{ascii::FONT_10X20, MonoTextStyle},
+  mono_font::{
+    ascii::{FONT_10X20, FONT_6X10},
+    MonoTextStyle,
+  },
   pixelcolor::Rgb565,
   prelude::*,
   primitives::{Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, RoundedRectangle},
   text::{Alignment, Text},
 };
+use embedded_qr::{QrCode, QrCodeEcc, QrDrawable, Version};
+use libvulcan::bitcoin::Network;
 use stm32h7xx_hal::Never;
 
 pub type ViewColor = <Display as DrawTarget>::Color;
@@ -27,6 +33,8 @@ pub fn get_fonts() -> Fonts<'static> {
 pub const ORANGE: Rgb565 = Rgb565::new(255, 165, 0);
 pub const MARGIN_X: u32 = 10;
 pub const MARGIN_Y: u32 = 10;
+/// Characters of the small font that fit on a line
+pub const LINE_LEN: usize = 38;
 
 fn rectangle_style(item_n: usize, cur_item: usize) -> PrimitiveStyle<Rgb565> {
   let mut style = PrimitiveStyleBuilder::new()
@@ -76,6 +84,143 @@ pub fn draw_button(
   ));
 }
 
+/// Draws a button per item below `top_left`, scrolled so the selected item
+/// stays on screen.
+pub fn draw_menu(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+  top_left: &Point,
+  items: &[&str],
+) -> ViewResult {
+  let button_height = (30 + MARGIN_Y) as i32;
+  let height = display.bounding_box().size.height as i32;
+  let visible = core::cmp::max((height - top_left.y) / button_height, 1) as usize;
+  let first = (state.selected_item + 1).saturating_sub(visible);
+
+  let mut top_left = *top_left;
+  for (i, item) in items.iter().enumerate().skip(first).take(visible) {
+    top_left = draw_button(display, state, &top_left, i, item)?;
+  }
+
+  Ok(())
+}
+
+/// Draws a mnemonic below the nav bar as two columns of numbered words.
+pub fn draw_words(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  phrase: &str,
+) -> ViewResult {
+  let words: Vec<&str> = phrase.split(' ').collect();
+  return draw_numbered_words(display, &words, 1);
+}
+
+/// Draws the words in two columns, numbered from `first`.
+pub fn draw_numbered_words(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  words: &[&str],
+  first: usize,
+) -> ViewResult {
+  let style = MonoTextStyle::new(&FONT_6X10, Rgb565::BLACK);
+  let rows = (words.len() + 1) / 2;
+  for (i, word) in words.iter().enumerate() {
+    let x = MARGIN_X as i32 + (i / rows) as i32 * 120;
+    let y = 50 + (i % rows) as i32 * 13;
+    Text::new(
+      &format!("{:>2}. {}", first + i, word),
+      Point::new(x, y),
+      style,
+    )
+    .draw(display)?;
+  }
+
+  Ok(())
+}
+
+/// Draws `text` in the small font from `y` down, wrapped at the screen
+/// width. Returns the y of the next line.
+pub fn draw_wrapped(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  text: &str,
+  y: i32,
+) -> Result<i32, ViewError> {
+  let style = MonoTextStyle::new(&FONT_6X10, Rgb565::BLACK);
+  let mut y = y;
+  for line in text.as_bytes().chunks(LINE_LEN) {
+    let line = core::str::from_utf8(line).unwrap_or("");
+    Text::new(line, Point::new(MARGIN_X as i32, y), style).draw(display)?;
+    y += 13;
+  }
+  return Ok(y);
+}
+
+/// Draws the fingerprint of a recovered seed, whether it's this wallet's and
+/// how to use it as the wallet if it isn't.
+pub fn draw_recovered(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  state: &Model,
+) -> ViewResult {
+  let fonts = get_fonts();
+
+  if let Some((fingerprint, own)) = &state.recovered {
+    Text::with_alignment(
+      &format!("Fingerprint {}", fingerprint),
+      display.bounding_box().center() + Point::new(0, -25),
+      fonts.black,
+      Alignment::Center,
+    )
+    .draw(display)?;
+    Text::with_alignment(
+      if *own {
+        "Matches this wallet"
+      } else {
+        "NOT this wallet"
+      },
+      display.bounding_box().center() + Point::new(0, 5),
+      fonts.black,
+      Alignment::Center,
+    )
+    .draw(display)?;
+  }
+  let hint = match &state.recovered {
+    Some((_, false)) => "# to use as wallet, * to discard",
+    _ => "# to continue",
+  };
+  Text::with_alignment(
+    hint,
+    display.bounding_box().center() + Point::new(0, 60),
+    fonts.black,
+    Alignment::Center,
+  )
+  .draw(display)?;
+
+  Ok(())
+}
+
+/// Draws the network in the corner of the nav bar.
+pub fn draw_network(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  network: Network,
+) -> ViewResult {
+  let label = match network {
+    Network::Bitcoin => "MAIN",
+    Network::Testnet => "TEST",
+    Network::Signet => "SIGNET",
+    Network::Regtest => "REGTEST",
+  };
+  Text::with_alignment(
+    label,
+    Point::new(
+      display.bounding_box().size.width as i32 - MARGIN_X as i32,
+      12,
+    ),
+    MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE),
+    Alignment::Right,
+  )
+  .draw(display)?;
+
+  Ok(())
+}
+
 pub fn draw_nav(
   display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
   text: &str,
@@ -109,3 +254,53 @@ pub fn draw_nav(
     nav_bar.bounding_box().bottom_right().unwrap().y,
   ));
 }
+
+/// Draws `text` as the smallest QR code that fits it. Version 15 is the
+/// largest that still gets 3 pixels per module on the screen, longer text
+/// gets a note to use the file on the SD card instead.
+pub fn draw_text_qr(
+  display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
+  text: &str,
+) -> ViewResult {
+  let max_version = Version::new(15);
+  let mut outbuffer = vec![0u8; max_version.buffer_len()];
+  let mut tempbuffer = vec![0u8; max_version.buffer_len()];
+  let qr: QrCode = match QrCode::encode_text(
+    text,
+    &mut tempbuffer,
+    &mut outbuffer,
+    QrCodeEcc::Low,
+    Version::new(1),
+    max_version,
+    None,
+    true,
+  ) {
+    Ok(qr) => qr,
+    Err(_) => {
+      let fonts = get_fonts();
+      Text::with_alignment(
+        "Too large for a QR code",
+        display.bounding_box().center() + Point::new(0, -10),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      Text::with_alignment(
+        "see the SD card",
+        display.bounding_box().center() + Point::new(0, 15),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+      return Ok(());
+    }
+  };
+  core::mem::drop(tempbuffer);
+
+  let mut buff = [false; 240 * 240];
+  let mut drawable: QrDrawable<'_, '_, Rgb565> = QrDrawable::new(&qr, &mut buff);
+  drawable.prepare(240).unwrap();
+  drawable.draw(display)?;
+
+  Ok(())
+}
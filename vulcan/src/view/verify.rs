@@ -1,6 +1,8 @@
-use super::util::get_fonts;
-use crate::types::Model;
-use crate::view::{ViewError, ViewResult};
+use super::util::{
+  draw_menu, draw_nav, draw_wrapped, get_fonts, ViewError, ViewResult, MARGIN_X, MARGIN_Y,
+};
+use crate::types::{Model, VerifyScreen};
+use alloc::{format, string::ToString};
 use embedded_graphics::{
   pixelcolor::Rgb565,
   prelude::*,
@@ -10,18 +12,87 @@ use embedded_graphics::{
 pub fn verify_address(
   display: &mut impl DrawTarget<Color = Rgb565, Error = ViewError>,
   state: &Model,
+  screen: &VerifyScreen,
 ) -> ViewResult {
   display.clear(Rgb565::WHITE)?;
 
   let fonts = get_fonts();
 
-  Text::with_alignment(
-    "verify address",
-    display.bounding_box().center() + Point::new(0, 5),
-    fonts.black,
-    Alignment::Center,
-  )
-  .draw(display)?;
+  match screen {
+    VerifyScreen::Menu => {
+      let top_left =
+        draw_nav(display, "Verify Address")? + Point::new(MARGIN_X as i32, MARGIN_Y as i32);
+
+      let gap_limit = format!("Gap Limit: {}", state.gap_limit);
+      draw_menu(
+        display,
+        &state,
+        &top_left,
+        &["Load From SD Card", &gap_limit],
+      )?;
+    }
+    VerifyScreen::FromFile => {
+      draw_nav(display, "Load From SD Card")?;
+
+      Text::with_alignment(
+        "Reading ADDRESS.TXT",
+        display.bounding_box().center() + Point::new(0, 5),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+    VerifyScreen::Result => {
+      draw_nav(display, "Verify Address")?;
+
+      if let Some(address) = &state.address {
+        draw_wrapped(display, &address.to_string(), 50)?;
+      }
+
+      match &state.address_match {
+        Some(found) => {
+          Text::with_alignment(
+            "Owned at",
+            display.bounding_box().center() + Point::new(0, 5),
+            fonts.black,
+            Alignment::Center,
+          )
+          .draw(display)?;
+          Text::with_alignment(
+            &found.to_string(),
+            display.bounding_box().center() + Point::new(0, 30),
+            fonts.black,
+            Alignment::Center,
+          )
+          .draw(display)?;
+        }
+        None => {
+          Text::with_alignment(
+            "NOT found",
+            display.bounding_box().center() + Point::new(0, 5),
+            fonts.black,
+            Alignment::Center,
+          )
+          .draw(display)?;
+          Text::with_alignment(
+            &format!("in first {} of each chain", state.gap_limit),
+            display.bounding_box().center() + Point::new(0, 30),
+            fonts.black,
+            Alignment::Center,
+          )
+          .draw(display)?;
+        }
+      }
+
+      Text::with_alignment(
+        "# to continue",
+        display.bounding_box().center() + Point::new(0, 110),
+        fonts.black,
+        Alignment::Center,
+      )
+      .draw(display)?;
+    }
+  }
 
   Ok(())
 }